@@ -0,0 +1,190 @@
+use crate::bit_util::build_14_bit_value_from_two_7_bit_values;
+use crate::{controller_numbers, Channel, ShortMessage, StructuredShortMessage, U14, U7};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A patch change detected by [`BankTracker`].
+///
+/// [`BankTracker`]: struct.BankTracker.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PatchChange {
+    pub channel: Channel,
+    /// The currently selected bank or `None` if no Bank Select message has been received yet.
+    pub bank: Option<U14>,
+    pub program: U7,
+}
+
+/// Tracker for detecting patch changes (bank and program) in a stream of short messages.
+///
+/// Bank Select messages (controllers 0 and 32) only update the bank of the corresponding channel.
+/// The patch change itself is emitted on the next Program Change message, because that's when
+/// the patch actually changes. If only the bank MSB (controller 0) has been received, the LSB is
+/// assumed to be 0 and vice versa.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, program_change, u14, u7};
+/// use helgoboss_midi::{BankTracker, PatchChange};
+///
+/// let mut tracker = BankTracker::new();
+/// assert_eq!(tracker.feed(&control_change(0, 0, 1)), None);
+/// assert_eq!(tracker.feed(&control_change(0, 32, 2)), None);
+/// assert_eq!(
+///     tracker.feed(&program_change(0, 5)),
+///     Some(PatchChange {
+///         channel: channel(0),
+///         bank: Some(u14(130)),
+///         program: u7(5),
+///     })
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BankTracker {
+    tracker_by_channel: [TrackerForOneChannel; 16],
+}
+
+impl BankTracker {
+    /// Creates a new tracker.
+    pub fn new() -> BankTracker {
+        Default::default()
+    }
+
+    /// Feeds the tracker a single short message.
+    ///
+    /// Returns the patch change if the message is a Program Change message.
+    pub fn feed(&mut self, msg: &impl ShortMessage) -> Option<PatchChange> {
+        let channel = msg.channel()?;
+        self.tracker_by_channel[usize::from(channel)].feed(msg)
+    }
+
+    /// Returns the currently selected bank of the given channel.
+    pub fn bank(&self, channel: Channel) -> Option<U14> {
+        self.tracker_by_channel[usize::from(channel)].bank()
+    }
+
+    /// Returns the current program of the given channel or `None` if no Program Change message
+    /// has been received on that channel yet.
+    pub fn program(&self, channel: Channel) -> Option<U7> {
+        self.tracker_by_channel[usize::from(channel)].program
+    }
+
+    /// Resets the tracker forgetting all banks and programs.
+    pub fn reset(&mut self) {
+        for t in self.tracker_by_channel.iter_mut() {
+            *t = Default::default();
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct TrackerForOneChannel {
+    bank_msb: Option<U7>,
+    bank_lsb: Option<U7>,
+    program: Option<U7>,
+}
+
+impl TrackerForOneChannel {
+    fn feed(&mut self, msg: &impl ShortMessage) -> Option<PatchChange> {
+        match msg.to_structured() {
+            StructuredShortMessage::ControlChange {
+                controller_number,
+                control_value,
+                ..
+            } => {
+                if controller_number == controller_numbers::BANK_SELECT {
+                    self.bank_msb = Some(control_value);
+                } else if controller_number == controller_numbers::BANK_SELECT_LSB {
+                    self.bank_lsb = Some(control_value);
+                }
+                None
+            }
+            StructuredShortMessage::ProgramChange {
+                channel,
+                program_number,
+            } => {
+                self.program = Some(program_number);
+                Some(PatchChange {
+                    channel,
+                    bank: self.bank(),
+                    program: program_number,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn bank(&self) -> Option<U14> {
+        if self.bank_msb.is_none() && self.bank_lsb.is_none() {
+            return None;
+        }
+        Some(build_14_bit_value_from_two_7_bit_values(
+            self.bank_msb.unwrap_or_default(),
+            self.bank_lsb.unwrap_or_default(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, key_number, u14, u7};
+    use crate::{RawShortMessage, ShortMessageFactory};
+
+    #[test]
+    fn should_emit_patch_change_on_program_change_only() {
+        // Given
+        let mut tracker = BankTracker::new();
+        // When
+        let result_1 = tracker.feed(&RawShortMessage::note_on(ch(3), key_number(60), u7(100)));
+        let result_2 = tracker.feed(&RawShortMessage::control_change(ch(3), cn(0), u7(1)));
+        let result_3 = tracker.feed(&RawShortMessage::note_off(ch(3), key_number(60), u7(0)));
+        let result_4 = tracker.feed(&RawShortMessage::control_change(ch(3), cn(32), u7(2)));
+        let result_5 = tracker.feed(&RawShortMessage::program_change(ch(3), u7(5)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(result_4, None);
+        assert_eq!(
+            result_5,
+            Some(PatchChange {
+                channel: ch(3),
+                bank: Some(u14(130)),
+                program: u7(5)
+            })
+        );
+        assert_eq!(tracker.bank(ch(3)), Some(u14(130)));
+        assert_eq!(tracker.program(ch(3)), Some(u7(5)));
+    }
+
+    #[test]
+    fn should_track_channels_separately() {
+        // Given
+        let mut tracker = BankTracker::new();
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(0), u7(1)));
+        let result_1 = tracker.feed(&RawShortMessage::program_change(ch(1), u7(7)));
+        let result_2 = tracker.feed(&RawShortMessage::program_change(ch(0), u7(8)));
+        // Then
+        assert_eq!(
+            result_1,
+            Some(PatchChange {
+                channel: ch(1),
+                bank: None,
+                program: u7(7)
+            })
+        );
+        assert_eq!(
+            result_2,
+            Some(PatchChange {
+                channel: ch(0),
+                bank: Some(u14(128)),
+                program: u7(8)
+            })
+        );
+    }
+}
@@ -0,0 +1,56 @@
+/// The optional Cargo features this crate was built with.
+///
+/// Returned by [`features`].
+///
+/// [`features`]: fn.features.html
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Features {
+    /// Whether the `serde` feature is enabled, which adds serialization and deserialization
+    /// support to most data structures.
+    pub serde: bool,
+    /// Whether the `serde_repr` feature is enabled, which adds serialization and deserialization
+    /// support to C-like enums (using their numeric representation).
+    pub serde_repr: bool,
+}
+
+/// Returns which optional Cargo features this crate was built with.
+///
+/// Useful for diagnostics, e.g. to find out whether serialization support has been compiled in.
+///
+/// # Example
+///
+/// ```
+/// let features = helgoboss_midi::features();
+/// println!("serde support: {}", features.serde);
+/// ```
+pub const fn features() -> Features {
+    Features {
+        serde: cfg!(feature = "serde"),
+        serde_repr: cfg!(feature = "serde_repr"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn reports_serde_enabled() {
+        // Given
+        // When
+        let result = features();
+        // Then
+        assert!(result.serde);
+    }
+
+    #[test]
+    #[cfg(not(feature = "serde"))]
+    fn reports_serde_disabled() {
+        // Given
+        // When
+        let result = features();
+        // Then
+        assert!(!result.serde);
+    }
+}
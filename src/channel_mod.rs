@@ -1,3 +1,5 @@
+use crate::ShortMessage;
+
 // Basic newtype definition
 
 newtype! {
@@ -38,3 +40,80 @@ impl_try_from_primitive_to_newtype!(u128, Channel);
 impl_try_from_primitive_to_newtype!(i128, Channel);
 impl_try_from_primitive_to_newtype!(usize, Channel);
 impl_try_from_primitive_to_newtype!(isize, Channel);
+
+/// Returns the channel on which the given sample of messages has been sent if all of them share
+/// one channel.
+///
+/// Useful for automatically assigning a monophonic controller to a channel. Messages without
+/// channel (System Common, System Real Time and System Exclusive messages) are ignored. Returns
+/// `None` if the messages span multiple channels or if the sample doesn't contain any channel
+/// message.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, note_on, timing_clock};
+/// use helgoboss_midi::detect_channel;
+///
+/// let msgs = [note_on(3, 60, 100), timing_clock(), control_change(3, 7, 100)];
+/// assert_eq!(detect_channel(&msgs), Some(channel(3)));
+/// let msgs = [note_on(3, 60, 100), note_on(4, 60, 100)];
+/// assert_eq!(detect_channel(&msgs), None);
+/// ```
+pub fn detect_channel(msgs: &[impl ShortMessage]) -> Option<Channel> {
+    let mut channels = msgs.iter().filter_map(|msg| msg.channel());
+    let first = channels.next()?;
+    if channels.all(|channel| channel == first) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, control_change, note_off, note_on, timing_clock};
+
+    #[test]
+    fn all() {
+        // Given
+        // When
+        let channels: Vec<_> = Channel::all().collect();
+        // Then
+        assert_eq!(channels.len(), 16);
+        assert_eq!(channels.first(), Some(&Channel::new(0)));
+        assert_eq!(channels.last(), Some(&Channel::new(15)));
+    }
+
+    #[test]
+    fn detect_channel_single_channel() {
+        // Given
+        let msgs = [
+            timing_clock(),
+            note_on(9, 36, 100),
+            control_change(9, 64, 127),
+            note_off(9, 36, 0),
+            timing_clock(),
+        ];
+        // When
+        let result = detect_channel(&msgs);
+        // Then
+        assert_eq!(result, Some(ch(9)));
+    }
+
+    #[test]
+    fn detect_channel_multiple_channels() {
+        // Given
+        let msgs = [
+            note_on(0, 60, 100),
+            note_on(0, 64, 100),
+            note_on(1, 67, 100),
+        ];
+        // When
+        let result = detect_channel(&msgs);
+        // Then
+        assert_eq!(result, None);
+        assert_eq!(detect_channel(&[timing_clock()]), None);
+    }
+}
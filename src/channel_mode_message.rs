@@ -0,0 +1,165 @@
+use crate::{controller_numbers, Channel, ControllerNumber, ShortMessageFactory, U7};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A Channel Mode message, that is a Control Change message on one of the controllers 120 - 127
+/// with a special meaning.
+///
+/// Can be obtained from any short message via [`ShortMessage::to_channel_mode_message`].
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change};
+/// use helgoboss_midi::{ChannelModeMessage, RawShortMessage, ShortMessage};
+///
+/// let msg = control_change(0, 122, 0);
+/// assert_eq!(
+///     msg.to_channel_mode_message(),
+///     Some(ChannelModeMessage::LocalControl(false))
+/// );
+/// let msg: RawShortMessage = ChannelModeMessage::AllNotesOff.to_short_message(channel(3));
+/// assert_eq!(msg, control_change(3, 123, 0));
+/// ```
+///
+/// [`ShortMessage::to_channel_mode_message`]:
+/// trait.ShortMessage.html#method.to_channel_mode_message
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ChannelModeMessage {
+    AllSoundOff,
+    ResetAllControllers,
+    /// Local Control off (`false`) or on (`true`).
+    LocalControl(bool),
+    AllNotesOff,
+    OmniModeOff,
+    OmniModeOn,
+    /// Mono Mode on with the given number of channels (0 means as many as the receiver has
+    /// voices).
+    MonoModeOn {
+        channel_count: U7,
+    },
+    PolyModeOn,
+}
+
+impl ChannelModeMessage {
+    /// Returns the Channel Mode message encoded by the given Control Change parameters.
+    ///
+    /// Returns `None` if the controller number is not a Channel Mode controller number or if the
+    /// value is invalid for Local Control, which only accepts 0 (off) and 127 (on).
+    pub fn from_control_change(
+        controller_number: ControllerNumber,
+        control_value: U7,
+    ) -> Option<ChannelModeMessage> {
+        use controller_numbers::*;
+        use ChannelModeMessage::*;
+        let msg = match controller_number {
+            ALL_SOUND_OFF => AllSoundOff,
+            RESET_ALL_CONTROLLERS => ResetAllControllers,
+            LOCAL_CONTROL_ON_OFF => match control_value.get() {
+                0 => LocalControl(false),
+                127 => LocalControl(true),
+                _ => return None,
+            },
+            ALL_NOTES_OFF => AllNotesOff,
+            OMNI_MODE_OFF => OmniModeOff,
+            OMNI_MODE_ON => OmniModeOn,
+            MONO_MODE_ON => MonoModeOn {
+                channel_count: control_value,
+            },
+            POLY_MODE_ON => PolyModeOn,
+            _ => return None,
+        };
+        Some(msg)
+    }
+
+    /// Creates the Control Change message which sends this Channel Mode message on the given
+    /// channel.
+    pub fn to_short_message<T: ShortMessageFactory>(&self, channel: Channel) -> T {
+        use controller_numbers::*;
+        use ChannelModeMessage::*;
+        let (controller_number, control_value) = match *self {
+            AllSoundOff => (ALL_SOUND_OFF, U7::MIN),
+            ResetAllControllers => (RESET_ALL_CONTROLLERS, U7::MIN),
+            LocalControl(on) => (LOCAL_CONTROL_ON_OFF, if on { U7::MAX } else { U7::MIN }),
+            AllNotesOff => (ALL_NOTES_OFF, U7::MIN),
+            OmniModeOff => (OMNI_MODE_OFF, U7::MIN),
+            OmniModeOn => (OMNI_MODE_ON, U7::MIN),
+            MonoModeOn { channel_count } => (MONO_MODE_ON, channel_count),
+            PolyModeOn => (POLY_MODE_ON, U7::MIN),
+        };
+        T::control_change(channel, controller_number, control_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u7};
+    use crate::{RawShortMessage, ShortMessage};
+
+    #[test]
+    fn local_control() {
+        // Given
+        let off = RawShortMessage::control_change(ch(0), cn(122), u7(0));
+        let on = RawShortMessage::control_change(ch(0), cn(122), u7(127));
+        let invalid = RawShortMessage::control_change(ch(0), cn(122), u7(64));
+        // When
+        // Then
+        assert_eq!(
+            off.to_channel_mode_message(),
+            Some(ChannelModeMessage::LocalControl(false))
+        );
+        assert_eq!(
+            on.to_channel_mode_message(),
+            Some(ChannelModeMessage::LocalControl(true))
+        );
+        assert_eq!(invalid.to_channel_mode_message(), None);
+    }
+
+    #[test]
+    fn other_messages() {
+        // Given
+        // When
+        // Then
+        assert_eq!(
+            RawShortMessage::control_change(ch(0), cn(7), u7(0)).to_channel_mode_message(),
+            None
+        );
+        assert_eq!(
+            RawShortMessage::program_change(ch(0), u7(122)).to_channel_mode_message(),
+            None
+        );
+        assert_eq!(
+            RawShortMessage::control_change(ch(0), cn(126), u7(4)).to_channel_mode_message(),
+            Some(ChannelModeMessage::MonoModeOn {
+                channel_count: u7(4)
+            })
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        // Given
+        let msgs = [
+            ChannelModeMessage::AllSoundOff,
+            ChannelModeMessage::ResetAllControllers,
+            ChannelModeMessage::LocalControl(false),
+            ChannelModeMessage::LocalControl(true),
+            ChannelModeMessage::AllNotesOff,
+            ChannelModeMessage::OmniModeOff,
+            ChannelModeMessage::OmniModeOn,
+            ChannelModeMessage::MonoModeOn {
+                channel_count: u7(0),
+            },
+            ChannelModeMessage::PolyModeOn,
+        ];
+        // When
+        // Then
+        for msg in msgs.iter() {
+            let short_msg: RawShortMessage = msg.to_short_message(ch(5));
+            assert_eq!(short_msg.channel(), Some(ch(5)));
+            assert_eq!(short_msg.to_channel_mode_message(), Some(*msg));
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    controller_numbers, Channel, ChannelModeMessage, RawShortMessage, ShortMessageFactory, U14, U7,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Configures which messages [`channel_reset_messages_with`] produces for bringing a channel into
+/// a neutral state.
+///
+/// The default configuration resets all controllers, centers pitch bend, sets the volume to 100
+/// (the General MIDI default), centers pan and turns all notes off.
+///
+/// [`channel_reset_messages_with`]: fn.channel_reset_messages_with.html
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChannelResetConfig {
+    /// Whether to send Reset All Controllers (controller 121).
+    pub reset_all_controllers: bool,
+    /// The pitch bend value to send or `None` if pitch bend should be left alone.
+    pub pitch_bend_value: Option<U14>,
+    /// The Channel Volume (controller 7) to send or `None` if it should be left alone.
+    pub volume: Option<U7>,
+    /// The Pan (controller 10) to send or `None` if it should be left alone.
+    pub pan: Option<U7>,
+    /// Whether to send All Notes Off (controller 123).
+    pub all_notes_off: bool,
+}
+
+impl Default for ChannelResetConfig {
+    fn default() -> Self {
+        ChannelResetConfig {
+            reset_all_controllers: true,
+            pitch_bend_value: Some(U14(8192)),
+            volume: Some(U7(100)),
+            pan: Some(U7(64)),
+            all_notes_off: true,
+        }
+    }
+}
+
+/// Returns the messages which bring the given channel into a neutral state, using the default
+/// [`ChannelResetConfig`].
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, pitch_bend_change};
+/// use helgoboss_midi::channel_reset_messages;
+///
+/// assert_eq!(
+///     channel_reset_messages(channel(2)),
+///     vec![
+///         control_change(2, 121, 0),
+///         pitch_bend_change(2, 8192),
+///         control_change(2, 7, 100),
+///         control_change(2, 10, 64),
+///         control_change(2, 123, 0),
+///     ]
+/// );
+/// ```
+///
+/// [`ChannelResetConfig`]: struct.ChannelResetConfig.html
+pub fn channel_reset_messages(channel: Channel) -> Vec<RawShortMessage> {
+    channel_reset_messages_with(channel, &Default::default())
+}
+
+/// Returns the messages which bring the given channel into a neutral state, using the given
+/// configuration.
+///
+/// The messages are ordered as follows: Reset All Controllers, Pitch Bend Change, Channel Volume,
+/// Pan, All Notes Off. Messages which are disabled in the configuration are omitted.
+pub fn channel_reset_messages_with(
+    channel: Channel,
+    config: &ChannelResetConfig,
+) -> Vec<RawShortMessage> {
+    let mut messages = Vec::with_capacity(5);
+    if config.reset_all_controllers {
+        messages.push(ChannelModeMessage::ResetAllControllers.to_short_message(channel));
+    }
+    if let Some(value) = config.pitch_bend_value {
+        messages.push(RawShortMessage::pitch_bend_change(channel, value));
+    }
+    if let Some(value) = config.volume {
+        messages.push(RawShortMessage::control_change(
+            channel,
+            controller_numbers::CHANNEL_VOLUME,
+            value,
+        ));
+    }
+    if let Some(value) = config.pan {
+        messages.push(RawShortMessage::control_change(
+            channel,
+            controller_numbers::PAN,
+            value,
+        ));
+    }
+    if config.all_notes_off {
+        messages.push(ChannelModeMessage::AllNotesOff.to_short_message(channel));
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u14, u7};
+
+    #[test]
+    fn default_messages() {
+        // Given
+        // When
+        let messages = channel_reset_messages(ch(5));
+        // Then
+        assert!(messages.contains(&RawShortMessage::pitch_bend_change(ch(5), u14(8192))));
+        assert!(messages.contains(&RawShortMessage::control_change(ch(5), cn(121), u7(0))));
+        assert_eq!(messages.len(), 5);
+    }
+
+    #[test]
+    fn custom_messages() {
+        // Given
+        let config = ChannelResetConfig {
+            reset_all_controllers: false,
+            pitch_bend_value: None,
+            volume: Some(u7(127)),
+            pan: None,
+            all_notes_off: true,
+        };
+        // When
+        let messages = channel_reset_messages_with(ch(0), &config);
+        // Then
+        assert_eq!(
+            messages,
+            vec![
+                RawShortMessage::control_change(ch(0), cn(7), u7(127)),
+                RawShortMessage::control_change(ch(0), cn(123), u7(0)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+/// Number of Timing Clock messages per quarter note.
+const CLOCKS_PER_QUARTER_NOTE: usize = 24;
+
+/// Estimator for the tempo of incoming Timing Clock messages.
+///
+/// MIDI clock is sent with 24 Timing Clock messages per quarter note. Because the timestamps of
+/// incoming messages are usually jittery, the tempo is derived from the average interval between
+/// the last 24 clocks, that is the duration of the last quarter note. Until that many intervals
+/// are known, no tempo is available.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::ClockTempoEstimator;
+/// use std::time::Duration;
+///
+/// let mut estimator = ClockTempoEstimator::new();
+/// for i in 0..25 {
+///     estimator.feed(Duration::from_micros(i * 20_833));
+/// }
+/// assert!((estimator.bpm().unwrap() - 120.0).abs() < 0.01);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct ClockTempoEstimator {
+    last_timestamp: Option<Duration>,
+    intervals: [Duration; CLOCKS_PER_QUARTER_NOTE],
+    interval_count: usize,
+    next_index: usize,
+}
+
+impl ClockTempoEstimator {
+    /// Creates a new estimator which hasn't received any clocks yet.
+    pub fn new() -> ClockTempoEstimator {
+        Default::default()
+    }
+
+    /// Feeds the estimator the time at which a Timing Clock message has been received.
+    ///
+    /// Timestamps must not decrease.
+    pub fn feed(&mut self, timestamp: Duration) {
+        if let Some(last_timestamp) = self.last_timestamp.replace(timestamp) {
+            self.intervals[self.next_index] = timestamp.saturating_sub(last_timestamp);
+            self.next_index = (self.next_index + 1) % CLOCKS_PER_QUARTER_NOTE;
+            self.interval_count = (self.interval_count + 1).min(CLOCKS_PER_QUARTER_NOTE);
+        }
+    }
+
+    /// Returns the estimated tempo in beats (quarter notes) per minute or `None` if not enough
+    /// clocks have been received yet.
+    pub fn bpm(&self) -> Option<f64> {
+        if self.interval_count < CLOCKS_PER_QUARTER_NOTE {
+            return None;
+        }
+        let quarter_note_duration: Duration = self.intervals.iter().sum();
+        if quarter_note_duration == Duration::ZERO {
+            return None;
+        }
+        Some(60.0 / quarter_note_duration.as_secs_f64())
+    }
+
+    /// Resets the estimator forgetting all received clocks, e.g. when the clock has been stopped.
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_evenly(estimator: &mut ClockTempoEstimator, start: Duration, bpm: f64, count: u32) {
+        let interval = Duration::from_secs_f64(60.0 / bpm / 24.0);
+        for i in 0..count {
+            estimator.feed(start + interval * i);
+        }
+    }
+
+    #[test]
+    fn should_estimate_even_clocks() {
+        // Given
+        let mut estimator = ClockTempoEstimator::new();
+        // When
+        feed_evenly(&mut estimator, Duration::ZERO, 120.0, 24);
+        let result_1 = estimator.bpm();
+        estimator.feed(Duration::from_secs_f64(0.5));
+        let result_2 = estimator.bpm();
+        // Then
+        assert_eq!(result_1, None);
+        assert!((result_2.unwrap() - 120.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn should_smooth_jitter() {
+        // Given
+        let mut estimator = ClockTempoEstimator::new();
+        let interval_micros = 20_833;
+        // When
+        for i in 0..49u64 {
+            let jitter = if i % 2 == 0 { 0 } else { 3000 };
+            estimator.feed(Duration::from_micros(i * interval_micros + jitter));
+        }
+        // Then
+        assert!((estimator.bpm().unwrap() - 120.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn should_follow_tempo_change() {
+        // Given
+        let mut estimator = ClockTempoEstimator::new();
+        // When
+        feed_evenly(&mut estimator, Duration::ZERO, 120.0, 48);
+        feed_evenly(&mut estimator, Duration::from_secs(2), 90.0, 25);
+        let result_1 = estimator.bpm();
+        estimator.reset();
+        let result_2 = estimator.bpm();
+        // Then
+        assert!((result_1.unwrap() - 90.0).abs() < 0.001);
+        assert_eq!(result_2, None);
+    }
+}
@@ -1,7 +1,7 @@
-use crate::{
-    extract_high_7_bit_value_from_14_bit_value, extract_low_7_bit_value_from_14_bit_value, Channel,
-    ControllerNumber, ShortMessageFactory, U14,
+use crate::bit_util::{
+    build_14_bit_value_from_two_7_bit_values, extract_high_7_bit_value_from_14_bit_value,
 };
+use crate::{high_res, Channel, ControllerNumber, ShortMessageFactory, U14, U7};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -72,6 +72,26 @@ impl ControlChange14BitMessage {
         }
     }
 
+    /// Creates a 14-bit Control Change message from the two 7-bit halves of its value, as they
+    /// are transmitted by the 2 short Control Change messages.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `msb_controller_number` can't serve as controller number for
+    /// transmitting the most significant byte of a 14-bit Control Change message.
+    pub fn from_parts(
+        channel: Channel,
+        msb_controller_number: ControllerNumber,
+        value_msb: U7,
+        value_lsb: U7,
+    ) -> ControlChange14BitMessage {
+        ControlChange14BitMessage::new(
+            channel,
+            msb_controller_number,
+            build_14_bit_value_from_two_7_bit_values(value_msb, value_lsb),
+        )
+    }
+
     /// Returns the channel of this message.
     pub fn channel(&self) -> Channel {
         self.channel
@@ -96,19 +116,42 @@ impl ControlChange14BitMessage {
 
     /// Translates this message into 2 short messages, which need to be sent in a row in order to
     /// encode this 14-bit Control Change message.
+    ///
+    /// The first one is sent on the MSB controller, the second one on the corresponding LSB
+    /// controller.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, control_change_14_bit};
+    /// use helgoboss_midi::RawShortMessage;
+    ///
+    /// let msg = control_change_14_bit(0, 2, 1057);
+    /// let short_messages: [RawShortMessage; 2] = msg.to_short_messages();
+    /// assert_eq!(
+    ///     short_messages,
+    ///     [control_change(0, 2, 8), control_change(0, 34, 33)]
+    /// );
+    /// let short_messages: [RawShortMessage; 2] = msg.into();
+    /// assert_eq!(short_messages[1], control_change(0, 34, 33));
+    /// ```
     pub fn to_short_messages<T: ShortMessageFactory>(&self) -> [T; 2] {
-        [
-            T::control_change(
-                self.channel,
-                self.msb_controller_number(),
-                extract_high_7_bit_value_from_14_bit_value(self.value),
-            ),
-            T::control_change(
-                self.channel,
-                self.lsb_controller_number(),
-                extract_low_7_bit_value_from_14_bit_value(self.value),
-            ),
-        ]
+        high_res::control_change_14_bit(self.channel, self.msb_controller_number, self.value)
+    }
+
+    /// Translates this message into a single 7-bit Control Change message on the MSB controller,
+    /// carrying the most significant 7 bits of the value.
+    ///
+    /// Useful for devices which only understand 7-bit Control Change messages. The LSB is
+    /// dropped. Use [`to_short_messages`] if both should be kept.
+    ///
+    /// [`to_short_messages`]: #method.to_short_messages
+    pub fn to_7_bit_short_message<T: ShortMessageFactory>(&self) -> T {
+        T::control_change(
+            self.channel,
+            self.msb_controller_number,
+            extract_high_7_bit_value_from_14_bit_value(self.value),
+        )
     }
 }
 
@@ -122,7 +165,9 @@ impl<T: ShortMessageFactory> From<ControlChange14BitMessage> for [T; 2] {
 mod tests {
     use super::*;
     use crate::test_util::{channel as ch, controller_number as cn, u14, u7};
-    use crate::RawShortMessage;
+    use crate::{RawShortMessage, ShortMessage};
+    #[cfg(feature = "serde")]
+    use serde_json::json;
 
     #[test]
     fn basics() {
@@ -145,4 +190,61 @@ mod tests {
         let short_msgs_2: [RawShortMessage; 2] = msg.into();
         assert_eq!(short_msgs_2, short_msgs);
     }
+
+    #[test]
+    fn to_7_bit_short_message() {
+        // Given
+        let msg = ControlChange14BitMessage::new(ch(5), cn(2), u14(1057));
+        // When
+        let short_msg: RawShortMessage = msg.to_7_bit_short_message();
+        // Then
+        assert_eq!(
+            short_msg,
+            RawShortMessage::control_change(ch(5), cn(2), u7(8))
+        );
+        assert_eq!(short_msg.data_byte_2(), msg.value().split().0);
+    }
+
+    #[test]
+    fn from_parts() {
+        // Given
+        let msg = ControlChange14BitMessage::from_parts(ch(5), cn(2), u7(8), u7(33));
+        // When
+        // Then
+        assert_eq!(msg.channel(), ch(5));
+        assert_eq!(msg.msb_controller_number(), cn(2));
+        assert_eq!(
+            msg.value(),
+            build_14_bit_value_from_two_7_bit_values(u7(8), u7(33))
+        );
+        assert_eq!(msg, ControlChange14BitMessage::new(ch(5), cn(2), u14(1057)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_parts_panics_on_invalid_msb_controller_number() {
+        ControlChange14BitMessage::from_parts(ch(5), cn(34), u7(8), u7(33));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_and_back() {
+        // Given
+        let msg = ControlChange14BitMessage::new(ch(5), cn(2), u14(1057));
+        // When
+        let j = serde_json::to_value(msg).unwrap();
+        let deserialized: ControlChange14BitMessage = serde_json::from_value(j.clone()).unwrap();
+        // Then
+        assert_eq!(
+            j,
+            json! {
+                {
+                    "channel": 5,
+                    "msb_controller_number": 2,
+                    "value": 1057
+                }
+            }
+        );
+        assert_eq!(deserialized, msg);
+    }
 }
@@ -1,7 +1,9 @@
 use crate::{
-    build_14_bit_value_from_two_7_bit_values, Channel, ControlChange14BitMessage, ControllerNumber,
-    ShortMessage, StructuredShortMessage, U7,
+    is_sequence_boundary, Channel, ControlChange14BitMessage, ControllerNumber, ShortMessage,
+    StructuredShortMessage, U7,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Scanner for detecting 14-bit Control Change messages in a stream of short MIDI messages.
 ///
@@ -27,8 +29,18 @@ use crate::{
 /// );
 /// ```
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ControlChange14BitMessageScanner {
     scanner_by_channel: [ScannerForOneChannel; 16],
+    boundary_on_system_messages: bool,
+    options: ScanOptions,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct ScanOptions {
+    emit_on_msb_change: bool,
+    accept_lsb_first: bool,
 }
 
 impl ControlChange14BitMessageScanner {
@@ -41,8 +53,97 @@ impl ControlChange14BitMessageScanner {
     ///
     /// Returns the 14-bit Control Change message if one has been detected.  
     pub fn feed(&mut self, msg: &impl ShortMessage) -> Option<ControlChange14BitMessage> {
-        let channel = msg.channel()?;
-        self.scanner_by_channel[usize::from(channel)].feed(msg)
+        let channel = match msg.channel() {
+            Some(c) => c,
+            None => {
+                if self.boundary_on_system_messages && is_sequence_boundary(msg) {
+                    self.reset();
+                }
+                return None;
+            }
+        };
+        self.scanner_by_channel[usize::from(channel)].feed(msg, self.options)
+    }
+
+    /// Sets whether System Exclusive, System Common and System Reset messages should end any
+    /// incomplete 14-bit Control Change message.
+    ///
+    /// If enabled, such a message discards all intermediate scanning progress, just like
+    /// [`reset`]. A sender which interrupts a sequence with one of these messages has most likely
+    /// abandoned it. Other System Real Time messages such as Timing Clock never have this effect
+    /// because they may be interleaved with anything.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`reset`]: #method.reset
+    pub fn set_boundary_on_system_messages(&mut self, enabled: bool) {
+        self.boundary_on_system_messages = enabled;
+    }
+
+    /// Sets whether an MSB for a different controller should emit the pending MSB of the previous
+    /// controller if its LSB hasn't arrived yet.
+    ///
+    /// By default, the pending MSB is silently replaced. If enabled, it is emitted instead, with
+    /// an LSB of 0. Such a message is the 14-bit equivalent of the original 7-bit value, which can
+    /// be obtained again via [`ControlChange14BitMessage::to_7_bit_short_message`]. This is useful
+    /// if the sender mixes 7-bit and 14-bit usage of the same controllers.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`ControlChange14BitMessage::to_7_bit_short_message`]:
+    /// struct.ControlChange14BitMessage.html#method.to_7_bit_short_message
+    pub fn set_emit_on_msb_change(&mut self, enabled: bool) {
+        self.options.emit_on_msb_change = enabled;
+    }
+
+    /// Sets whether 14-bit Control Change messages whose LSB is sent before the MSB should be
+    /// detected as well.
+    ///
+    /// Some devices send the LSB (controller 32 - 63) before the MSB (controller 0 - 31). If
+    /// enabled, an LSB which doesn't complete a pending MSB is remembered and the message is
+    /// emitted as soon as the MSB of the corresponding controller arrives next.
+    ///
+    /// An LSB can either complete a pending MSB or start an LSB-first pair. The scanner uses the
+    /// following heuristic: If an MSB of the corresponding controller is pending, that is, it
+    /// hasn't been completed by an LSB yet, the LSB completes it. Otherwise it's considered the
+    /// start of an LSB-first pair. A remembered LSB is discarded as soon as any MSB arrives. As a
+    /// consequence, a lone LSB for refining the value of an already completed message doesn't
+    /// result in a message anymore. If the MSB completes an LSB-first pair, this completed message
+    /// takes precedence over the message emitted because of [`set_emit_on_msb_change`].
+    ///
+    /// Disabled by default.
+    ///
+    /// [`set_emit_on_msb_change`]: #method.set_emit_on_msb_change
+    pub fn set_accept_lsb_first(&mut self, enabled: bool) {
+        self.options.accept_lsb_first = enabled;
+    }
+
+    /// Emits a pending MSB which hasn't been completed by an LSB as 14-bit message with an LSB of
+    /// 0 and clears the scanning progress of its channel.
+    ///
+    /// Some devices send only the MSB (controller 0 - 31), which effectively means a 7-bit value.
+    /// Because the scanner waits for the LSB, those messages would never be emitted. This method
+    /// is meant to be called at the end of a buffer or after a timeout in order to not lose them.
+    ///
+    /// Returns at most one message per call, starting with the lowest channel. Call it repeatedly
+    /// until it returns `None` in order to flush all channels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, control_change_14_bit};
+    /// use helgoboss_midi::ControlChange14BitMessageScanner;
+    ///
+    /// let mut scanner = ControlChange14BitMessageScanner::new();
+    /// assert_eq!(scanner.feed(&control_change(0, 2, 8)), None);
+    /// assert_eq!(scanner.flush(), Some(control_change_14_bit(0, 2, 1024)));
+    /// assert_eq!(scanner.flush(), None);
+    /// ```
+    pub fn flush(&mut self) -> Option<ControlChange14BitMessage> {
+        self.scanner_by_channel
+            .iter_mut()
+            .enumerate()
+            .find_map(|(i, p)| p.flush(Channel(i as u8)))
     }
 
     /// Resets the scanner discarding all intermediate scanning progress.
@@ -51,24 +152,69 @@ impl ControlChange14BitMessageScanner {
             p.reset();
         }
     }
+
+    /// Resets the given channel only, discarding its intermediate scanning progress.
+    ///
+    /// Useful if the device on one channel has been reset. The other channels are not affected.
+    pub fn reset_channel(&mut self, channel: Channel) {
+        self.scanner_by_channel[usize::from(channel)].reset();
+    }
+}
+
+/// Runs a new [`ControlChange14BitMessageScanner`] across the given short messages and collects
+/// all detected 14-bit Control Change messages.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{control_change, control_change_14_bit, note_on};
+/// use helgoboss_midi::scan_14_bit_ccs;
+///
+/// let msgs = [
+///     control_change(0, 2, 8),
+///     note_on(0, 60, 100),
+///     control_change(0, 34, 33),
+/// ];
+/// assert_eq!(
+///     scan_14_bit_ccs(&msgs),
+///     vec![control_change_14_bit(0, 2, 1057)]
+/// );
+/// ```
+///
+/// [`ControlChange14BitMessageScanner`]: struct.ControlChange14BitMessageScanner.html
+pub fn scan_14_bit_ccs(msgs: &[impl ShortMessage]) -> Vec<ControlChange14BitMessage> {
+    let mut scanner = ControlChange14BitMessageScanner::new();
+    msgs.iter().filter_map(|msg| scanner.feed(msg)).collect()
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct ScannerForOneChannel {
     msb_controller_number: Option<ControllerNumber>,
     value_msb: Option<U7>,
+    msb_pending: bool,
+    lsb_controller_number: Option<ControllerNumber>,
+    value_lsb: Option<U7>,
 }
 
 impl ScannerForOneChannel {
-    fn feed(&mut self, msg: &impl ShortMessage) -> Option<ControlChange14BitMessage> {
+    fn feed(
+        &mut self,
+        msg: &impl ShortMessage,
+        options: ScanOptions,
+    ) -> Option<ControlChange14BitMessage> {
         match msg.to_structured() {
             StructuredShortMessage::ControlChange {
                 controller_number,
                 channel,
                 control_value,
             } => match controller_number.get() {
-                (0..=31) => self.process_value_msb(controller_number, control_value),
-                (32..=63) => self.process_value_lsb(channel, controller_number, control_value),
+                (0..=31) => {
+                    self.process_value_msb(channel, controller_number, control_value, options)
+                }
+                (32..=63) => {
+                    self.process_value_lsb(channel, controller_number, control_value, options)
+                }
                 _ => None,
             },
             _ => None,
@@ -78,16 +224,65 @@ impl ScannerForOneChannel {
     fn reset(&mut self) {
         self.msb_controller_number = None;
         self.value_msb = None;
+        self.msb_pending = false;
+        self.lsb_controller_number = None;
+        self.value_lsb = None;
+    }
+
+    fn flush(&mut self, channel: Channel) -> Option<ControlChange14BitMessage> {
+        if !self.msb_pending {
+            return None;
+        }
+        let msg = ControlChange14BitMessage::from_parts(
+            channel,
+            self.msb_controller_number?,
+            self.value_msb?,
+            U7::MIN,
+        );
+        self.reset();
+        Some(msg)
     }
 
     fn process_value_msb(
         &mut self,
+        channel: Channel,
         msb_controller_number: ControllerNumber,
         value_msb: U7,
+        options: ScanOptions,
     ) -> Option<ControlChange14BitMessage> {
+        let abandoned_msg = match (self.msb_controller_number, self.value_msb) {
+            (Some(previous_controller_number), Some(previous_value_msb))
+                if options.emit_on_msb_change
+                    && self.msb_pending
+                    && previous_controller_number != msb_controller_number =>
+            {
+                Some(ControlChange14BitMessage::from_parts(
+                    channel,
+                    previous_controller_number,
+                    previous_value_msb,
+                    U7::MIN,
+                ))
+            }
+            _ => None,
+        };
+        let lsb_first_msg = match (self.lsb_controller_number.take(), self.value_lsb.take()) {
+            (Some(lsb_controller_number), Some(value_lsb))
+                if msb_controller_number.corresponding_14_bit_lsb_controller_number()
+                    == Some(lsb_controller_number) =>
+            {
+                Some(ControlChange14BitMessage::from_parts(
+                    channel,
+                    msb_controller_number,
+                    value_msb,
+                    value_lsb,
+                ))
+            }
+            _ => None,
+        };
         self.msb_controller_number = Some(msb_controller_number);
         self.value_msb = Some(value_msb);
-        None
+        self.msb_pending = lsb_first_msg.is_none();
+        lsb_first_msg.or(abandoned_msg)
     }
 
     fn process_value_lsb(
@@ -95,21 +290,31 @@ impl ScannerForOneChannel {
         channel: Channel,
         lsb_controller_number: ControllerNumber,
         value_lsb: U7,
+        options: ScanOptions,
     ) -> Option<ControlChange14BitMessage> {
-        let msb_controller_number = self.msb_controller_number?;
-        let value_msb = self.value_msb?;
-        if lsb_controller_number
-            != msb_controller_number
-                .corresponding_14_bit_lsb_controller_number()
-                .expect("impossible")
-        {
+        let matching_msb = match (self.msb_controller_number, self.value_msb) {
+            (Some(msb_controller_number), Some(value_msb))
+                if msb_controller_number
+                    .corresponding_14_bit_lsb_controller_number()
+                    .expect("impossible")
+                    == lsb_controller_number =>
+            {
+                Some((msb_controller_number, value_msb))
+            }
+            _ => None,
+        };
+        if options.accept_lsb_first && !(self.msb_pending && matching_msb.is_some()) {
+            self.lsb_controller_number = Some(lsb_controller_number);
+            self.value_lsb = Some(value_lsb);
             return None;
         }
-        let value = build_14_bit_value_from_two_7_bit_values(value_msb, value_lsb);
-        Some(ControlChange14BitMessage::new(
+        let (msb_controller_number, value_msb) = matching_msb?;
+        self.msb_pending = false;
+        Some(ControlChange14BitMessage::from_parts(
             channel,
             msb_controller_number,
-            value,
+            value_msb,
+            value_lsb,
         ))
     }
 }
@@ -217,4 +422,220 @@ mod tests {
         assert_eq!(result_4.lsb_controller_number(), cn(35));
         assert_eq!(result_4.value(), u14(1058));
     }
+
+    #[test]
+    fn should_emit_pending_msb_on_msb_change_if_enabled() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        scanner.set_emit_on_msb_change(true);
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(8)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(3), u7(8)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(35), u7(34)));
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(9)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(ControlChange14BitMessage::new(ch(5), cn(2), u14(1024)))
+        );
+        assert_eq!(
+            result_2
+                .unwrap()
+                .to_7_bit_short_message::<RawShortMessage>(),
+            RawShortMessage::control_change(ch(5), cn(2), u7(8))
+        );
+        assert_eq!(
+            result_3,
+            Some(ControlChange14BitMessage::new(ch(5), cn(3), u14(1058)))
+        );
+        // Completed message is not emitted again
+        assert_eq!(result_4, None);
+    }
+
+    #[test]
+    fn should_not_emit_pending_msb_on_same_controller() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        scanner.set_emit_on_msb_change(true);
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(8)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(9)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+    }
+
+    #[test]
+    fn scan_14_bit_ccs_collects_all_messages() {
+        // Given
+        let msgs = [
+            RawShortMessage::control_change(ch(5), cn(2), u7(8)),
+            RawShortMessage::control_change(ch(5), cn(34), u7(33)),
+            RawShortMessage::note_on(ch(5), key_number(60), u7(100)),
+            RawShortMessage::control_change(ch(6), cn(3), u7(8)),
+            RawShortMessage::control_change(ch(6), cn(35), u7(34)),
+        ];
+        // When
+        let result = scan_14_bit_ccs(&msgs);
+        // Then
+        assert_eq!(
+            result,
+            vec![
+                ControlChange14BitMessage::new(ch(5), cn(2), u14(1057)),
+                ControlChange14BitMessage::new(ch(6), cn(3), u14(1058)),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_detect_lsb_first_if_enabled() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        scanner.set_accept_lsb_first(true);
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(34), u7(33)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(8)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(35), u7(34)));
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(9)));
+        let result_5 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(34), u7(1)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(ControlChange14BitMessage::new(ch(5), cn(2), u14(1057)))
+        );
+        assert_eq!(result_3, None);
+        // Stashed LSB doesn't match, so MSB is pending now
+        assert_eq!(result_4, None);
+        // Pending MSB is completed MSB-first
+        assert_eq!(
+            result_5,
+            Some(ControlChange14BitMessage::new(ch(5), cn(2), u14(1153)))
+        );
+    }
+
+    #[test]
+    fn lsb_first_yields_same_value_as_msb_first() {
+        // Given
+        let mut lsb_first_scanner = ControlChange14BitMessageScanner::new();
+        lsb_first_scanner.set_accept_lsb_first(true);
+        let mut msb_first_scanner = lsb_first_scanner;
+        let lsb = RawShortMessage::control_change(ch(0), cn(34), u7(33));
+        let msb = RawShortMessage::control_change(ch(0), cn(2), u7(8));
+        // When
+        lsb_first_scanner.feed(&lsb);
+        let lsb_first_result = lsb_first_scanner.feed(&msb);
+        msb_first_scanner.feed(&msb);
+        let msb_first_result = msb_first_scanner.feed(&lsb);
+        // Then
+        assert_eq!(
+            lsb_first_result,
+            Some(ControlChange14BitMessage::new(ch(0), cn(2), u14(1057)))
+        );
+        assert_eq!(lsb_first_result, msb_first_result);
+    }
+
+    #[test]
+    fn factory_pair_should_be_detected() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        let msgs = RawShortMessage::control_change_14_bit(ch(4), cn(11), u14(9999));
+        // When
+        let results: Vec<_> = msgs.iter().filter_map(|m| scanner.feed(m)).collect();
+        // Then
+        assert_eq!(
+            results,
+            vec![ControlChange14BitMessage::new(ch(4), cn(11), u14(9999))]
+        );
+    }
+
+    #[test]
+    fn flush_pending_msbs() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(2), u7(8)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(34), u7(33)));
+        scanner.feed(&RawShortMessage::control_change(ch(3), cn(7), u7(100)));
+        scanner.feed(&RawShortMessage::control_change(ch(1), cn(1), u7(127)));
+        // When
+        let result_1 = scanner.flush();
+        let result_2 = scanner.flush();
+        let result_3 = scanner.flush();
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(33), u7(5)));
+        // Then
+        assert_eq!(
+            result_1,
+            Some(ControlChange14BitMessage::new(ch(1), cn(1), u14(16256)))
+        );
+        assert_eq!(
+            result_2,
+            Some(ControlChange14BitMessage::new(ch(3), cn(7), u14(12800)))
+        );
+        assert_eq!(result_3, None);
+        assert_eq!(result_4, None);
+    }
+
+    #[test]
+    fn reset_channel_should_leave_other_channels_intact() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(2), u7(8)));
+        scanner.feed(&RawShortMessage::control_change(ch(2), cn(2), u7(8)));
+        // When
+        scanner.reset_channel(ch(0));
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(34), u7(33)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(2), cn(34), u7(33)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(ControlChange14BitMessage::new(ch(2), cn(2), u14(1057)))
+        );
+    }
+
+    #[test]
+    fn should_not_detect_lsb_first_by_default() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(34), u7(33)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(8)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+    }
+
+    #[test]
+    fn should_abandon_sequence_on_system_reset_if_enabled() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        scanner.set_boundary_on_system_messages(true);
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(8)));
+        let result_2 = scanner.feed(&RawShortMessage::system_reset());
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(5), cn(34), u7(33)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_continue_scanning_after_checkpoint_restore() {
+        // Given
+        let mut scanner = ControlChange14BitMessageScanner::new();
+        scanner.feed(&RawShortMessage::control_change(ch(5), cn(2), u7(8)));
+        // When
+        let checkpoint = serde_json::to_string(&scanner).unwrap();
+        let mut restored: ControlChange14BitMessageScanner =
+            serde_json::from_str(&checkpoint).unwrap();
+        let result = restored.feed(&RawShortMessage::control_change(ch(5), cn(34), u7(33)));
+        // Then
+        assert_eq!(
+            result,
+            Some(ControlChange14BitMessage::new(ch(5), cn(2), u14(1057)))
+        );
+    }
 }
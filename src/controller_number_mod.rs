@@ -1,3 +1,6 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 // Basic newtype definition
 newtype! {
     #[doc = r"A controller number (0 - 127) of a MIDI Control Change message."]
@@ -57,15 +60,221 @@ impl ControllerNumber {
 
     /// Returns whether this controller number is intended to be used to send part of a (N)RPN
     /// message.
+    ///
+    /// This doesn't include Data Increment and Data Decrement. Use
+    /// [`is_parameter_number_controller`] if they should be included.
+    ///
+    /// [`is_parameter_number_controller`]: fn.is_parameter_number_controller.html
     pub fn is_parameter_number_message_controller_number(&self) -> bool {
         matches!(self.0, 98 | 99 | 100 | 101 | 38 | 6)
     }
 
     /// Returns whether this controller number is intended to be used to send Channel Mode
     /// messages.
+    ///
+    /// For historical reasons, this doesn't include All Sound Off (120). Use
+    /// [`is_channel_mode_message`] if it should be included.
+    ///
+    /// [`is_channel_mode_message`]: #method.is_channel_mode_message
     pub fn is_channel_mode_message_controller_number(&self) -> bool {
         *self >= controller_numbers::RESET_ALL_CONTROLLERS
     }
+
+    /// Returns the role of this controller number.
+    ///
+    /// Some controller numbers fall into more than one category. (N)RPN controllers take
+    /// precedence, so Data Entry MSB (6) and Data Entry LSB (38) are classified as
+    /// [`ParameterNumberMessage`] although they are also within the 14-bit ranges. Data Increment
+    /// (96) and Data Decrement (97) are classified as [`ParameterNumberMessage`] as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::controller_number;
+    /// use helgoboss_midi::ControllerNumberKind;
+    ///
+    /// assert_eq!(controller_number(7).kind(), ControllerNumberKind::FourteenBitMsb);
+    /// assert_eq!(controller_number(64).kind(), ControllerNumberKind::Switch);
+    /// assert_eq!(controller_number(6).kind(), ControllerNumberKind::ParameterNumberMessage);
+    /// ```
+    ///
+    /// [`ParameterNumberMessage`]: enum.ControllerNumberKind.html#variant.ParameterNumberMessage
+    pub fn kind(&self) -> ControllerNumberKind {
+        use ControllerNumberKind::*;
+        if is_parameter_number_controller(*self) {
+            ParameterNumberMessage
+        } else if self.is_channel_mode_message() {
+            ChannelModeMessage
+        } else if self.is_14_bit_msb() {
+            FourteenBitMsb
+        } else if self.is_14_bit_lsb() {
+            FourteenBitLsb
+        } else if self.is_switch() {
+            Switch
+        } else {
+            Other
+        }
+    }
+
+    /// Returns whether this controller number is used to send the most significant byte of a
+    /// 14-bit Control Change message (0 - 31).
+    pub fn is_14_bit_msb(&self) -> bool {
+        self.0 < 32
+    }
+
+    /// Returns whether this controller number is used to send the least significant byte of a
+    /// 14-bit Control Change message (32 - 63).
+    pub fn is_14_bit_lsb(&self) -> bool {
+        (32..64).contains(&self.0)
+    }
+
+    /// Returns whether this controller number is intended to be used as on/off switch (64 - 69).
+    pub fn is_switch(&self) -> bool {
+        (64..=69).contains(&self.0)
+    }
+
+    /// Same as [`is_parameter_number_message_controller_number`]. This shorter name is the
+    /// preferred one.
+    ///
+    /// [`is_parameter_number_message_controller_number`]:
+    /// #method.is_parameter_number_message_controller_number
+    pub fn is_parameter_number_message(&self) -> bool {
+        self.is_parameter_number_message_controller_number()
+    }
+
+    /// Returns whether this controller number is used to send Channel Mode messages (120 - 127).
+    ///
+    /// In contrast to [`is_channel_mode_message_controller_number`], this includes All Sound Off
+    /// (120), as defined by the MIDI specification.
+    ///
+    /// [`is_channel_mode_message_controller_number`]:
+    /// #method.is_channel_mode_message_controller_number
+    pub fn is_channel_mode_message(&self) -> bool {
+        *self >= controller_numbers::ALL_SOUND_OFF
+    }
+
+    /// Returns the name of this controller as defined by the MIDI specification, e.g. for
+    /// displaying it in a MIDI monitor.
+    ///
+    /// Returns `None` for undefined and general-purpose controller numbers because they don't have
+    /// a standard meaning.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::controller_number;
+    ///
+    /// assert_eq!(controller_number(1).standard_name(), Some("Modulation Wheel"));
+    /// assert_eq!(controller_number(3).standard_name(), None);
+    /// ```
+    pub fn standard_name(&self) -> Option<&'static str> {
+        let name = match self.0 {
+            0 => "Bank Select",
+            1 => "Modulation Wheel",
+            2 => "Breath Controller",
+            4 => "Foot Controller",
+            5 => "Portamento Time",
+            6 => "Data Entry",
+            7 => "Channel Volume",
+            8 => "Balance",
+            10 => "Pan",
+            11 => "Expression Controller",
+            12 => "Effect Control 1",
+            13 => "Effect Control 2",
+            32 => "Bank Select (LSB)",
+            33 => "Modulation Wheel (LSB)",
+            34 => "Breath Controller (LSB)",
+            36 => "Foot Controller (LSB)",
+            37 => "Portamento Time (LSB)",
+            38 => "Data Entry (LSB)",
+            39 => "Channel Volume (LSB)",
+            40 => "Balance (LSB)",
+            42 => "Pan (LSB)",
+            43 => "Expression Controller (LSB)",
+            44 => "Effect Control 1 (LSB)",
+            45 => "Effect Control 2 (LSB)",
+            64 => "Sustain Pedal",
+            65 => "Portamento On/Off",
+            66 => "Sostenuto",
+            67 => "Soft Pedal",
+            68 => "Legato Footswitch",
+            69 => "Hold 2",
+            70 => "Sound Variation",
+            71 => "Timbre/Harmonic Intensity",
+            72 => "Release Time",
+            73 => "Attack Time",
+            74 => "Brightness",
+            75 => "Decay Time",
+            76 => "Vibrato Rate",
+            77 => "Vibrato Depth",
+            78 => "Vibrato Delay",
+            79 => "Sound Controller 10",
+            84 => "Portamento Control",
+            88 => "High Resolution Velocity Prefix",
+            91 => "Reverb Send Level",
+            92 => "Tremolo Depth",
+            93 => "Chorus Send Level",
+            94 => "Celeste Depth",
+            95 => "Phaser Depth",
+            96 => "Data Increment",
+            97 => "Data Decrement",
+            98 => "NRPN (LSB)",
+            99 => "NRPN (MSB)",
+            100 => "RPN (LSB)",
+            101 => "RPN (MSB)",
+            120 => "All Sound Off",
+            121 => "Reset All Controllers",
+            122 => "Local Control",
+            123 => "All Notes Off",
+            124 => "Omni Mode Off",
+            125 => "Omni Mode On",
+            126 => "Mono Mode On",
+            127 => "Poly Mode On",
+            _ => return None,
+        };
+        Some(name)
+    }
+}
+
+/// The role of a controller number, as returned by [`ControllerNumber::kind`].
+///
+/// [`ControllerNumber::kind`]: struct.ControllerNumber.html#method.kind
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ControllerNumberKind {
+    /// Most significant byte of a 14-bit Control Change message (0 - 31, except 6).
+    FourteenBitMsb,
+    /// Least significant byte of a 14-bit Control Change message (32 - 63, except 38).
+    FourteenBitLsb,
+    /// On/off switch such as Damper Pedal (64 - 69).
+    Switch,
+    /// Part of a (N)RPN message (6, 38, 96 - 101).
+    ParameterNumberMessage,
+    /// Channel Mode message (120 - 127).
+    ChannelModeMessage,
+    /// Any other controller number.
+    Other,
+}
+
+/// Returns whether the given controller number is intended to be used to send part of a (N)RPN
+/// message, that is 6, 38, 96, 97, 98, 99, 100 or 101.
+///
+/// In contrast to [`ControllerNumber::is_parameter_number_message_controller_number`], this
+/// includes Data Increment (96) and Data Decrement (97).
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::{controller_numbers, is_parameter_number_controller};
+///
+/// assert!(is_parameter_number_controller(controller_numbers::DATA_ENTRY_MSB));
+/// assert!(!is_parameter_number_controller(controller_numbers::CHANNEL_VOLUME));
+/// ```
+///
+/// [`ControllerNumber::is_parameter_number_message_controller_number`]:
+/// struct.ControllerNumber.html#method.is_parameter_number_message_controller_number
+pub fn is_parameter_number_controller(cn: ControllerNumber) -> bool {
+    matches!(cn.0, 98 | 99 | 100 | 101 | 96 | 97 | 38 | 6)
 }
 
 /// Contains predefined controller numbers.
@@ -159,3 +368,109 @@ pub mod controller_numbers {
     pub const MONO_MODE_ON: ControllerNumber = ControllerNumber(0x7E);
     pub const POLY_MODE_ON: ControllerNumber = ControllerNumber(0x7F);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::controller_number as cn;
+
+    #[test]
+    fn all() {
+        // Given
+        // When
+        // Then
+        assert_eq!(ControllerNumber::all().count(), 128);
+        assert_eq!(ControllerNumber::all().last(), Some(cn(127)));
+    }
+
+    #[test]
+    fn kind_at_boundaries() {
+        // Given
+        use ControllerNumberKind::*;
+        let expectations = [
+            (0, FourteenBitMsb),
+            (5, FourteenBitMsb),
+            (6, ParameterNumberMessage),
+            (31, FourteenBitMsb),
+            (32, FourteenBitLsb),
+            (38, ParameterNumberMessage),
+            (63, FourteenBitLsb),
+            (64, Switch),
+            (69, Switch),
+            (70, Other),
+            (96, ParameterNumberMessage),
+            (101, ParameterNumberMessage),
+            (119, Other),
+            (120, ChannelModeMessage),
+            (127, ChannelModeMessage),
+        ];
+        // When
+        // Then
+        for (n, kind) in expectations.iter() {
+            assert_eq!(cn(*n).kind(), *kind, "{}", n);
+        }
+    }
+
+    #[test]
+    fn predicates_at_boundaries() {
+        // Given
+        // When
+        // Then
+        assert!(cn(31).is_14_bit_msb());
+        assert!(!cn(32).is_14_bit_msb());
+        assert!(cn(32).is_14_bit_lsb());
+        assert!(cn(63).is_14_bit_lsb());
+        assert!(!cn(64).is_14_bit_lsb());
+        assert!(cn(64).is_switch());
+        assert!(!cn(119).is_channel_mode_message());
+        assert!(cn(120).is_channel_mode_message());
+        assert!(!cn(120).is_channel_mode_message_controller_number());
+        assert!(cn(121).is_channel_mode_message_controller_number());
+        assert!(cn(6).is_14_bit_msb());
+        assert!(cn(6).is_parameter_number_message());
+    }
+
+    #[test]
+    fn standard_name() {
+        // Given
+        // When
+        // Then
+        assert_eq!(cn(0).standard_name(), Some("Bank Select"));
+        assert_eq!(cn(7).standard_name(), Some("Channel Volume"));
+        assert_eq!(cn(64).standard_name(), Some("Sustain Pedal"));
+        assert_eq!(cn(16).standard_name(), None);
+        assert_eq!(cn(102).standard_name(), None);
+    }
+
+    #[test]
+    fn is_parameter_number_controller_positive() {
+        // Given
+        let numbers = [6, 38, 96, 97, 98, 99, 100, 101];
+        // When
+        // Then
+        for n in numbers.iter() {
+            assert!(is_parameter_number_controller(cn(*n)), "{}", n);
+        }
+    }
+
+    #[test]
+    fn is_parameter_number_message_controller_number_excludes_increment() {
+        // Given
+        // When
+        // Then
+        assert!(cn(6).is_parameter_number_message_controller_number());
+        assert!(!cn(96).is_parameter_number_message_controller_number());
+        assert!(!cn(97).is_parameter_number_message_controller_number());
+    }
+
+    #[test]
+    fn is_parameter_number_controller_negative() {
+        // Given
+        let numbers = [0, 5, 7, 37, 39, 64, 95, 102, 127];
+        // When
+        // Then
+        for n in numbers.iter() {
+            assert!(!is_parameter_number_controller(cn(*n)), "{}", n);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    controller_numbers, Channel, ControllerNumber, RawShortMessage, ShortMessage,
+    ShortMessageFactory, StructuredShortMessage, U14, U7,
+};
+
+/// Cache of the latest controller state of all channels, that is Control Change values, pitch
+/// bend value and program.
+///
+/// Values which have never been received are unknown (`None`). Channel Mode messages (controllers
+/// 120 - 127) are not cached because they are commands rather than state.
+///
+/// Two caches can be compared using [`reconcile`] in order to find out which messages are
+/// necessary to bring one state to another one.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, controller_number, u7};
+/// use helgoboss_midi::ControllerValueCache;
+///
+/// let mut cache = ControllerValueCache::new();
+/// cache.feed(&control_change(0, 7, 100));
+/// assert_eq!(
+///     cache.control_value(channel(0), controller_number(7)),
+///     Some(u7(100))
+/// );
+/// assert_eq!(cache.control_value(channel(0), controller_number(10)), None);
+/// ```
+///
+/// [`reconcile`]: fn.reconcile.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct ControllerValueCache {
+    cache_by_channel: [CacheForOneChannel; 16],
+}
+
+impl ControllerValueCache {
+    /// Creates a new cache in which all values are unknown.
+    pub fn new() -> ControllerValueCache {
+        Default::default()
+    }
+
+    /// Feeds the cache a single short message.
+    pub fn feed(&mut self, msg: &impl ShortMessage) {
+        use StructuredShortMessage::*;
+        match msg.to_structured() {
+            ControlChange {
+                channel,
+                controller_number,
+                control_value,
+            } => self.set_control_value(channel, controller_number, control_value),
+            ProgramChange {
+                channel,
+                program_number,
+            } => self.set_program(channel, program_number),
+            PitchBendChange {
+                channel,
+                pitch_bend_value,
+            } => self.set_pitch_bend_value(channel, pitch_bend_value),
+            _ => {}
+        }
+    }
+
+    /// Returns the latest value of the given controller.
+    pub fn control_value(
+        &self,
+        channel: Channel,
+        controller_number: ControllerNumber,
+    ) -> Option<U7> {
+        self.cache_by_channel[usize::from(channel)].control_values[usize::from(controller_number)]
+    }
+
+    /// Sets the value of the given controller.
+    ///
+    /// Ignored for Channel Mode controller numbers (120 - 127).
+    pub fn set_control_value(
+        &mut self,
+        channel: Channel,
+        controller_number: ControllerNumber,
+        control_value: U7,
+    ) {
+        if controller_number >= controller_numbers::ALL_SOUND_OFF {
+            return;
+        }
+        self.cache_by_channel[usize::from(channel)].control_values
+            [usize::from(controller_number)] = Some(control_value);
+    }
+
+    /// Returns the latest pitch bend value of the given channel.
+    pub fn pitch_bend_value(&self, channel: Channel) -> Option<U14> {
+        self.cache_by_channel[usize::from(channel)].pitch_bend_value
+    }
+
+    /// Sets the pitch bend value of the given channel.
+    pub fn set_pitch_bend_value(&mut self, channel: Channel, pitch_bend_value: U14) {
+        self.cache_by_channel[usize::from(channel)].pitch_bend_value = Some(pitch_bend_value);
+    }
+
+    /// Returns the latest program of the given channel.
+    pub fn program(&self, channel: Channel) -> Option<U7> {
+        self.cache_by_channel[usize::from(channel)].program
+    }
+
+    /// Sets the program of the given channel.
+    pub fn set_program(&mut self, channel: Channel, program_number: U7) {
+        self.cache_by_channel[usize::from(channel)].program = Some(program_number);
+    }
+
+    /// Resets the cache making all values unknown.
+    pub fn reset(&mut self) {
+        for c in self.cache_by_channel.iter_mut() {
+            *c = Default::default();
+        }
+    }
+}
+
+/// Returns the minimal messages which need to be sent in order to bring the `current` controller
+/// state to the `desired` one.
+///
+/// Values which already match are skipped, as are values which are unknown in `desired`. Values
+/// which are unknown in `current` are always sent. Per channel, the Program Change message comes
+/// first (because receivers might reset controllers on program change), followed by the Control
+/// Change messages in ascending controller number order and finally the Pitch Bend Change
+/// message.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{control_change, pitch_bend_change};
+/// use helgoboss_midi::{reconcile, ControllerValueCache};
+///
+/// let mut current = ControllerValueCache::new();
+/// current.feed(&control_change(0, 7, 100));
+/// current.feed(&pitch_bend_change(0, 8192));
+/// let mut desired = current;
+/// desired.feed(&control_change(0, 7, 90));
+/// assert_eq!(reconcile(&current, &desired), vec![control_change(0, 7, 90)]);
+/// ```
+pub fn reconcile(
+    current: &ControllerValueCache,
+    desired: &ControllerValueCache,
+) -> Vec<RawShortMessage> {
+    let mut messages = Vec::new();
+    for (i, (c, d)) in current
+        .cache_by_channel
+        .iter()
+        .zip(desired.cache_by_channel.iter())
+        .enumerate()
+    {
+        let channel = Channel(i as u8);
+        if let Some(program) = changed_value(c.program, d.program) {
+            messages.push(RawShortMessage::program_change(channel, program));
+        }
+        for (j, (cv, dv)) in c
+            .control_values
+            .iter()
+            .zip(d.control_values.iter())
+            .enumerate()
+        {
+            if let Some(value) = changed_value(*cv, *dv) {
+                messages.push(RawShortMessage::control_change(
+                    channel,
+                    ControllerNumber(j as u8),
+                    value,
+                ));
+            }
+        }
+        if let Some(value) = changed_value(c.pitch_bend_value, d.pitch_bend_value) {
+            messages.push(RawShortMessage::pitch_bend_change(channel, value));
+        }
+    }
+    messages
+}
+
+fn changed_value<T: PartialEq>(current: Option<T>, desired: Option<T>) -> Option<T> {
+    let desired = desired?;
+    if current.as_ref() == Some(&desired) {
+        return None;
+    }
+    Some(desired)
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct CacheForOneChannel {
+    control_values: [Option<U7>; 128],
+    pitch_bend_value: Option<U14>,
+    program: Option<U7>,
+}
+
+impl Default for CacheForOneChannel {
+    fn default() -> Self {
+        CacheForOneChannel {
+            control_values: [None; 128],
+            pitch_bend_value: None,
+            program: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u14, u7};
+
+    #[test]
+    fn reconcile_only_differing_controllers() {
+        // Given
+        let mut current = ControllerValueCache::new();
+        current.feed(&RawShortMessage::control_change(ch(0), cn(1), u7(10)));
+        current.feed(&RawShortMessage::control_change(ch(0), cn(7), u7(100)));
+        current.feed(&RawShortMessage::control_change(ch(0), cn(10), u7(64)));
+        current.feed(&RawShortMessage::control_change(ch(3), cn(7), u7(20)));
+        current.feed(&RawShortMessage::pitch_bend_change(ch(0), u14(8192)));
+        current.feed(&RawShortMessage::program_change(ch(0), u7(5)));
+        let mut desired = current;
+        desired.feed(&RawShortMessage::control_change(ch(0), cn(10), u7(30)));
+        // When
+        let messages = reconcile(&current, &desired);
+        // Then
+        assert_eq!(
+            messages,
+            vec![RawShortMessage::control_change(ch(0), cn(10), u7(30))]
+        );
+    }
+
+    #[test]
+    fn reconcile_unknown_values() {
+        // Given
+        let mut current = ControllerValueCache::new();
+        current.feed(&RawShortMessage::control_change(ch(1), cn(7), u7(100)));
+        let mut desired = ControllerValueCache::new();
+        desired.feed(&RawShortMessage::pitch_bend_change(ch(1), u14(0)));
+        desired.feed(&RawShortMessage::control_change(ch(1), cn(11), u7(50)));
+        desired.feed(&RawShortMessage::program_change(ch(1), u7(3)));
+        // When
+        let messages = reconcile(&current, &desired);
+        // Then
+        assert_eq!(
+            messages,
+            vec![
+                RawShortMessage::program_change(ch(1), u7(3)),
+                RawShortMessage::control_change(ch(1), cn(11), u7(50)),
+                RawShortMessage::pitch_bend_change(ch(1), u14(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reconcile_equal() {
+        // Given
+        let mut cache = ControllerValueCache::new();
+        cache.feed(&RawShortMessage::control_change(ch(0), cn(7), u7(100)));
+        // When
+        // Then
+        assert!(reconcile(&cache, &cache).is_empty());
+    }
+
+    #[test]
+    fn ignore_channel_mode_messages() {
+        // Given
+        let mut cache = ControllerValueCache::new();
+        // When
+        cache.feed(&RawShortMessage::control_change(ch(0), cn(120), u7(0)));
+        cache.feed(&RawShortMessage::control_change(ch(0), cn(123), u7(0)));
+        // Then
+        assert_eq!(cache.control_value(ch(0), cn(120)), None);
+        assert_eq!(cache.control_value(ch(0), cn(123)), None);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{KeyNumber, U14};
+
+/// Frequency of A4 (key number 69) in Hz.
+pub(crate) const CONCERT_PITCH: f64 = 440.0;
+
+/// Converts the given (possibly fractional) key number to a frequency in Hz, assuming 12-tone
+/// equal temperament tuned to concert pitch.
+pub(crate) fn frequency_from_semitones(semitones: f64) -> f64 {
+    tuned_frequency_from_semitones(semitones, CONCERT_PITCH)
+}
+
+fn tuned_frequency_from_semitones(semitones: f64, tuning_a4: f64) -> f64 {
+    tuning_a4 * 2f64.powf((semitones - 69.0) / 12.0)
+}
+
+/// Calculates the frequency in Hz of the given note bent by the given pitch bend value, assuming
+/// 12-tone equal temperament.
+///
+/// `bend_range_semitones` is the pitch bend range of the receiver (often 2 semitones), which is
+/// reached by the maximum and minimum pitch bend value. `tuning_a4` is the frequency of A4 (key
+/// number 69), usually 440 Hz.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::{note_with_bend_to_frequency, KeyNumber, U14};
+///
+/// let frequency = note_with_bend_to_frequency(KeyNumber::new(69), U14::MAX, 12.0, 440.0);
+/// assert!((frequency - 880.0).abs() < 0.001);
+/// ```
+pub fn note_with_bend_to_frequency(
+    key: KeyNumber,
+    bend: U14,
+    bend_range_semitones: f64,
+    tuning_a4: f64,
+) -> f64 {
+    let semitones = key.get() as f64 + bend.to_pitch_bend_semitones(bend_range_semitones);
+    tuned_frequency_from_semitones(semitones, tuning_a4)
+}
+
+impl KeyNumber {
+    /// Returns the frequency of this key number in Hz, assuming 12-tone equal temperament with
+    /// A4 (key number 69) tuned to `a4_hz`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::KeyNumber;
+    ///
+    /// assert_eq!(KeyNumber::new(57).frequency_hz(432.0), 216.0);
+    /// ```
+    pub fn frequency_hz(&self, a4_hz: f64) -> f64 {
+        tuned_frequency_from_semitones(self.0 as f64, a4_hz)
+    }
+
+    /// Returns the frequency of this key number in Hz, assuming 12-tone equal temperament with
+    /// A4 tuned to concert pitch (440 Hz).
+    pub fn frequency_hz_standard(&self) -> f64 {
+        self.frequency_hz(CONCERT_PITCH)
+    }
+
+    /// Returns the key number whose equal-tempered frequency is closest to the given frequency,
+    /// with A4 (key number 69) tuned to `a4_hz`.
+    ///
+    /// Frequencies outside of the key number range result in key number 0 or 127.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::KeyNumber;
+    ///
+    /// assert_eq!(KeyNumber::closest_from_frequency(265.0, 440.0), KeyNumber::new(60));
+    /// ```
+    pub fn closest_from_frequency(hz: f64, a4_hz: f64) -> KeyNumber {
+        let semitones = 69.0 + 12.0 * (hz / a4_hz).log2();
+        KeyNumber(semitones.round().clamp(0.0, KeyNumber::MAX.get() as f64) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{key_number as kn, u14};
+
+    fn assert_frequency(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 0.001,
+            "expected {} but got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn center_bend_equals_note_frequency() {
+        // Given
+        // When
+        // Then
+        assert_frequency(
+            note_with_bend_to_frequency(kn(69), u14(8192), 2.0, 440.0),
+            440.0,
+        );
+        assert_frequency(
+            note_with_bend_to_frequency(kn(60), u14(8192), 2.0, 440.0),
+            261.626,
+        );
+        assert_frequency(
+            note_with_bend_to_frequency(kn(69), u14(8192), 2.0, 432.0),
+            432.0,
+        );
+    }
+
+    #[test]
+    fn full_bend_equals_note_plus_minus_range() {
+        // Given
+        // When
+        // Then
+        assert_frequency(
+            note_with_bend_to_frequency(kn(69), u14(16383), 2.0, 440.0),
+            frequency_from_semitones(71.0),
+        );
+        assert_frequency(
+            note_with_bend_to_frequency(kn(69), u14(0), 2.0, 440.0),
+            frequency_from_semitones(67.0),
+        );
+        assert_frequency(
+            note_with_bend_to_frequency(kn(69), u14(0), 12.0, 440.0),
+            220.0,
+        );
+    }
+
+    #[test]
+    fn key_number_frequency() {
+        // Given
+        // When
+        // Then
+        assert_frequency(kn(69).frequency_hz_standard(), 440.0);
+        assert_frequency(kn(60).frequency_hz_standard(), 261.626);
+        assert_frequency(kn(81).frequency_hz(432.0), 864.0);
+    }
+
+    #[test]
+    fn closest_key_number_from_frequency() {
+        // Given
+        // When
+        // Then
+        assert_eq!(KeyNumber::closest_from_frequency(440.0, 440.0), kn(69));
+        assert_eq!(KeyNumber::closest_from_frequency(256.0, 440.0), kn(60));
+        assert_eq!(KeyNumber::closest_from_frequency(432.0, 432.0), kn(69));
+        assert_eq!(KeyNumber::closest_from_frequency(1.0, 440.0), kn(0));
+        assert_eq!(KeyNumber::closest_from_frequency(0.0, 440.0), kn(0));
+        assert_eq!(KeyNumber::closest_from_frequency(50_000.0, 440.0), kn(127));
+        for key in KeyNumber::all() {
+            assert_eq!(
+                KeyNumber::closest_from_frequency(key.frequency_hz_standard(), 440.0),
+                key
+            );
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Contains functions for sending 14-bit values in the byte order required by the respective
+//! message type.
+//!
+//! The MIDI 1.0 specification transmits the two 7-bit halves of a 14-bit value in different
+//! orders, depending on the context:
+//!
+//! - **Pitch Bend Change** packs both halves into one short message. Data byte 1 carries the
+//!   least significant byte (LSB), data byte 2 the most significant byte (MSB).
+//! - **14-bit Control Change** uses two short messages. The MSB is sent first via controller
+//!   0 - 31, the LSB second via the corresponding controller 32 - 63.
+//! - **(N)RPN Data Entry** uses two short messages as well, but the LSB is sent first via
+//!   controller 38 (Data Entry LSB) and the MSB second via controller 6 (Data Entry MSB). That's
+//!   the order expected by [`ParameterNumberMessageScanner`].
+//!
+//! The functions in this module take care of the correct order so that client code doesn't
+//! have to remember it.
+//!
+//! # Example
+//!
+//! ```
+//! use helgoboss_midi::test_util::{control_change, pitch_bend_change};
+//! use helgoboss_midi::{controller_numbers, high_res, Channel, RawShortMessage, U14};
+//!
+//! let value = U14::new(1057);
+//! let pitch_bend: RawShortMessage = high_res::pitch_bend_change(Channel::new(0), value);
+//! assert_eq!(pitch_bend, pitch_bend_change(0, 1057));
+//! let cc: [RawShortMessage; 2] = high_res::control_change_14_bit(
+//!     Channel::new(0),
+//!     controller_numbers::CHANNEL_VOLUME,
+//!     value,
+//! );
+//! assert_eq!(cc, [control_change(0, 7, 8), control_change(0, 39, 33)]);
+//! let data_entry: [RawShortMessage; 2] = high_res::data_entry(Channel::new(0), value);
+//! assert_eq!(data_entry, [control_change(0, 38, 33), control_change(0, 6, 8)]);
+//! ```
+//!
+//! [`ParameterNumberMessageScanner`]: ../struct.ParameterNumberMessageScanner.html
+use crate::bit_util::{
+    build_14_bit_value_from_two_7_bit_values, extract_high_7_bit_value_from_14_bit_value,
+    extract_low_7_bit_value_from_14_bit_value,
+};
+use crate::{
+    controller_numbers, Channel, ControllerNumber, ShortMessageFactory, TryFromGreaterError, U14,
+    U7,
+};
+use std::convert::TryFrom;
+
+/// Creates a Pitch Bend Change message, which carries the LSB in data byte 1 and the MSB in data
+/// byte 2.
+pub fn pitch_bend_change<T: ShortMessageFactory>(channel: Channel, value: U14) -> T {
+    T::pitch_bend_change(channel, value)
+}
+
+/// Assembles the pitch bend value from the two data bytes of a Pitch Bend Change message, which
+/// carries the LSB in data byte 1 and the MSB in data byte 2.
+///
+/// # Errors
+///
+/// Returns an error if one of the bytes has the high bit set, i.e. is not a valid data byte.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::{high_res, U14};
+///
+/// assert_eq!(
+///     high_res::pitch_bend_value_from_bytes(0x00, 0x40),
+///     Ok(U14::new(8192))
+/// );
+/// assert!(high_res::pitch_bend_value_from_bytes(0x80, 0x40).is_err());
+/// ```
+pub fn pitch_bend_value_from_bytes(lsb: u8, msb: u8) -> Result<U14, TryFromGreaterError> {
+    let lsb = U7::try_from(lsb)?;
+    let msb = U7::try_from(msb)?;
+    Ok(build_14_bit_value_from_two_7_bit_values(msb, lsb))
+}
+
+/// Creates the 2 Control Change messages which make up a 14-bit Control Change message, MSB
+/// first.
+///
+/// # Panics
+///
+/// This function panics if `msb_controller_number` can't serve as controller number for
+/// transmitting the most significant byte of a 14-bit Control Change message.
+pub fn control_change_14_bit<T: ShortMessageFactory>(
+    channel: Channel,
+    msb_controller_number: ControllerNumber,
+    value: U14,
+) -> [T; 2] {
+    let lsb_controller_number = msb_controller_number
+        .corresponding_14_bit_lsb_controller_number()
+        .expect("controller number can't be used for sending 14-bit MSB");
+    [
+        T::control_change(
+            channel,
+            msb_controller_number,
+            extract_high_7_bit_value_from_14_bit_value(value),
+        ),
+        T::control_change(
+            channel,
+            lsb_controller_number,
+            extract_low_7_bit_value_from_14_bit_value(value),
+        ),
+    ]
+}
+
+/// Creates the 2 Data Entry Control Change messages which transmit a 14-bit (N)RPN value, LSB
+/// first.
+///
+/// The preceding parameter number selection is not included.
+pub fn data_entry<T: ShortMessageFactory>(channel: Channel, value: U14) -> [T; 2] {
+    [
+        T::control_change(
+            channel,
+            controller_numbers::DATA_ENTRY_MSB_LSB,
+            extract_low_7_bit_value_from_14_bit_value(value),
+        ),
+        T::control_change(
+            channel,
+            controller_numbers::DATA_ENTRY_MSB,
+            extract_high_7_bit_value_from_14_bit_value(value),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u14, u7};
+    use crate::{ParameterNumberMessage, RawShortMessage, ShortMessage};
+
+    #[test]
+    fn pitch_bend_change_sends_lsb_first() {
+        // Given
+        // When
+        let msg: RawShortMessage = pitch_bend_change(ch(3), u14(1057));
+        // Then
+        assert_eq!(msg.status_byte(), 0xe3);
+        assert_eq!(msg.data_byte_1(), u7(33));
+        assert_eq!(msg.data_byte_2(), u7(8));
+    }
+
+    #[test]
+    fn pitch_bend_value_from_bytes_reads_lsb_first() {
+        // Given
+        // When
+        // Then
+        assert_eq!(pitch_bend_value_from_bytes(0x00, 0x40), Ok(u14(8192)));
+        assert_eq!(pitch_bend_value_from_bytes(33, 8), Ok(u14(1057)));
+        assert_eq!(pitch_bend_value_from_bytes(0x7f, 0x7f), Ok(u14(16383)));
+    }
+
+    #[test]
+    fn pitch_bend_value_from_bytes_rejects_high_bit() {
+        // Given
+        // When
+        // Then
+        assert!(pitch_bend_value_from_bytes(0x80, 0x40).is_err());
+        assert!(pitch_bend_value_from_bytes(0x00, 0xc0).is_err());
+    }
+
+    #[test]
+    fn control_change_14_bit_sends_msb_first() {
+        // Given
+        // When
+        let msgs: [RawShortMessage; 2] = control_change_14_bit(ch(3), cn(2), u14(1057));
+        // Then
+        assert_eq!(
+            msgs,
+            [
+                RawShortMessage::control_change(ch(3), cn(2), u7(8)),
+                RawShortMessage::control_change(ch(3), cn(34), u7(33)),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn control_change_14_bit_panics_on_invalid_msb_controller_number() {
+        let _: [RawShortMessage; 2] = control_change_14_bit(ch(3), cn(40), u14(1057));
+    }
+
+    #[test]
+    fn data_entry_sends_lsb_first() {
+        // Given
+        // When
+        let msgs: [RawShortMessage; 2] = data_entry(ch(3), u14(15000));
+        // Then
+        assert_eq!(
+            msgs,
+            [
+                RawShortMessage::control_change(ch(3), cn(38), u7(24)),
+                RawShortMessage::control_change(ch(3), cn(6), u7(117)),
+            ]
+        );
+        let pn_msgs: [Option<RawShortMessage>; 4] =
+            ParameterNumberMessage::registered_14_bit(ch(3), u14(420), u14(15000))
+                .to_short_messages();
+        assert_eq!(pn_msgs[2], Some(msgs[0]));
+        assert_eq!(pn_msgs[3], Some(msgs[1]));
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{
+    is_parameter_number_controller, Channel, ControlChange14BitMessage,
+    ControlChange14BitMessageScanner, ParameterNumberMessage, ParameterNumberMessageScanner,
+    ShortMessage, StructuredShortMessage, U14,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An event detected by [`HighResScanner`].
+///
+/// [`HighResScanner`]: struct.HighResScanner.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum HighResEvent {
+    /// An (N)RPN message detected by the underlying [`ParameterNumberMessageScanner`].
+    ///
+    /// [`ParameterNumberMessageScanner`]: struct.ParameterNumberMessageScanner.html
+    ParameterNumber(ParameterNumberMessage),
+    /// A 14-bit Control Change message detected by the underlying
+    /// [`ControlChange14BitMessageScanner`].
+    ///
+    /// [`ControlChange14BitMessageScanner`]: struct.ControlChange14BitMessageScanner.html
+    ControlChange14Bit(ControlChange14BitMessage),
+    /// A Pitch Bend Change message, which is 14-bit by nature.
+    PitchBend { channel: Channel, value: U14 },
+}
+
+/// Scanner for detecting both (N)RPN messages and 14-bit Control Change messages in a stream of
+/// short messages.
+///
+/// Combines a [`ParameterNumberMessageScanner`] and a [`ControlChange14BitMessageScanner`].
+/// Control Change messages on (N)RPN controllers (6, 38, 96 - 101) are fed to the former only.
+/// That way Data Entry messages are never misinterpreted as 14-bit Control Change messages. In
+/// other words, (N)RPN takes precedence: Controller 6 and 38 are always treated as Data Entry MSB
+/// and LSB, so a 14-bit Control Change message on controller 6 is never detected, and Data Entry
+/// messages don't interrupt a pending 14-bit Control Change message. All other Control Change
+/// messages are fed to both, so the (N)RPN scanner can discard the selected parameter number if
+/// it's configured to be strict (see [`ChannelScanConfig`]). Otherwise it ignores them. Messages
+/// without channel (System messages) are fed to both as well. Pitch Bend Change messages are
+/// complete 14-bit messages already, so they are passed through immediately as
+/// [`HighResEvent::PitchBend`] without any buffering.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{control_change, control_change_14_bit, nrpn_14_bit};
+/// use helgoboss_midi::{HighResEvent, HighResScanner};
+///
+/// let mut scanner = HighResScanner::new();
+/// let results: Vec<_> = [
+///     control_change(0, 99, 3),
+///     control_change(0, 2, 8),
+///     control_change(0, 98, 37),
+///     control_change(0, 34, 33),
+///     control_change(0, 38, 65),
+///     control_change(0, 6, 2),
+/// ]
+/// .iter()
+/// .filter_map(|msg| scanner.feed(msg))
+/// .collect();
+/// assert_eq!(
+///     results,
+///     vec![
+///         HighResEvent::ControlChange14Bit(control_change_14_bit(0, 2, 1057)),
+///         HighResEvent::ParameterNumber(nrpn_14_bit(0, 421, 321)),
+///     ]
+/// );
+/// ```
+///
+/// [`ParameterNumberMessageScanner`]: struct.ParameterNumberMessageScanner.html
+/// [`ControlChange14BitMessageScanner`]: struct.ControlChange14BitMessageScanner.html
+/// [`ChannelScanConfig`]: struct.ChannelScanConfig.html
+/// [`HighResEvent::PitchBend`]: enum.HighResEvent.html#variant.PitchBend
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HighResScanner {
+    parameter_number_message_scanner: ParameterNumberMessageScanner,
+    control_change_14_bit_message_scanner: ControlChange14BitMessageScanner,
+}
+
+impl HighResScanner {
+    /// Creates a new scanner.
+    pub fn new() -> HighResScanner {
+        Default::default()
+    }
+
+    /// Feeds the scanner a single short message.
+    ///
+    /// Returns the (N)RPN, 14-bit Control Change or Pitch Bend Change message if one has been
+    /// detected.
+    pub fn feed(&mut self, msg: &impl ShortMessage) -> Option<HighResEvent> {
+        match msg.to_structured() {
+            StructuredShortMessage::ControlChange {
+                controller_number, ..
+            } => {
+                if is_parameter_number_controller(controller_number) {
+                    self.parameter_number_message_scanner
+                        .feed(msg)
+                        .map(HighResEvent::ParameterNumber)
+                } else {
+                    // Only relevant in strict mode, otherwise ignored by the (N)RPN scanner
+                    self.parameter_number_message_scanner.feed(msg);
+                    self.control_change_14_bit_message_scanner
+                        .feed(msg)
+                        .map(HighResEvent::ControlChange14Bit)
+                }
+            }
+            StructuredShortMessage::PitchBendChange {
+                channel,
+                pitch_bend_value,
+            } => Some(HighResEvent::PitchBend {
+                channel,
+                value: pitch_bend_value,
+            }),
+            _ if msg.channel().is_none() => {
+                self.parameter_number_message_scanner.feed(msg);
+                self.control_change_14_bit_message_scanner.feed(msg);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying (N)RPN scanner.
+    pub fn parameter_number_message_scanner(&mut self) -> &mut ParameterNumberMessageScanner {
+        &mut self.parameter_number_message_scanner
+    }
+
+    /// Returns the underlying 14-bit Control Change scanner.
+    pub fn control_change_14_bit_message_scanner(
+        &mut self,
+    ) -> &mut ControlChange14BitMessageScanner {
+        &mut self.control_change_14_bit_message_scanner
+    }
+
+    /// Resets the scanner discarding all intermediate scanning progress.
+    pub fn reset(&mut self) {
+        self.parameter_number_message_scanner.reset();
+        self.control_change_14_bit_message_scanner.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u14, u7};
+    use crate::{RawShortMessage, ShortMessageFactory};
+
+    #[test]
+    fn should_detect_interleaved_nrpn_and_14_bit_control_change() {
+        // Given
+        let mut scanner = HighResScanner::new();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(99), u7(3)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(7), u7(8)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(98), u7(37)));
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(39), u7(33)));
+        let result_5 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(38), u7(65)));
+        let result_6 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(6), u7(2)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(
+            result_4,
+            Some(HighResEvent::ControlChange14Bit(
+                ControlChange14BitMessage::new(ch(1), cn(7), u14(1057))
+            ))
+        );
+        assert_eq!(result_5, None);
+        assert_eq!(
+            result_6,
+            Some(HighResEvent::ParameterNumber(
+                ParameterNumberMessage::non_registered_14_bit(ch(1), u14(421), u14(321))
+            ))
+        );
+    }
+
+    #[test]
+    fn strict_mode_should_discard_interrupted_nrpn() {
+        // Given
+        let mut scanner = HighResScanner::new();
+        *scanner.parameter_number_message_scanner() = ParameterNumberMessageScanner::new_strict();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(99), u7(3)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(98), u7(37)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(7), u7(8)));
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(6), u7(2)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(result_4, None);
+    }
+
+    #[test]
+    fn should_not_treat_data_entry_as_14_bit_control_change() {
+        // Given
+        let mut scanner = HighResScanner::new();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(8)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(38), u7(33)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+    }
+
+    #[test]
+    fn data_entry_should_not_interrupt_14_bit_control_change() {
+        // Given
+        let mut scanner = HighResScanner::new();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(0)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(5), u7(8)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(0)));
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(12)));
+        let result_5 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(37), u7(33)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(
+            result_4,
+            Some(HighResEvent::ParameterNumber(
+                ParameterNumberMessage::registered_7_bit(ch(0), u14(0), u7(12))
+            ))
+        );
+        assert_eq!(
+            result_5,
+            Some(HighResEvent::ControlChange14Bit(
+                ControlChange14BitMessage::new(ch(0), cn(5), u14(1057))
+            ))
+        );
+    }
+
+    #[test]
+    fn should_emit_pitch_bend_immediately() {
+        // Given
+        let mut scanner = HighResScanner::new();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(2), cn(7), u7(8)));
+        let result_2 = scanner.feed(&RawShortMessage::pitch_bend_change(ch(2), u14(9000)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(2), cn(39), u7(33)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(HighResEvent::PitchBend {
+                channel: ch(2),
+                value: u14(9000)
+            })
+        );
+        assert_eq!(
+            result_3,
+            Some(HighResEvent::ControlChange14Bit(
+                ControlChange14BitMessage::new(ch(2), cn(7), u14(1057))
+            ))
+        );
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 // Basic newtype definition
 newtype! {
     #[doc = r"A key number (0 - 127), e.g. of a MIDI Note On message."]
@@ -37,3 +40,147 @@ impl_try_from_primitive_to_newtype!(u128, KeyNumber);
 impl_try_from_primitive_to_newtype!(i128, KeyNumber);
 impl_try_from_primitive_to_newtype!(usize, KeyNumber);
 impl_try_from_primitive_to_newtype!(isize, KeyNumber);
+
+/// Defines which octave number key number 60 (middle C) belongs to when displaying note names.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OctaveConvention {
+    /// Key number 60 is C4 and key number 0 is C-1 (scientific pitch notation).
+    Scientific,
+    /// Key number 60 is C3 and key number 0 is C-2, as used by Yamaha and many DAWs.
+    Yamaha,
+}
+
+impl OctaveConvention {
+    /// Returns the octave number of key number 0.
+    fn lowest_octave(self) -> i32 {
+        match self {
+            OctaveConvention::Scientific => -1,
+            OctaveConvention::Yamaha => -2,
+        }
+    }
+}
+
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+impl KeyNumber {
+    /// Returns the note name of this key number, e.g. "C4" or "A#3".
+    ///
+    /// Accidentals are always displayed as sharps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::{KeyNumber, OctaveConvention};
+    ///
+    /// let key = KeyNumber::new(60);
+    /// assert_eq!(key.note_name(OctaveConvention::Scientific), "C4");
+    /// assert_eq!(key.note_name(OctaveConvention::Yamaha), "C3");
+    /// ```
+    pub fn note_name(&self, convention: OctaveConvention) -> String {
+        let octave = self.0 as i32 / 12 + convention.lowest_octave();
+        format!("{}{}", NOTE_NAMES[self.0 as usize % 12], octave)
+    }
+
+    /// Parses the given note name, e.g. "C4", "F#5" or "Bb-1", using the given octave
+    /// convention.
+    ///
+    /// The note letter is case-insensitive and can be followed by `#` (sharp) or `b` (flat).
+    /// Returns `None` if the name is invalid or out of range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::{KeyNumber, OctaveConvention};
+    ///
+    /// let key = KeyNumber::from_note_name("Bb3", OctaveConvention::Scientific);
+    /// assert_eq!(key, Some(KeyNumber::new(58)));
+    /// assert_eq!(KeyNumber::from_note_name("H3", OctaveConvention::Scientific), None);
+    /// ```
+    pub fn from_note_name(name: &str, convention: OctaveConvention) -> Option<KeyNumber> {
+        let mut chars = name.chars();
+        let natural = match chars.next()?.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' => 11,
+            _ => return None,
+        };
+        let rest = chars.as_str();
+        let (accidental, octave) = if let Some(octave) = rest.strip_prefix('#') {
+            (1, octave)
+        } else if let Some(octave) = rest.strip_prefix('b') {
+            (-1, octave)
+        } else {
+            (0, rest)
+        };
+        let octave: i32 = octave.parse().ok()?;
+        let value = octave
+            .checked_sub(convention.lowest_octave())?
+            .checked_mul(12)?
+            .checked_add(natural + accidental)?;
+        if !(0..=KeyNumber::MAX.get() as i32).contains(&value) {
+            return None;
+        }
+        Some(KeyNumber(value as u8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::key_number as kn;
+
+    #[test]
+    fn note_name() {
+        // Given
+        // When
+        // Then
+        assert_eq!(kn(60).note_name(OctaveConvention::Scientific), "C4");
+        assert_eq!(kn(60).note_name(OctaveConvention::Yamaha), "C3");
+        assert_eq!(kn(0).note_name(OctaveConvention::Scientific), "C-1");
+        assert_eq!(kn(127).note_name(OctaveConvention::Yamaha), "G8");
+    }
+
+    #[test]
+    fn from_note_name() {
+        // Given
+        let scientific = OctaveConvention::Scientific;
+        // When
+        // Then
+        assert_eq!(KeyNumber::from_note_name("C4", scientific), Some(kn(60)));
+        assert_eq!(
+            KeyNumber::from_note_name("C3", OctaveConvention::Yamaha),
+            Some(kn(60))
+        );
+        assert_eq!(KeyNumber::from_note_name("Gb5", scientific), Some(kn(78)));
+        assert_eq!(KeyNumber::from_note_name("c-1", scientific), Some(kn(0)));
+        assert_eq!(KeyNumber::from_note_name("Cb-1", scientific), None);
+        assert_eq!(KeyNumber::from_note_name("G#9", scientific), None);
+        assert_eq!(KeyNumber::from_note_name("C", scientific), None);
+        assert_eq!(KeyNumber::from_note_name("C999999999", scientific), None);
+        assert_eq!(KeyNumber::from_note_name("C-2147483648", scientific), None);
+        assert_eq!(KeyNumber::from_note_name("", scientific), None);
+    }
+
+    #[test]
+    fn note_name_round_trip() {
+        // Given
+        let conventions = [OctaveConvention::Scientific, OctaveConvention::Yamaha];
+        // When
+        // Then
+        for convention in conventions.iter() {
+            let key = KeyNumber::from_note_name("F#5", *convention).unwrap();
+            assert_eq!(key.note_name(*convention), "F#5");
+            for key in KeyNumber::all() {
+                let name = key.note_name(*convention);
+                assert_eq!(KeyNumber::from_note_name(&name, *convention), Some(key));
+            }
+        }
+    }
+}
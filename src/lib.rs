@@ -8,6 +8,7 @@
 //!     - Short messages (3 bytes)
 //!     - 14-bit Control Change messages
 //!     - (N)RPN messages
+//!     - System Exclusive messages (as manufacturer ID and data bytes)
 //! - Scanners for extracting 14-bit Control Change and (N)RPN messages from a stream of short
 //!   messages
 //! - Suitable for real-time usage (no heap allocation, no dynamic dispatch, no locking)
@@ -17,9 +18,9 @@
 //!
 //! # Not yet implemented
 //!
-//! Data structures and utilities for dealing with System Exclusive messages are not yet
-//! implemented. They will be added eventually as separate structures on top of the
-//! existing ones (similar to (N)RPN and 14-bit Control Change).
+//! Besides the MIDI Tuning Standard, specific System Exclusive messages (e.g. the Universal
+//! System Exclusive messages) are not yet modeled as separate structures. They will be added
+//! eventually on top of [`SysExMessage`](struct.SysExMessage.html).
 //!
 //! # Examples
 //!
@@ -47,9 +48,24 @@ pub use short_message_factory::*;
 mod structured_short_message;
 pub use structured_short_message::*;
 
+mod channel_mode_message;
+pub use channel_mode_message::*;
+
+mod channel_reset;
+pub use channel_reset::*;
+
 mod raw_short_message;
 pub use raw_short_message::*;
 
+mod short_message_ref;
+pub use short_message_ref::*;
+
+mod short_message_iterator_ext;
+pub use short_message_iterator_ext::*;
+
+mod running_status;
+pub use running_status::*;
+
 mod control_change_14_bit_message;
 pub use control_change_14_bit_message::*;
 
@@ -59,9 +75,75 @@ pub use control_change_14_bit_message_scanner::*;
 mod parameter_number_message;
 pub use parameter_number_message::*;
 
+mod parameter_number_transaction;
+pub use parameter_number_transaction::*;
+
 mod parameter_number_message_scanner;
 pub use parameter_number_message_scanner::*;
 
+mod high_res_scanner;
+pub use high_res_scanner::*;
+
+mod program_change_with_bank;
+pub use program_change_with_bank::*;
+
+mod program_change_with_bank_scanner;
+pub use program_change_with_bank_scanner::*;
+
+mod bank_tracker;
+pub use bank_tracker::*;
+
+mod voice_limiter;
+pub use voice_limiter::*;
+
+mod sustain_tracker;
+pub use sustain_tracker::*;
+
+mod note_state_tracker;
+pub use note_state_tracker::*;
+
+mod play_style_detector;
+pub use play_style_detector::*;
+
+mod controller_value_cache;
+pub use controller_value_cache::*;
+
+mod panic_detector;
+pub use panic_detector::*;
+
+mod velocity_curve;
+pub use velocity_curve::*;
+
+mod relative;
+pub use relative::*;
+
+mod mts;
+pub use mts::*;
+
+mod sys_ex_message;
+pub use sys_ex_message::*;
+
+mod sys_ex_scanner;
+pub use sys_ex_scanner::*;
+
+mod meta_event;
+pub use meta_event::*;
+
+mod tempo_map;
+pub use tempo_map::*;
+
+mod clock_tempo_estimator;
+pub use clock_tempo_estimator::*;
+
+mod song_position_pointer;
+pub use song_position_pointer::*;
+
+mod timed;
+pub use timed::*;
+
+mod min_duration;
+pub use min_duration::*;
+
 // I added the _mod suffix because of intellij-rust issue 4992
 mod channel_mod;
 pub use channel_mod::*;
@@ -81,7 +163,23 @@ pub use u7_mod::*;
 mod u14_mod;
 pub use u14_mod::*;
 
+mod frequency;
+pub use frequency::*;
+
+mod pitch_bend;
+
+mod build_info;
+pub use build_info::*;
+
 mod bit_util;
-pub(crate) use bit_util::*;
+pub(crate) use bit_util::{build_status_byte, extract_channel_from_status_byte};
+
+pub mod high_res;
+
+pub mod panic;
+
+pub mod rpn;
+
+pub mod transform;
 
 pub mod test_util;
@@ -0,0 +1,241 @@
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An error which can occur when parsing a meta event.
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[display(fmt = "invalid or unsupported meta event")]
+pub struct ParseMetaEventError(pub(crate) ());
+
+impl std::error::Error for ParseMetaEventError {}
+
+/// A meta event as it occurs in Standard MIDI Files (SMF).
+///
+/// Meta events are not MIDI messages and never sent over the wire, but they accompany short
+/// messages in files. Only the most common types are supported. Their byte form consists of
+/// `0xFF`, the type byte, the data length as variable-length quantity and the data.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::MetaEvent;
+///
+/// let event = MetaEvent::SetTempo {
+///     microseconds_per_quarter_note: 500_000,
+/// };
+/// let bytes = event.to_bytes();
+/// assert_eq!(bytes, vec![0xff, 0x51, 0x03, 0x07, 0xa1, 0x20]);
+/// assert_eq!(MetaEvent::from_bytes(&bytes), Ok(event));
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MetaEvent {
+    /// Sequence or track name (type 0x03).
+    TrackName(String),
+    /// End of track (type 0x2F).
+    EndOfTrack,
+    /// Tempo change (type 0x51).
+    SetTempo {
+        /// Duration of one quarter note in microseconds (24-bit).
+        microseconds_per_quarter_note: u32,
+    },
+    /// Time signature (type 0x58).
+    TimeSignature {
+        numerator: u8,
+        /// The denominator as power of 2, e.g. 2 for a quarter note.
+        denominator_exponent: u8,
+        /// Number of MIDI clocks per metronome click.
+        clocks_per_click: u8,
+        /// Number of notated 32nd notes per MIDI quarter note (usually 8).
+        thirty_second_notes_per_quarter_note: u8,
+    },
+    /// Key signature (type 0x59).
+    KeySignature {
+        /// Number of sharps (positive) or flats (negative), from -7 to 7.
+        sharps_or_flats: i8,
+        is_minor: bool,
+    },
+}
+
+const META_EVENT_STATUS: u8 = 0xff;
+const TRACK_NAME: u8 = 0x03;
+const END_OF_TRACK: u8 = 0x2f;
+const SET_TEMPO: u8 = 0x51;
+const TIME_SIGNATURE: u8 = 0x58;
+const KEY_SIGNATURE: u8 = 0x59;
+
+impl MetaEvent {
+    /// Parses the given complete meta event, starting with `0xFF`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes don't make up exactly one meta event of a supported type.
+    pub fn from_bytes(bytes: &[u8]) -> Result<MetaEvent, ParseMetaEventError> {
+        let (&status, rest) = bytes.split_first().ok_or(ParseMetaEventError(()))?;
+        let (&r#type, rest) = rest.split_first().ok_or(ParseMetaEventError(()))?;
+        if status != META_EVENT_STATUS {
+            return Err(ParseMetaEventError(()));
+        }
+        let (len, data) = read_variable_length_quantity(rest).ok_or(ParseMetaEventError(()))?;
+        if data.len() != len as usize {
+            return Err(ParseMetaEventError(()));
+        }
+        let event = match (r#type, data) {
+            (TRACK_NAME, _) => MetaEvent::TrackName(String::from_utf8_lossy(data).into_owned()),
+            (END_OF_TRACK, []) => MetaEvent::EndOfTrack,
+            (SET_TEMPO, &[b0, b1, b2]) => MetaEvent::SetTempo {
+                microseconds_per_quarter_note: u32::from_be_bytes([0, b0, b1, b2]),
+            },
+            (TIME_SIGNATURE, &[nn, dd, cc, bb]) => MetaEvent::TimeSignature {
+                numerator: nn,
+                denominator_exponent: dd,
+                clocks_per_click: cc,
+                thirty_second_notes_per_quarter_note: bb,
+            },
+            (KEY_SIGNATURE, &[sf, mi]) if mi <= 1 => MetaEvent::KeySignature {
+                sharps_or_flats: sf as i8,
+                is_minor: mi == 1,
+            },
+            _ => return Err(ParseMetaEventError(())),
+        };
+        Ok(event)
+    }
+
+    /// Returns the byte form of this meta event, starting with `0xFF`.
+    ///
+    /// The tempo is truncated to 24 bits.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        use MetaEvent::*;
+        let (r#type, data) = match self {
+            TrackName(name) => (TRACK_NAME, name.as_bytes().to_vec()),
+            EndOfTrack => (END_OF_TRACK, vec![]),
+            SetTempo {
+                microseconds_per_quarter_note,
+            } => (
+                SET_TEMPO,
+                microseconds_per_quarter_note.to_be_bytes()[1..].to_vec(),
+            ),
+            TimeSignature {
+                numerator,
+                denominator_exponent,
+                clocks_per_click,
+                thirty_second_notes_per_quarter_note,
+            } => (
+                TIME_SIGNATURE,
+                vec![
+                    *numerator,
+                    *denominator_exponent,
+                    *clocks_per_click,
+                    *thirty_second_notes_per_quarter_note,
+                ],
+            ),
+            KeySignature {
+                sharps_or_flats,
+                is_minor,
+            } => (KEY_SIGNATURE, vec![*sharps_or_flats as u8, *is_minor as u8]),
+        };
+        let mut bytes = vec![META_EVENT_STATUS, r#type];
+        write_variable_length_quantity(data.len() as u32, &mut bytes);
+        bytes.extend(data);
+        bytes
+    }
+}
+
+/// Reads a variable-length quantity (at most 4 bytes) and returns it together with the remaining
+/// bytes.
+fn read_variable_length_quantity(bytes: &[u8]) -> Option<(u32, &[u8])> {
+    let mut value: u32 = 0;
+    for (i, b) in bytes.iter().take(4).enumerate() {
+        value = (value << 7) | (b & 0x7f) as u32;
+        if b & 0x80 == 0 {
+            return Some((value, &bytes[i + 1..]));
+        }
+    }
+    None
+}
+
+fn write_variable_length_quantity(value: u32, bytes: &mut Vec<u8>) {
+    let mut groups = vec![(value & 0x7f) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push((rest & 0x7f) as u8 | 0x80);
+        rest >>= 7;
+    }
+    bytes.extend(groups.iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_set_tempo() {
+        // Given
+        let bytes = [0xff, 0x51, 0x03, 0x0f, 0x42, 0x40];
+        // When
+        let result = MetaEvent::from_bytes(&bytes);
+        // Then
+        assert_eq!(
+            result,
+            Ok(MetaEvent::SetTempo {
+                microseconds_per_quarter_note: 1_000_000
+            })
+        );
+    }
+
+    #[test]
+    fn parse_time_signature() {
+        // Given
+        let bytes = [0xff, 0x58, 0x04, 0x06, 0x03, 0x24, 0x08];
+        // When
+        let result = MetaEvent::from_bytes(&bytes);
+        // Then
+        let expected = MetaEvent::TimeSignature {
+            numerator: 6,
+            denominator_exponent: 3,
+            clocks_per_click: 36,
+            thirty_second_notes_per_quarter_note: 8,
+        };
+        assert_eq!(result, Ok(expected.clone()));
+        assert_eq!(expected.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn round_trip() {
+        // Given
+        let events = vec![
+            MetaEvent::TrackName("Bass".repeat(40)),
+            MetaEvent::EndOfTrack,
+            MetaEvent::KeySignature {
+                sharps_or_flats: -3,
+                is_minor: true,
+            },
+        ];
+        // When
+        // Then
+        for event in events {
+            assert_eq!(MetaEvent::from_bytes(&event.to_bytes()), Ok(event));
+        }
+        assert_eq!(
+            MetaEvent::TrackName("Bass".repeat(40)).to_bytes()[2..4],
+            [0x81, 0x20]
+        );
+    }
+
+    #[test]
+    fn reject_invalid() {
+        // Given
+        let invalid: [&[u8]; 5] = [
+            &[],
+            &[0xff],
+            &[0xfe, 0x2f, 0x00],
+            &[0xff, 0x51, 0x03, 0x07, 0xa1],
+            &[0xff, 0x7f, 0x00],
+        ];
+        // When
+        // Then
+        for bytes in invalid.iter() {
+            assert_eq!(MetaEvent::from_bytes(bytes), Err(ParseMetaEventError(())));
+        }
+    }
+}
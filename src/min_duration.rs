@@ -0,0 +1,209 @@
+use crate::{RawShortMessage, ShortMessage, StructuredShortMessage, Timed, U7};
+use std::time::Duration;
+
+/// Enforces a minimum note duration by delaying Note Off messages.
+///
+/// Very short notes, e.g. caused by glitchy controllers, can result in clicks. Feed this
+/// transform all timed messages. If a Note Off arrives before the note has sounded for the
+/// minimum duration, it's held back and released as soon as the minimum duration has passed,
+/// either by a later call of [`feed`] or by [`flush`]. Released Note Off messages carry the time
+/// at which they are due. All other messages are passed through unchanged.
+///
+/// If a note is triggered again while its Note Off is held back, the Note Off is released
+/// immediately before the new Note On, so the minimum durations don't stack up. The new note gets
+/// its own minimum duration.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{note_off, note_on};
+/// use helgoboss_midi::{MinDuration, Timed};
+/// use std::time::Duration;
+///
+/// let ms = Duration::from_millis;
+/// let mut transform = MinDuration::new(ms(20));
+/// assert_eq!(
+///     transform.feed(Timed::new(ms(0), note_on(0, 60, 100))),
+///     vec![Timed::new(ms(0), note_on(0, 60, 100))]
+/// );
+/// assert!(transform.feed(Timed::new(ms(1), note_off(0, 60, 0))).is_empty());
+/// assert_eq!(
+///     transform.flush(ms(30)),
+///     vec![Timed::new(ms(20), note_off(0, 60, 0))]
+/// );
+/// ```
+///
+/// [`feed`]: #method.feed
+/// [`flush`]: #method.flush
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct MinDuration {
+    min_duration: Duration,
+    note_on_times: [[Option<Duration>; 128]; 16],
+    delayed_note_offs: Vec<Timed<RawShortMessage>>,
+}
+
+impl MinDuration {
+    /// Creates a transform which makes each note sound for at least the given duration.
+    ///
+    /// Note Off messages which would be due beyond `Duration::MAX` are due at `Duration::MAX`.
+    pub fn new(min_duration: Duration) -> MinDuration {
+        MinDuration {
+            min_duration,
+            note_on_times: [[None; 128]; 16],
+            delayed_note_offs: Vec::new(),
+        }
+    }
+
+    /// Feeds the transform a single timed short message.
+    ///
+    /// Returns the messages which should be sent now, that is, Note Off messages which became due
+    /// in the meantime followed by the given message unless it's held back. Times must not
+    /// decrease.
+    pub fn feed(&mut self, msg: Timed<impl ShortMessage>) -> Vec<Timed<RawShortMessage>> {
+        let mut out = self.flush(msg.time);
+        let raw_msg = msg.value.to_raw();
+        match msg.value.to_structured() {
+            StructuredShortMessage::NoteOn {
+                channel,
+                key_number,
+                velocity,
+            } if velocity > U7::MIN => {
+                // Retrigger: Release the held-back Note Off right now
+                if let Some(i) = self.delayed_note_offs.iter().position(|off| {
+                    off.value.channel() == Some(channel)
+                        && off.value.key_number() == Some(key_number)
+                }) {
+                    let note_off = self.delayed_note_offs.remove(i);
+                    out.push(Timed::new(msg.time, note_off.value));
+                }
+                self.note_on_times[usize::from(channel)][usize::from(key_number)] = Some(msg.time);
+            }
+            StructuredShortMessage::NoteOn {
+                channel,
+                key_number,
+                ..
+            }
+            | StructuredShortMessage::NoteOff {
+                channel,
+                key_number,
+                ..
+            } => {
+                let note_on_time =
+                    self.note_on_times[usize::from(channel)][usize::from(key_number)].take();
+                if let Some(t) = note_on_time {
+                    let due_time = t.checked_add(self.min_duration).unwrap_or(Duration::MAX);
+                    if msg.time < due_time {
+                        self.delayed_note_offs.push(Timed::new(due_time, raw_msg));
+                        return out;
+                    }
+                }
+            }
+            _ => {}
+        }
+        out.push(Timed::new(msg.time, raw_msg));
+        out
+    }
+
+    /// Returns all held-back Note Off messages which are due at the given time, ordered by time.
+    ///
+    /// Should be called regularly, e.g. once per audio block, or at the end of a stream, in which
+    /// case `now` should be late enough to release all of them.
+    pub fn flush(&mut self, now: Duration) -> Vec<Timed<RawShortMessage>> {
+        let mut due: Vec<_> = self
+            .delayed_note_offs
+            .iter()
+            .filter(|off| off.time <= now)
+            .copied()
+            .collect();
+        self.delayed_note_offs.retain(|off| off.time > now);
+        due.sort_by_key(|off| off.time);
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, key_number as kn, u7};
+    use crate::ShortMessageFactory;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn note_on(key_number: u8) -> RawShortMessage {
+        RawShortMessage::note_on(ch(0), kn(key_number), u7(100))
+    }
+
+    fn note_off(key_number: u8) -> RawShortMessage {
+        RawShortMessage::note_off(ch(0), kn(key_number), u7(0))
+    }
+
+    #[test]
+    fn should_extend_short_note() {
+        // Given
+        let mut transform = MinDuration::new(ms(50));
+        // When
+        let result_1 = transform.feed(Timed::new(ms(100), note_on(60)));
+        let result_2 = transform.feed(Timed::new(ms(101), note_off(60)));
+        let cc = RawShortMessage::control_change(ch(0), cn(7), u7(100));
+        let result_3 = transform.feed(Timed::new(ms(160), cc));
+        // Then
+        assert_eq!(result_1, vec![Timed::new(ms(100), note_on(60))]);
+        assert_eq!(result_2, vec![]);
+        assert_eq!(
+            result_3,
+            vec![Timed::new(ms(150), note_off(60)), Timed::new(ms(160), cc)]
+        );
+        assert_eq!(transform.flush(ms(1000)), vec![]);
+    }
+
+    #[test]
+    fn should_pass_long_note() {
+        // Given
+        let mut transform = MinDuration::new(ms(50));
+        // When
+        transform.feed(Timed::new(ms(0), note_on(60)));
+        let result = transform.feed(Timed::new(ms(50), note_off(60)));
+        // Then
+        assert_eq!(result, vec![Timed::new(ms(50), note_off(60))]);
+    }
+
+    #[test]
+    fn should_not_stack_min_durations_on_retrigger() {
+        // Given
+        let mut transform = MinDuration::new(ms(50));
+        // When
+        transform.feed(Timed::new(ms(0), note_on(60)));
+        transform.feed(Timed::new(ms(1), note_off(60)));
+        let result_1 = transform.feed(Timed::new(ms(10), note_on(60)));
+        let result_2 = transform.feed(Timed::new(ms(11), note_off(60)));
+        let result_3 = transform.flush(ms(59));
+        let result_4 = transform.flush(ms(60));
+        // Then
+        assert_eq!(
+            result_1,
+            vec![
+                Timed::new(ms(10), note_off(60)),
+                Timed::new(ms(10), note_on(60))
+            ]
+        );
+        assert_eq!(result_2, vec![]);
+        assert_eq!(result_3, vec![]);
+        assert_eq!(result_4, vec![Timed::new(ms(60), note_off(60))]);
+    }
+    #[test]
+    fn should_saturate_due_time() {
+        // Given
+        let mut transform = MinDuration::new(Duration::MAX);
+        // When
+        transform.feed(Timed::new(ms(100), note_on(60)));
+        let result_1 = transform.feed(Timed::new(ms(101), note_off(60)));
+        let result_2 = transform.flush(ms(1000));
+        let result_3 = transform.flush(Duration::MAX);
+        // Then
+        assert_eq!(result_1, vec![]);
+        assert_eq!(result_2, vec![]);
+        assert_eq!(result_3, vec![Timed::new(Duration::MAX, note_off(60))]);
+    }
+}
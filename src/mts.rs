@@ -0,0 +1,272 @@
+use crate::bit_util::build_14_bit_value_from_two_7_bit_values;
+use crate::frequency::frequency_from_semitones;
+use crate::{KeyNumber, U7};
+use derive_more::Display;
+
+/// An error which can occur when decoding a MIDI Tuning Standard message.
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[display(fmt = "invalid MIDI Tuning Standard message")]
+pub struct MtsDecodeError(pub(crate) ());
+
+impl std::error::Error for MtsDecodeError {}
+
+/// A table which assigns a frequency to each of the 128 key numbers.
+///
+/// The default table uses 12-tone equal temperament with A4 (key number 69) at 440 Hz.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TuningTable {
+    frequencies: [f64; 128],
+}
+
+impl Default for TuningTable {
+    fn default() -> Self {
+        let mut frequencies = [0.0; 128];
+        for (i, f) in frequencies.iter_mut().enumerate() {
+            *f = frequency_from_semitones(i as f64);
+        }
+        TuningTable { frequencies }
+    }
+}
+
+impl TuningTable {
+    /// Creates a 12-tone equal temperament tuning table.
+    pub fn new() -> TuningTable {
+        Default::default()
+    }
+
+    /// Returns the frequency of the given key number in Hz.
+    pub fn frequency(&self, key_number: KeyNumber) -> f64 {
+        self.frequencies[usize::from(key_number)]
+    }
+
+    /// Sets the frequency of the given key number in Hz.
+    pub fn set_frequency(&mut self, key_number: KeyNumber, frequency: f64) {
+        self.frequencies[usize::from(key_number)] = frequency;
+    }
+
+    /// Returns the frequencies of all key numbers in Hz.
+    pub fn frequencies(&self) -> &[f64; 128] {
+        &self.frequencies
+    }
+}
+
+/// Decoder for MIDI Tuning Standard (MTS) System Exclusive messages.
+///
+/// Supports the Bulk Tuning Dump (non-real-time) and the Single Note Tuning Change (real-time)
+/// messages. Each decoded message is applied to a [`TuningTable`]. The tuning program number
+/// contained in the messages is ignored, which means that all messages affect the same table.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::{KeyNumber, MtsDecoder};
+///
+/// let mut decoder = MtsDecoder::new();
+/// // Tune A4 up by a quarter tone
+/// let msg = [
+///     0xf0, 0x7f, 0x7f, 0x08, 0x02, 0x00, 0x01, 0x45, 0x45, 0x40, 0x00, 0xf7,
+/// ];
+/// assert_eq!(decoder.decode(&msg), Ok(true));
+/// let frequency = decoder.tuning_table().frequency(KeyNumber::new(69));
+/// assert!((frequency - 452.893).abs() < 0.001);
+/// ```
+///
+/// [`TuningTable`]: struct.TuningTable.html
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct MtsDecoder {
+    tuning_table: TuningTable,
+}
+
+const SYSTEM_EXCLUSIVE_START: u8 = 0xf0;
+const SYSTEM_EXCLUSIVE_END: u8 = 0xf7;
+const NON_REAL_TIME: u8 = 0x7e;
+const REAL_TIME: u8 = 0x7f;
+const MIDI_TUNING_STANDARD: u8 = 0x08;
+const BULK_TUNING_DUMP: u8 = 0x01;
+const SINGLE_NOTE_TUNING_CHANGE: u8 = 0x02;
+const BULK_TUNING_DUMP_NAME_LENGTH: usize = 16;
+const FREQUENCY_DATA_LENGTH: usize = 3;
+
+impl MtsDecoder {
+    /// Creates a decoder starting with a 12-tone equal temperament tuning table.
+    pub fn new() -> MtsDecoder {
+        Default::default()
+    }
+
+    /// Creates a decoder starting with the given tuning table.
+    pub fn with_tuning_table(tuning_table: TuningTable) -> MtsDecoder {
+        MtsDecoder { tuning_table }
+    }
+
+    /// Returns the current tuning table.
+    pub fn tuning_table(&self) -> &TuningTable {
+        &self.tuning_table
+    }
+
+    /// Decodes the given complete System Exclusive message (including start and end byte) and
+    /// applies it to the tuning table.
+    ///
+    /// Returns `Ok(false)` if the message is not a supported MIDI Tuning Standard message, in
+    /// which case it is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is a MIDI Tuning Standard message but malformed, e.g. if it
+    /// is truncated or the checksum is wrong. The tuning table is left unchanged in this case.
+    pub fn decode(&mut self, sys_ex: &[u8]) -> Result<bool, MtsDecodeError> {
+        // Start, universal ID, device ID, sub ID 1, sub ID 2
+        if sys_ex.len() < 5
+            || sys_ex[0] != SYSTEM_EXCLUSIVE_START
+            || sys_ex[3] != MIDI_TUNING_STANDARD
+        {
+            return Ok(false);
+        }
+        match (sys_ex[1], sys_ex[4]) {
+            (NON_REAL_TIME, BULK_TUNING_DUMP) => self.decode_bulk_tuning_dump(sys_ex)?,
+            (REAL_TIME, SINGLE_NOTE_TUNING_CHANGE) => {
+                self.decode_single_note_tuning_change(sys_ex)?
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn decode_bulk_tuning_dump(&mut self, sys_ex: &[u8]) -> Result<(), MtsDecodeError> {
+        // Header, program, name, frequency data, checksum, end
+        let frequency_data_start = 6 + BULK_TUNING_DUMP_NAME_LENGTH;
+        let checksum_index = frequency_data_start + 128 * FREQUENCY_DATA_LENGTH;
+        if sys_ex.len() != checksum_index + 2 || sys_ex[checksum_index + 1] != SYSTEM_EXCLUSIVE_END
+        {
+            return Err(MtsDecodeError(()));
+        }
+        let payload = &sys_ex[1..checksum_index];
+        check_data_bytes(payload)?;
+        let checksum = payload.iter().fold(0, |acc, b| acc ^ b) & 0x7f;
+        if checksum != sys_ex[checksum_index] {
+            return Err(MtsDecodeError(()));
+        }
+        let frequency_data = &sys_ex[frequency_data_start..checksum_index];
+        for (i, data) in frequency_data.chunks(FREQUENCY_DATA_LENGTH).enumerate() {
+            if let Some(f) = decode_frequency(data) {
+                self.tuning_table.frequencies[i] = f;
+            }
+        }
+        Ok(())
+    }
+
+    fn decode_single_note_tuning_change(&mut self, sys_ex: &[u8]) -> Result<(), MtsDecodeError> {
+        // Header, program, change count
+        if sys_ex.len() < 8 {
+            return Err(MtsDecodeError(()));
+        }
+        let count = sys_ex[6] as usize;
+        let changes_start = 7;
+        let changes_end = changes_start + count * (1 + FREQUENCY_DATA_LENGTH);
+        if sys_ex.len() != changes_end + 1 || sys_ex[changes_end] != SYSTEM_EXCLUSIVE_END {
+            return Err(MtsDecodeError(()));
+        }
+        check_data_bytes(&sys_ex[1..changes_end])?;
+        for change in sys_ex[changes_start..changes_end].chunks(1 + FREQUENCY_DATA_LENGTH) {
+            if let Some(f) = decode_frequency(&change[1..]) {
+                self.tuning_table.frequencies[change[0] as usize] = f;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_data_bytes(bytes: &[u8]) -> Result<(), MtsDecodeError> {
+    if bytes.iter().any(|b| *b > U7::MAX.get()) {
+        return Err(MtsDecodeError(()));
+    }
+    Ok(())
+}
+
+/// Decodes the 3-byte frequency data format. Returns `None` if it means "no change".
+fn decode_frequency(data: &[u8]) -> Option<f64> {
+    if data == [0x7f, 0x7f, 0x7f] {
+        return None;
+    }
+    let semitone = data[0] as f64;
+    let fraction = build_14_bit_value_from_two_7_bit_values(U7(data[1]), U7(data[2])).get();
+    Some(frequency_from_semitones(
+        semitone + fraction as f64 / 16384.0,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::key_number as kn;
+
+    fn assert_frequency(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 0.001,
+            "expected {} but got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn default_tuning_table() {
+        // Given
+        let table = TuningTable::new();
+        // When
+        // Then
+        assert_frequency(table.frequency(kn(69)), 440.0);
+        assert_frequency(table.frequency(kn(57)), 220.0);
+        assert_frequency(table.frequency(kn(60)), 261.626);
+    }
+
+    #[test]
+    fn decode_single_note_tuning_change() {
+        // Given
+        let mut decoder = MtsDecoder::new();
+        let msg = [
+            0xf0, 0x7f, 0x00, 0x08, 0x02, 0x00, 0x02, 0x45, 0x45, 0x40, 0x00, 0x3c, 0x7f, 0x7f,
+            0x7f, 0xf7,
+        ];
+        // When
+        let result = decoder.decode(&msg);
+        // Then
+        assert_eq!(result, Ok(true));
+        assert_frequency(decoder.tuning_table().frequency(kn(69)), 452.893);
+        assert_frequency(decoder.tuning_table().frequency(kn(60)), 261.626);
+        assert_frequency(decoder.tuning_table().frequency(kn(70)), 466.164);
+    }
+
+    #[test]
+    fn decode_bulk_tuning_dump() {
+        // Given
+        let mut decoder = MtsDecoder::new();
+        let mut msg = vec![0xf0, 0x7e, 0x00, 0x08, 0x01, 0x00];
+        msg.extend_from_slice(b"Everything at A4");
+        for _ in 0..128 {
+            msg.extend_from_slice(&[0x45, 0x00, 0x00]);
+        }
+        let checksum = msg[1..].iter().fold(0, |acc, b| acc ^ b) & 0x7f;
+        msg.push(checksum);
+        msg.push(0xf7);
+        // When
+        let result = decoder.decode(&msg);
+        // Then
+        assert_eq!(result, Ok(true));
+        for f in decoder.tuning_table().frequencies().iter() {
+            assert_frequency(*f, 440.0);
+        }
+    }
+
+    #[test]
+    fn decode_invalid() {
+        // Given
+        let mut decoder = MtsDecoder::new();
+        // When
+        let truncated = decoder.decode(&[0xf0, 0x7f, 0x00, 0x08, 0x02, 0x00, 0x01, 0x45, 0xf7]);
+        let other = decoder.decode(&[0xf0, 0x43, 0x10, 0x4c, 0x00, 0xf7]);
+        // Then
+        assert_eq!(truncated, Err(MtsDecodeError(())));
+        assert_eq!(other, Ok(false));
+        assert_eq!(decoder.tuning_table(), &TuningTable::new());
+    }
+}
@@ -77,6 +77,11 @@ This function panics if `value` is greater than ", $max, "."
             pub const fn get(self) -> $repr {
                 self.0
             }
+
+            /// Returns an iterator over all valid values in ascending order.
+            pub fn all() -> impl Iterator<Item = $name> {
+                (0..=$max).map($name)
+            }
         }
 
         impl std::str::FromStr for $name {
@@ -160,3 +165,40 @@ macro_rules! impl_try_from_primitive_to_newtype {
         }
     };
 }
+
+/// Creates checked, saturating and wrapping arithmetic methods for a newtype.
+macro_rules! impl_arithmetic {
+    ($name: ident) => {
+        impl $name {
+            /// Adds `rhs`, returning `None` if the result would be greater than `MAX`.
+            pub fn checked_add(self, rhs: $name) -> Option<$name> {
+                let sum = self.0 + rhs.0;
+                if sum > Self::MAX.0 {
+                    return None;
+                }
+                Some($name(sum))
+            }
+
+            /// Subtracts `rhs`, returning `None` if the result would be negative.
+            pub fn checked_sub(self, rhs: $name) -> Option<$name> {
+                self.0.checked_sub(rhs.0).map($name)
+            }
+
+            /// Adds `rhs`, clamping the result at `MAX`.
+            pub fn saturating_add(self, rhs: $name) -> $name {
+                self.checked_add(rhs).unwrap_or(Self::MAX)
+            }
+
+            /// Subtracts `rhs`, clamping the result at `MIN`.
+            pub fn saturating_sub(self, rhs: $name) -> $name {
+                self.checked_sub(rhs).unwrap_or(Self::MIN)
+            }
+
+            /// Adds `rhs`, wrapping around at the boundary of this type (not of the underlying
+            /// primitive type).
+            pub fn wrapping_add(self, rhs: $name) -> $name {
+                $name((self.0 + rhs.0) % (Self::MAX.0 + 1))
+            }
+        }
+    };
+}
@@ -0,0 +1,148 @@
+use crate::{controller_numbers, Channel, KeyNumber, ShortMessage, SustainTracker};
+
+/// Tracker for finding out which notes are currently on.
+///
+/// By default, only Note On and Note Off messages are taken into account. Optionally, the damper
+/// pedal (sustain) can be modeled as well, in which case notes released while the pedal is down
+/// are reported as sustained until the pedal is released. See [`SustainTracker`] for details.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, key_number, note_off, note_on};
+/// use helgoboss_midi::NoteStateTracker;
+///
+/// let mut tracker = NoteStateTracker::new();
+/// tracker.set_model_sustain(true);
+/// tracker.feed(&note_on(0, 60, 100));
+/// tracker.feed(&control_change(0, 64, 127));
+/// tracker.feed(&note_off(0, 60, 0));
+/// assert_eq!(tracker.held_notes(channel(0)).count(), 0);
+/// assert_eq!(
+///     tracker.sustained_notes(channel(0)).collect::<Vec<_>>(),
+///     vec![key_number(60)]
+/// );
+/// ```
+///
+/// [`SustainTracker`]: struct.SustainTracker.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct NoteStateTracker {
+    model_sustain: bool,
+    sustain_tracker: SustainTracker,
+}
+
+impl NoteStateTracker {
+    /// Creates a new tracker which doesn't model sustain.
+    pub fn new() -> NoteStateTracker {
+        Default::default()
+    }
+
+    /// Sets whether the damper pedal (sustain) should be modeled.
+    ///
+    /// Disabling it releases all currently sustained notes.
+    pub fn set_model_sustain(&mut self, enabled: bool) {
+        self.model_sustain = enabled;
+        if !enabled {
+            self.sustain_tracker.release_pedals();
+        }
+    }
+
+    /// Feeds the tracker a single short message.
+    pub fn feed(&mut self, msg: &impl ShortMessage) {
+        if !self.model_sustain
+            && msg.controller_number() == Some(controller_numbers::DAMPER_PEDAL_ON_OFF)
+        {
+            return;
+        }
+        self.sustain_tracker.feed(msg);
+    }
+
+    /// Returns the notes which are currently held by a key on the given channel.
+    pub fn held_notes(&self, channel: Channel) -> impl Iterator<Item = KeyNumber> {
+        self.sustain_tracker.held_notes(channel)
+    }
+
+    /// Returns the notes which have been released on the given channel but are still sounding
+    /// because of the damper pedal.
+    ///
+    /// Always empty if sustain is not modeled.
+    pub fn sustained_notes(&self, channel: Channel) -> impl Iterator<Item = KeyNumber> {
+        self.sustain_tracker.sustained_notes(channel)
+    }
+
+    /// Returns the notes which are currently sounding on the given channel, either held or
+    /// sustained.
+    pub fn sounding_notes(&self, channel: Channel) -> impl Iterator<Item = KeyNumber> {
+        self.sustain_tracker.sounding_notes(channel)
+    }
+
+    /// Returns whether the given note is currently sounding, either held or sustained.
+    pub fn is_note_sounding(&self, channel: Channel, key_number: KeyNumber) -> bool {
+        self.sustain_tracker.is_note_sounding(channel, key_number)
+    }
+
+    /// Resets the tracker forgetting all notes.
+    pub fn reset(&mut self) {
+        self.sustain_tracker.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, key_number as kn, u7};
+    use crate::{RawShortMessage, ShortMessageFactory};
+
+    #[test]
+    fn should_release_sustained_note_on_pedal_up() {
+        // Given
+        let mut tracker = NoteStateTracker::new();
+        tracker.set_model_sustain(true);
+        // When
+        tracker.feed(&RawShortMessage::note_on(ch(2), kn(60), u7(100)));
+        // Then
+        assert_eq!(tracker.held_notes(ch(2)).collect::<Vec<_>>(), vec![kn(60)]);
+        assert_eq!(tracker.sustained_notes(ch(2)).count(), 0);
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(2), cn(64), u7(127)));
+        tracker.feed(&RawShortMessage::note_off(ch(2), kn(60), u7(0)));
+        // Then
+        assert_eq!(tracker.held_notes(ch(2)).count(), 0);
+        assert_eq!(
+            tracker.sustained_notes(ch(2)).collect::<Vec<_>>(),
+            vec![kn(60)]
+        );
+        assert!(tracker.is_note_sounding(ch(2), kn(60)));
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(2), cn(64), u7(0)));
+        // Then
+        assert_eq!(tracker.sustained_notes(ch(2)).count(), 0);
+        assert!(!tracker.is_note_sounding(ch(2), kn(60)));
+    }
+
+    #[test]
+    fn should_ignore_pedal_if_sustain_not_modeled() {
+        // Given
+        let mut tracker = NoteStateTracker::new();
+        // When
+        tracker.feed(&RawShortMessage::note_on(ch(2), kn(60), u7(100)));
+        tracker.feed(&RawShortMessage::control_change(ch(2), cn(64), u7(127)));
+        tracker.feed(&RawShortMessage::note_off(ch(2), kn(60), u7(0)));
+        // Then
+        assert_eq!(tracker.sounding_notes(ch(2)).count(), 0);
+    }
+
+    #[test]
+    fn disabling_sustain_releases_sustained_notes() {
+        // Given
+        let mut tracker = NoteStateTracker::new();
+        tracker.set_model_sustain(true);
+        tracker.feed(&RawShortMessage::control_change(ch(2), cn(64), u7(127)));
+        tracker.feed(&RawShortMessage::note_on(ch(2), kn(60), u7(100)));
+        tracker.feed(&RawShortMessage::note_off(ch(2), kn(60), u7(0)));
+        // When
+        tracker.set_model_sustain(false);
+        // Then
+        assert_eq!(tracker.sounding_notes(ch(2)).count(), 0);
+    }
+}
@@ -0,0 +1,81 @@
+//! Contains functions for emergency "stop all sound" messages.
+//!
+//! All Notes Off (controller 123) releases all notes of a channel, but notes held by the sustain
+//! pedal keep sounding. All Sound Off (controller 120) silences the channel immediately,
+//! including release phases and sustained notes. Reset All Controllers (controller 121) makes sure
+//! that a stuck sustain pedal or pitch bend doesn't affect notes played afterwards.
+//!
+//! # Example
+//!
+//! ```
+//! use helgoboss_midi::test_util::{channel, control_change};
+//! use helgoboss_midi::{panic, RawShortMessage};
+//!
+//! let msg: RawShortMessage = panic::all_notes_off(channel(3));
+//! assert_eq!(msg, control_change(3, 123, 0));
+//! let msgs: Vec<RawShortMessage> = panic::full_panic_all_channels().collect();
+//! assert_eq!(msgs.len(), 32);
+//! assert_eq!(msgs[0], control_change(0, 120, 0));
+//! assert_eq!(msgs[1], control_change(0, 121, 0));
+//! ```
+use crate::{Channel, ChannelModeMessage, ShortMessageFactory};
+
+/// Creates an All Notes Off message (controller 123) for the given channel.
+pub fn all_notes_off<T: ShortMessageFactory>(channel: Channel) -> T {
+    ChannelModeMessage::AllNotesOff.to_short_message(channel)
+}
+
+/// Creates an All Sound Off message (controller 120) for the given channel.
+pub fn all_sound_off<T: ShortMessageFactory>(channel: Channel) -> T {
+    ChannelModeMessage::AllSoundOff.to_short_message(channel)
+}
+
+/// Returns All Sound Off followed by Reset All Controllers for each of the 16 channels.
+pub fn full_panic_all_channels<T: ShortMessageFactory>() -> impl Iterator<Item = T> {
+    Channel::all().flat_map(|channel| {
+        IntoIterator::into_iter([
+            all_sound_off(channel),
+            ChannelModeMessage::ResetAllControllers.to_short_message(channel),
+        ])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn};
+    use crate::{controller_numbers, RawShortMessage, ShortMessage};
+
+    #[test]
+    fn single_channel() {
+        // Given
+        // When
+        let notes_off: RawShortMessage = all_notes_off(ch(15));
+        let sound_off: RawShortMessage = all_sound_off(ch(15));
+        // Then
+        assert_eq!(notes_off.controller_number(), Some(cn(123)));
+        assert_eq!(sound_off.controller_number(), Some(cn(120)));
+        assert_eq!(notes_off.channel(), Some(ch(15)));
+    }
+
+    #[test]
+    fn all_channels() {
+        // Given
+        // When
+        let msgs: Vec<RawShortMessage> = full_panic_all_channels().collect();
+        // Then
+        assert_eq!(msgs.len(), 32);
+        for (i, pair) in msgs.chunks(2).enumerate() {
+            assert_eq!(pair[0].channel(), Some(ch(i as u8)));
+            assert_eq!(
+                pair[0].controller_number(),
+                Some(controller_numbers::ALL_SOUND_OFF)
+            );
+            assert_eq!(pair[1].channel(), Some(ch(i as u8)));
+            assert_eq!(
+                pair[1].controller_number(),
+                Some(controller_numbers::RESET_ALL_CONTROLLERS)
+            );
+        }
+    }
+}
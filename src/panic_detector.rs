@@ -0,0 +1,162 @@
+use crate::{controller_numbers, ShortMessage};
+use std::time::Duration;
+
+/// Event emitted by [`PanicDetector`] when a panic request has been detected.
+///
+/// [`PanicDetector`]: struct.PanicDetector.html
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PanicRequested;
+
+/// Detector for "panic" requests, that is an All Notes Off burst on many channels.
+///
+/// Panic buttons usually send All Notes Off (controller 123) on all 16 channels in a row. This
+/// detector emits a single [`PanicRequested`] event as soon as All Notes Off has been received on
+/// at least `threshold` different channels within `window`, so that client code can react once
+/// instead of 16 times. After that, the detector starts over.
+///
+/// By default, the window is 100 ms and the threshold is 16 channels.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::control_change;
+/// use helgoboss_midi::{PanicDetector, PanicRequested};
+/// use std::time::Duration;
+///
+/// let mut detector = PanicDetector::new();
+/// let events: Vec<_> = (0..16)
+///     .filter_map(|ch| {
+///         let time = Duration::from_millis(ch as u64);
+///         detector.feed(time, &control_change(ch, 123, 0))
+///     })
+///     .collect();
+/// assert_eq!(events, vec![PanicRequested]);
+/// ```
+///
+/// [`PanicRequested`]: struct.PanicRequested.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PanicDetector {
+    window: Duration,
+    threshold: usize,
+    all_notes_off_times: [Option<Duration>; 16],
+}
+
+impl Default for PanicDetector {
+    fn default() -> Self {
+        PanicDetector {
+            window: Duration::from_millis(100),
+            threshold: 16,
+            all_notes_off_times: Default::default(),
+        }
+    }
+}
+
+impl PanicDetector {
+    /// Creates a new detector with a window of 100 ms and a threshold of 16 channels.
+    pub fn new() -> PanicDetector {
+        Default::default()
+    }
+
+    /// Sets the maximum time span in which the All Notes Off messages must arrive.
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Sets the number of different channels on which All Notes Off must arrive.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `threshold` is 0 or greater than 16.
+    pub fn set_threshold(&mut self, threshold: usize) {
+        assert!(
+            (1..=16).contains(&threshold),
+            "threshold must be between 1 and 16"
+        );
+        self.threshold = threshold;
+    }
+
+    /// Feeds the detector a single short message which occurred at the given time.
+    ///
+    /// Returns an event if this message completes a panic request. Times must not decrease.
+    pub fn feed(&mut self, time: Duration, msg: &impl ShortMessage) -> Option<PanicRequested> {
+        let channel = msg.channel()?;
+        if msg.controller_number()? != controller_numbers::ALL_NOTES_OFF {
+            return None;
+        }
+        self.all_notes_off_times[usize::from(channel)] = Some(time);
+        let window_start = time.checked_sub(self.window).unwrap_or_default();
+        let channel_count = self
+            .all_notes_off_times
+            .iter()
+            .filter(|t| matches!(t, Some(t) if *t >= window_start))
+            .count();
+        if channel_count < self.threshold {
+            return None;
+        }
+        self.reset();
+        Some(PanicRequested)
+    }
+
+    /// Resets the detector forgetting all received All Notes Off messages.
+    pub fn reset(&mut self) {
+        self.all_notes_off_times = Default::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u7};
+    use crate::{RawShortMessage, ShortMessageFactory};
+
+    fn all_notes_off(channel: u8) -> RawShortMessage {
+        RawShortMessage::control_change(ch(channel), cn(123), u7(0))
+    }
+
+    #[test]
+    fn should_detect_all_notes_off_on_all_channels_within_window() {
+        // Given
+        let mut detector = PanicDetector::new();
+        // When
+        let events: Vec<_> = (0..16)
+            .map(|i| detector.feed(Duration::from_millis(1000 + i as u64), &all_notes_off(i)))
+            .collect();
+        // Then
+        assert!(events[..15].iter().all(|e| e.is_none()));
+        assert_eq!(events[15], Some(PanicRequested));
+    }
+
+    #[test]
+    fn should_not_detect_burst_exceeding_window() {
+        // Given
+        let mut detector = PanicDetector::new();
+        // When
+        let events: Vec<_> = (0..16)
+            .map(|i| detector.feed(Duration::from_millis(i as u64 * 10), &all_notes_off(i)))
+            .collect();
+        // Then
+        assert!(events.iter().all(|e| e.is_none()));
+    }
+
+    #[test]
+    fn should_respect_threshold_and_start_over() {
+        // Given
+        let mut detector = PanicDetector::new();
+        detector.set_threshold(2);
+        let time = Duration::from_millis(0);
+        // When
+        let result_1 = detector.feed(time, &all_notes_off(0));
+        let result_2 = detector.feed(time, &all_notes_off(0));
+        let result_3 = detector.feed(time, &all_notes_off(1));
+        let result_4 = detector.feed(time, &all_notes_off(2));
+        let result_5 = detector.feed(time, &RawShortMessage::control_change(ch(3), cn(7), u7(0)));
+        let result_6 = detector.feed(time, &all_notes_off(3));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, Some(PanicRequested));
+        assert_eq!(result_4, None);
+        assert_eq!(result_5, None);
+        assert_eq!(result_6, Some(PanicRequested));
+    }
+}
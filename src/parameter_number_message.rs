@@ -1,9 +1,13 @@
-use crate::{
-    extract_high_7_bit_value_from_14_bit_value, extract_low_7_bit_value_from_14_bit_value, Channel,
-    ShortMessageFactory, U14, U7,
+use crate::bit_util::{
+    extract_high_7_bit_value_from_14_bit_value, extract_low_7_bit_value_from_14_bit_value,
 };
+use crate::rpn::StandardRpn;
+use crate::{Channel, FromBytesError, RawShortMessage, ShortMessageFactory, U14, U7};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
 
 /// A MIDI Parameter Number message, either registered (RPN) or non-registered (NRPN).
 ///
@@ -42,12 +46,110 @@ use serde::{Deserialize, Serialize};
 /// [`ParameterNumberMessageScanner`]: struct.ParameterNumberMessageScanner.html
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "SerializedParameterNumberMessage"),
+    serde(into = "SerializedParameterNumberMessage")
+)]
 pub struct ParameterNumberMessage {
     channel: Channel,
     number: U14,
     value: U14,
     is_registered: bool,
+    kind: ParameterNumberMessageKind,
+}
+
+/// Options for [`ParameterNumberMessage::to_short_messages_with`].
+///
+/// The default options produce the same messages as [`ParameterNumberMessage::to_short_messages`].
+///
+/// [`ParameterNumberMessage::to_short_messages_with`]:
+/// struct.ParameterNumberMessage.html#method.to_short_messages_with
+/// [`ParameterNumberMessage::to_short_messages`]:
+/// struct.ParameterNumberMessage.html#method.to_short_messages
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PnMessageEmitOptions {
+    /// If enabled, the Data Entry MSB (controller 6) is sent before the Data Entry LSB (controller
+    /// 38) for 14-bit values.
+    ///
+    /// That's the order in which the MIDI specification lists the two messages. It's expected by
+    /// devices which apply the value as soon as the LSB arrives, e.g. synthesizers from
+    /// Sequential (formerly Dave Smith Instruments), whose manuals document 14-bit NRPN as
+    /// controller 99, 98, 6, 38. Receivers which apply the value on the MSB, such as
+    /// [`ParameterNumberMessageScanner`] in its default configuration, would emit a 7-bit
+    /// message first. Disabled by default.
+    ///
+    /// [`ParameterNumberMessageScanner`]: struct.ParameterNumberMessageScanner.html
+    pub value_msb_first: bool,
+}
+
+/// The kind of a [`ParameterNumberMessage`], which determines how its value is transmitted.
+///
+/// [`ParameterNumberMessage`]: struct.ParameterNumberMessage.html
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParameterNumberMessageKind {
+    /// Absolute 7-bit value transmitted via Data Entry MSB (controller 6).
+    Absolute7Bit,
+    /// Absolute 14-bit value transmitted via Data Entry LSB (controller 38) and MSB (controller
+    /// 6).
+    Absolute14Bit,
+    /// Relative change transmitted via Data Increment (controller 96).
+    Increment,
+    /// Relative change transmitted via Data Decrement (controller 97).
+    Decrement,
+}
+
+/// Serialized form of [`ParameterNumberMessage`].
+///
+/// Older versions didn't have `kind` but only `is_14_bit`. It's still written so that older
+/// versions can read absolute messages, and it's used for deserializing data without `kind`.
+///
+/// [`ParameterNumberMessage`]: struct.ParameterNumberMessage.html
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SerializedParameterNumberMessage {
+    channel: Channel,
+    number: U14,
+    value: U14,
+    is_registered: bool,
+    #[serde(default)]
     is_14_bit: bool,
+    #[serde(default)]
+    kind: Option<ParameterNumberMessageKind>,
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedParameterNumberMessage> for ParameterNumberMessage {
+    fn from(msg: SerializedParameterNumberMessage) -> Self {
+        let kind = msg.kind.unwrap_or(if msg.is_14_bit {
+            ParameterNumberMessageKind::Absolute14Bit
+        } else {
+            ParameterNumberMessageKind::Absolute7Bit
+        });
+        ParameterNumberMessage {
+            channel: msg.channel,
+            number: msg.number,
+            value: msg.value,
+            is_registered: msg.is_registered,
+            kind,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ParameterNumberMessage> for SerializedParameterNumberMessage {
+    fn from(msg: ParameterNumberMessage) -> Self {
+        SerializedParameterNumberMessage {
+            channel: msg.channel,
+            number: msg.number,
+            value: msg.value,
+            is_registered: msg.is_registered,
+            is_14_bit: msg.is_14_bit(),
+            kind: Some(msg.kind),
+        }
+    }
 }
 
 impl ParameterNumberMessage {
@@ -79,6 +181,89 @@ impl ParameterNumberMessage {
         Self::fourteen_bit(channel, number, value, true)
     }
 
+    /// Creates an RPN message with a 14-bit value for one of the standardized parameter numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::rpn::StandardRpn;
+    /// use helgoboss_midi::test_util::{channel, rpn_14_bit, u7};
+    /// use helgoboss_midi::{ParameterNumberMessage, U14};
+    ///
+    /// // 12 semitones, 0 cents
+    /// let value = U14::join(u7(12), u7(0));
+    /// let rpn = StandardRpn::PitchBendSensitivity;
+    /// let msg = ParameterNumberMessage::registered_standard(channel(0), rpn, value);
+    /// assert_eq!(msg, rpn_14_bit(0, 0, 1536));
+    /// ```
+    pub fn registered_standard(
+        channel: Channel,
+        rpn: StandardRpn,
+        value: U14,
+    ) -> ParameterNumberMessage {
+        Self::registered_14_bit(channel, rpn.number(), value)
+    }
+
+    /// Creates an (N)RPN message which increments the value of the given parameter number,
+    /// transmitted via Data Increment (controller 96).
+    ///
+    /// The amount is sent as data byte. According to the MIDI specification, it's usually 0,
+    /// which means receivers just step by one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::control_change;
+    /// use helgoboss_midi::{
+    ///     Channel, ParameterNumberMessage, ParameterNumberMessageKind,
+    ///     ParameterNumberMessageScanner, RawShortMessage, U14, U7,
+    /// };
+    ///
+    /// let msg =
+    ///     ParameterNumberMessage::increment(Channel::new(0), U14::new(421), U7::new(0), false);
+    /// assert_eq!(msg.kind(), ParameterNumberMessageKind::Increment);
+    /// let short_messages: [Option<RawShortMessage>; 4] = msg.to_short_messages();
+    /// assert_eq!(
+    ///     short_messages,
+    ///     [
+    ///         Some(control_change(0, 99, 3)),
+    ///         Some(control_change(0, 98, 37)),
+    ///         Some(control_change(0, 96, 0)),
+    ///         None,
+    ///     ]
+    /// );
+    /// let mut scanner = ParameterNumberMessageScanner::new();
+    /// let results: Vec<_> = short_messages
+    ///     .iter()
+    ///     .flatten()
+    ///     .filter_map(|m| scanner.feed(m))
+    ///     .collect();
+    /// assert_eq!(results, vec![msg]);
+    /// ```
+    pub fn increment(
+        channel: Channel,
+        number: U14,
+        amount: U7,
+        is_registered: bool,
+    ) -> ParameterNumberMessage {
+        Self::relative(channel, number, amount, is_registered, true)
+    }
+
+    /// Creates an (N)RPN message which decrements the value of the given parameter number,
+    /// transmitted via Data Decrement (controller 97).
+    ///
+    /// See [`increment`] for details about the amount.
+    ///
+    /// [`increment`]: #method.increment
+    pub fn decrement(
+        channel: Channel,
+        number: U14,
+        amount: U7,
+        is_registered: bool,
+    ) -> ParameterNumberMessage {
+        Self::relative(channel, number, amount, is_registered, false)
+    }
+
     fn seven_bit(
         channel: Channel,
         number: U14,
@@ -90,7 +275,7 @@ impl ParameterNumberMessage {
             number,
             value: value.into(),
             is_registered,
-            is_14_bit: false,
+            kind: ParameterNumberMessageKind::Absolute7Bit,
         }
     }
 
@@ -105,7 +290,27 @@ impl ParameterNumberMessage {
             number,
             value,
             is_registered,
-            is_14_bit: true,
+            kind: ParameterNumberMessageKind::Absolute14Bit,
+        }
+    }
+
+    fn relative(
+        channel: Channel,
+        number: U14,
+        amount: U7,
+        is_registered: bool,
+        is_increment: bool,
+    ) -> ParameterNumberMessage {
+        ParameterNumberMessage {
+            channel,
+            number,
+            value: amount.into(),
+            is_registered,
+            kind: if is_increment {
+                ParameterNumberMessageKind::Increment
+            } else {
+                ParameterNumberMessageKind::Decrement
+            },
         }
     }
 
@@ -121,14 +326,69 @@ impl ParameterNumberMessage {
 
     /// Returns the value of this message.
     ///
-    /// If it's just a 7-bit message, the value is <= 127.
+    /// If it's just a 7-bit message, the value is <= 127. If it's a Data Increment or Data
+    /// Decrement message, this is the data byte of that message, that is the amount (also <= 127).
     pub fn value(&self) -> U14 {
         self.value
     }
 
-    /// Returns `true` if this message has a 14-bit value and `false` if only a 7-bit value.
+    /// Returns the most significant 7 bits of the value, that is the data byte of the Data Entry
+    /// MSB message.
+    ///
+    /// If it's just a 7-bit message, this is the complete value. If it's a Data Increment or Data
+    /// Decrement message, this is the amount.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{nrpn, nrpn_14_bit, u7};
+    ///
+    /// let msg = nrpn(0, 421, 126);
+    /// assert_eq!(msg.value_msb(), u7(126));
+    /// assert_eq!(msg.value_lsb(), None);
+    /// assert_eq!(msg.as_7_bit_value(), Some(u7(126)));
+    /// let msg = nrpn_14_bit(0, 421, 1057);
+    /// assert_eq!(msg.value_msb(), u7(8));
+    /// assert_eq!(msg.value_lsb(), Some(u7(33)));
+    /// assert_eq!(msg.as_7_bit_value(), None);
+    /// ```
+    pub fn value_msb(&self) -> U7 {
+        if self.is_14_bit() {
+            extract_high_7_bit_value_from_14_bit_value(self.value)
+        } else {
+            U7(self.value.get() as u8)
+        }
+    }
+
+    /// Returns the least significant 7 bits of the value, that is the data byte of the Data Entry
+    /// LSB message, or `None` if this message doesn't have a 14-bit value.
+    pub fn value_lsb(&self) -> Option<U7> {
+        if self.is_14_bit() {
+            Some(extract_low_7_bit_value_from_14_bit_value(self.value))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value as 7-bit value or `None` if this message has a 14-bit value.
+    ///
+    /// If it's a Data Increment or Data Decrement message, this is the amount.
+    pub fn as_7_bit_value(&self) -> Option<U7> {
+        if self.is_14_bit() {
+            None
+        } else {
+            Some(U7(self.value.get() as u8))
+        }
+    }
+
+    /// Returns the kind of this message.
+    pub fn kind(&self) -> ParameterNumberMessageKind {
+        self.kind
+    }
+
+    /// Returns `true` if this message has an absolute 14-bit value and `false` otherwise.
     pub fn is_14_bit(&self) -> bool {
-        self.is_14_bit
+        self.kind == ParameterNumberMessageKind::Absolute14Bit
     }
 
     /// Returns whether this message uses a registered parameter number.
@@ -140,53 +400,425 @@ impl ParameterNumberMessage {
     /// a row in order to encode this (N)RPN message.
     ///
     /// If this message has a 14-bit value, all returned messages are `Some`. If it has a 7-bit
-    /// value only, the last one is `None`.
+    /// value only or is a Data Increment or Data Decrement message, the last one is `None`.
+    ///
+    /// The value LSB is sent before the value MSB. Use [`to_short_messages_with`] for other
+    /// orders.
+    ///
+    /// [`to_short_messages_with`]: #method.to_short_messages_with
     pub fn to_short_messages<T: ShortMessageFactory>(&self) -> [Option<T>; 4] {
+        self.to_short_messages_with(Default::default())
+    }
+
+    /// Translates this message into up to 4 short Control Change messages using the given
+    /// options.
+    ///
+    /// Works like [`to_short_messages`] but allows adjusting the emitted messages to the
+    /// expectations of the receiving device. All `Some` messages come first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, nrpn_14_bit};
+    /// use helgoboss_midi::{PnMessageEmitOptions, RawShortMessage};
+    ///
+    /// let options = PnMessageEmitOptions {
+    ///     value_msb_first: true,
+    /// };
+    /// let short_messages: [Option<RawShortMessage>; 4] =
+    ///     nrpn_14_bit(0, 256, 15000).to_short_messages_with(options);
+    /// assert_eq!(
+    ///     short_messages,
+    ///     [
+    ///         Some(control_change(0, 99, 2)),
+    ///         Some(control_change(0, 98, 0)),
+    ///         Some(control_change(0, 6, 117)),
+    ///         Some(control_change(0, 38, 24)),
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// [`to_short_messages`]: #method.to_short_messages
+    pub fn to_short_messages_with<T: ShortMessageFactory>(
+        &self,
+        options: PnMessageEmitOptions,
+    ) -> [Option<T>; 4] {
         use crate::controller_numbers::*;
+        use ParameterNumberMessageKind::*;
         let mut messages = [None, None, None, None];
         let mut i = 0;
+        let mut push = |controller_number, value| {
+            messages[i] = Some(T::control_change(self.channel, controller_number, value));
+            i += 1;
+        };
         // Number MSB
-        messages[i] = Some(T::control_change(
-            self.channel,
+        push(
             if self.is_registered {
                 REGISTERED_PARAMETER_NUMBER_MSB
             } else {
                 NON_REGISTERED_PARAMETER_NUMBER_MSB
             },
             extract_high_7_bit_value_from_14_bit_value(self.number),
-        ));
-        i += 1;
+        );
         // Number LSB
-        messages[i] = Some(T::control_change(
-            self.channel,
+        push(
             if self.is_registered {
                 REGISTERED_PARAMETER_NUMBER_LSB
             } else {
                 NON_REGISTERED_PARAMETER_NUMBER_LSB
             },
             extract_low_7_bit_value_from_14_bit_value(self.number),
-        ));
-        i += 1;
-        // Value LSB
-        if self.is_14_bit {
-            messages[i] = Some(T::control_change(
-                self.channel,
-                DATA_ENTRY_MSB_LSB,
-                extract_low_7_bit_value_from_14_bit_value(self.value),
+        );
+        // Value or relative change
+        match self.kind {
+            Absolute7Bit => push(DATA_ENTRY_MSB, self.value_msb()),
+            Absolute14Bit => {
+                let (value_msb, value_lsb) = self.value.split();
+                if options.value_msb_first {
+                    push(DATA_ENTRY_MSB, value_msb);
+                    push(DATA_ENTRY_MSB_LSB, value_lsb);
+                } else {
+                    push(DATA_ENTRY_MSB_LSB, value_lsb);
+                    push(DATA_ENTRY_MSB, value_msb);
+                }
+            }
+            Increment => push(DATA_INCREMENT, self.value_msb()),
+            Decrement => push(DATA_DECREMENT, self.value_msb()),
+        };
+        messages
+    }
+
+    /// Translates this message into 4 short Control Change messages, sending a 7-bit value as
+    /// 14-bit value with an LSB of 0.
+    ///
+    /// Useful for devices which only accept 14-bit (N)RPN, that is, which expect both Data Entry
+    /// LSB (controller 38) and Data Entry MSB (controller 6). The 7-bit value ends up in the MSB.
+    /// Messages which already have a 14-bit value as well as increments and decrements are
+    /// translated exactly like in [`to_short_messages`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, nrpn};
+    /// use helgoboss_midi::RawShortMessage;
+    ///
+    /// let short_messages: [Option<RawShortMessage>; 4] =
+    ///     nrpn(0, 420, 64).to_short_messages_as_14_bit();
+    /// assert_eq!(
+    ///     short_messages,
+    ///     [
+    ///         Some(control_change(0, 99, 3)),
+    ///         Some(control_change(0, 98, 36)),
+    ///         Some(control_change(0, 38, 0)),
+    ///         Some(control_change(0, 6, 64)),
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// [`to_short_messages`]: #method.to_short_messages
+    pub fn to_short_messages_as_14_bit<T: ShortMessageFactory>(&self) -> [Option<T>; 4] {
+        if self.kind != ParameterNumberMessageKind::Absolute7Bit {
+            return self.to_short_messages();
+        }
+        let value = U14::join(self.value_msb(), U7::MIN);
+        Self::fourteen_bit(self.channel, self.number, value, self.is_registered).to_short_messages()
+    }
+
+    /// Returns an iterator over the short messages which encode this message on each of the 16
+    /// channels, ignoring the channel of this message.
+    ///
+    /// Useful for sending the same (N)RPN to all parts of a multi-timbral device. The messages
+    /// are ordered channel by channel, that is, first all messages for channel 0, then all for
+    /// channel 1 and so on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, rpn};
+    /// use helgoboss_midi::RawShortMessage;
+    ///
+    /// let msgs: Vec<RawShortMessage> = rpn(0, 0, 2).to_short_messages_all_channels().collect();
+    /// assert_eq!(msgs.len(), 48);
+    /// assert_eq!(msgs[3], control_change(1, 101, 0));
+    /// ```
+    pub fn to_short_messages_all_channels<T: ShortMessageFactory>(
+        &self,
+    ) -> impl Iterator<Item = T> {
+        let msg = *self;
+        Channel::all().flat_map(move |channel| {
+            let msg_on_channel = ParameterNumberMessage { channel, ..msg };
+            IntoIterator::into_iter(msg_on_channel.to_short_messages()).flatten()
+        })
+    }
+
+    /// Returns an iterator over the short messages which encode this message, followed by the 2
+    /// short messages which make up the RPN Null sequence (RPN 127/127).
+    ///
+    /// Sending the RPN Null sequence deselects the parameter number, so that subsequent Data Entry
+    /// messages can't accidentally change this parameter. That makes this the safest choice for
+    /// sending to hardware. Use [`to_short_messages`] if the parameter number should stay
+    /// selected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, rpn_14_bit};
+    ///
+    /// let msgs: Vec<_> = rpn_14_bit(0, 420, 15000).short_messages_with_null().collect();
+    /// assert_eq!(msgs.len(), 6);
+    /// assert_eq!(
+    ///     &msgs[4..],
+    ///     &[control_change(0, 101, 127), control_change(0, 100, 127)]
+    /// );
+    /// ```
+    ///
+    /// [`to_short_messages`]: #method.to_short_messages
+    pub fn short_messages_with_null(&self) -> impl Iterator<Item = RawShortMessage> {
+        use crate::controller_numbers::*;
+        let null = [
+            RawShortMessage::control_change(self.channel, REGISTERED_PARAMETER_NUMBER_MSB, U7::MAX),
+            RawShortMessage::control_change(self.channel, REGISTERED_PARAMETER_NUMBER_LSB, U7::MAX),
+        ];
+        IntoIterator::into_iter(self.to_short_messages::<RawShortMessage>())
+            .flatten()
+            .chain(IntoIterator::into_iter(null))
+    }
+
+    /// Encodes this message into 5 bytes, which is more compact than the serde representation.
+    ///
+    /// The layout is as follows:
+    ///
+    /// | Byte  | Bits  | Content                                                     |
+    /// |-------|-------|-------------------------------------------------------------|
+    /// | 0     | 0 - 3 | Channel                                                     |
+    /// | 0     | 4     | 1 if registered, 0 if non-registered                        |
+    /// | 0     | 5 - 6 | Kind: 0 = 7-bit, 1 = 14-bit, 2 = increment, 3 = decrement   |
+    /// | 0     | 7     | Always 0                                                    |
+    /// | 1 - 2 | all   | Parameter number (big-endian, upper 2 bits always 0)        |
+    /// | 3 - 4 | all   | Value or amount (big-endian, upper 2 bits always 0)         |
+    ///
+    /// 4 bytes are not enough because channel, flags, kind, number and value take 35 bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::nrpn_14_bit;
+    /// use helgoboss_midi::ParameterNumberMessage;
+    ///
+    /// let msg = nrpn_14_bit(3, 1234, 1057);
+    /// let bytes = msg.to_bytes();
+    /// assert_eq!(bytes, [0x23, 0x04, 0xd2, 0x04, 0x21]);
+    /// assert_eq!(ParameterNumberMessage::from_bytes(bytes), Ok(msg));
+    /// ```
+    pub fn to_bytes(&self) -> [u8; 5] {
+        use ParameterNumberMessageKind::*;
+        let kind: u8 = match self.kind {
+            Absolute7Bit => 0,
+            Absolute14Bit => 1,
+            Increment => 2,
+            Decrement => 3,
+        };
+        let header = self.channel.get() | (self.is_registered as u8) << 4 | kind << 5;
+        let [number_high, number_low] = self.number.get().to_be_bytes();
+        let [value_high, value_low] = self.value.get().to_be_bytes();
+        [header, number_high, number_low, value_high, value_low]
+    }
+
+    /// Decodes a message which has been encoded with [`to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes don't follow the layout described in [`to_bytes`] or if a
+    /// message which is not 14-bit has a value greater than 127.
+    ///
+    /// [`to_bytes`]: #method.to_bytes
+    pub fn from_bytes(bytes: [u8; 5]) -> Result<ParameterNumberMessage, FromBytesError> {
+        let [header, number_high, number_low, value_high, value_low] = bytes;
+        if header & 0x80 != 0 {
+            return Err(FromBytesError(()));
+        }
+        let channel = Channel(header & 0x0f);
+        let is_registered = header & 0x10 != 0;
+        let number = U14::try_from(u16::from_be_bytes([number_high, number_low]))
+            .map_err(|_| FromBytesError(()))?;
+        let value = u16::from_be_bytes([value_high, value_low]);
+        let msg = match (header >> 5) & 0x03 {
+            1 => {
+                let value = U14::try_from(value).map_err(|_| FromBytesError(()))?;
+                Self::fourteen_bit(channel, number, value, is_registered)
+            }
+            kind => {
+                let value = U7::try_from(value).map_err(|_| FromBytesError(()))?;
+                match kind {
+                    0 => Self::seven_bit(channel, number, value, is_registered),
+                    2 => Self::relative(channel, number, value, is_registered, true),
+                    _ => Self::relative(channel, number, value, is_registered, false),
+                }
+            }
+        };
+        Ok(msg)
+    }
+
+    /// Returns a human-readable description of this message, e.g. for displaying it in a MIDI
+    /// monitor.
+    ///
+    /// Well-known registered parameter numbers are described by name. The channel is displayed
+    /// as is, that is zero-based.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{nrpn, rpn_14_bit};
+    ///
+    /// assert_eq!(
+    ///     rpn_14_bit(1, 0, 512).describe(),
+    ///     "RPN Pitch Bend Sensitivity (0) = 512 [14-bit] ch 1"
+    /// );
+    /// assert_eq!(nrpn(3, 1234, 64).describe(), "NRPN 1234 = 64 [7-bit] ch 3");
+    /// ```
+    pub fn describe(&self) -> String {
+        use ParameterNumberMessageKind::*;
+        let value = match self.kind {
+            Absolute7Bit => format!("= {} [7-bit]", self.value),
+            Absolute14Bit => format!("= {} [14-bit]", self.value),
+            Increment => format!("+= {}", self.value),
+            Decrement => format!("-= {}", self.value),
+        };
+        let name = if self.is_registered {
+            registered_parameter_name(self.number)
+        } else {
+            None
+        };
+        let prefix = if self.is_registered { "RPN" } else { "NRPN" };
+        match name {
+            Some(name) => format!(
+                "{} {} ({}) {} ch {}",
+                prefix, name, self.number, value, self.channel
+            ),
+            None => format!("{} {} {} ch {}", prefix, self.number, value, self.channel),
+        }
+    }
+}
+
+fn registered_parameter_name(number: U14) -> Option<&'static str> {
+    let name = match number.get() {
+        0x0000 => "Pitch Bend Sensitivity",
+        0x0001 => "Channel Fine Tuning",
+        0x0002 => "Channel Coarse Tuning",
+        0x0003 => "Tuning Program Change",
+        0x0004 => "Tuning Bank Select",
+        0x0005 => "Modulation Depth Range",
+        0x0006 => "MPE Configuration",
+        0x3FFF => "Null",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Formats the message in a compact form which can be parsed back via [`FromStr`].
+///
+/// Absolute 7-bit messages look like `NRPN7[ch=2, num=421]=126`, absolute 14-bit messages like
+/// `RPN[ch=0, num=420]=15000 (14-bit)`. Increments and decrements use `+=` and `-=`, e.g.
+/// `RPN[ch=0, num=420]+=3`. Channels are zero-based. Use [`describe`] for a more verbose
+/// description.
+///
+/// [`FromStr`]: #impl-FromStr
+/// [`describe`]: #method.describe
+impl fmt::Display for ParameterNumberMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ParameterNumberMessageKind::*;
+        let prefix = if self.is_registered { "RPN" } else { "NRPN" };
+        let bits = if self.kind == Absolute7Bit { "7" } else { "" };
+        write!(
+            f,
+            "{}{}[ch={}, num={}]",
+            prefix, bits, self.channel, self.number
+        )?;
+        match self.kind {
+            Absolute7Bit => write!(f, "={}", self.value),
+            Absolute14Bit => write!(f, "={} (14-bit)", self.value),
+            Increment => write!(f, "+={}", self.value),
+            Decrement => write!(f, "-={}", self.value),
+        }
+    }
+}
+
+/// An error which can occur when parsing a string to a [`ParameterNumberMessage`].
+///
+/// [`ParameterNumberMessage`]: struct.ParameterNumberMessage.html
+#[derive(Clone, Eq, PartialEq, Debug, derive_more::Display)]
+#[display(fmt = "parsing string to parameter number message failed")]
+pub struct ParseParameterNumberMessageError(pub(crate) ());
+
+impl std::error::Error for ParseParameterNumberMessageError {}
+
+/// Parses the format produced by the [`Display`] implementation.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::nrpn;
+/// use helgoboss_midi::ParameterNumberMessage;
+///
+/// let msg: ParameterNumberMessage = "NRPN7[ch=2, num=421]=126".parse().unwrap();
+/// assert_eq!(msg, nrpn(2, 421, 126));
+/// assert!("NRPN7[ch=2, num=421]=128".parse::<ParameterNumberMessage>().is_err());
+/// ```
+///
+/// [`Display`]: #impl-Display
+impl FromStr for ParameterNumberMessage {
+    type Err = ParseParameterNumberMessageError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let error = || ParseParameterNumberMessageError(());
+        let (is_registered, rest) = if let Some(rest) = source.strip_prefix("NRPN") {
+            (false, rest)
+        } else if let Some(rest) = source.strip_prefix("RPN") {
+            (true, rest)
+        } else {
+            return Err(error());
+        };
+        let (is_7_bit, rest) = match rest.strip_prefix('7') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let rest = rest.strip_prefix("[ch=").ok_or_else(error)?;
+        let (channel, rest) = rest.split_once(", num=").ok_or_else(error)?;
+        let (number, rest) = rest.split_once(']').ok_or_else(error)?;
+        let channel: Channel = channel.parse().map_err(|_| error())?;
+        let number: U14 = number.parse().map_err(|_| error())?;
+        let parse_u7 = |value: &str| value.parse::<U7>().map_err(|_| error());
+        if is_7_bit {
+            let value = rest.strip_prefix('=').ok_or_else(error)?;
+            return Ok(Self::seven_bit(
+                channel,
+                number,
+                parse_u7(value)?,
+                is_registered,
             ));
-            i += 1;
         }
-        // Value MSB
-        messages[i] = Some(T::control_change(
-            self.channel,
-            DATA_ENTRY_MSB,
-            if self.is_14_bit {
-                extract_high_7_bit_value_from_14_bit_value(self.value)
-            } else {
-                U7(self.value.get() as u8)
-            },
-        ));
-        messages
+        if let Some(amount) = rest.strip_prefix("+=") {
+            return Ok(Self::increment(
+                channel,
+                number,
+                parse_u7(amount)?,
+                is_registered,
+            ));
+        }
+        if let Some(amount) = rest.strip_prefix("-=") {
+            return Ok(Self::decrement(
+                channel,
+                number,
+                parse_u7(amount)?,
+                is_registered,
+            ));
+        }
+        let value = rest
+            .strip_prefix('=')
+            .and_then(|rest| rest.strip_suffix(" (14-bit)"))
+            .ok_or_else(error)?;
+        let value: U14 = value.parse().map_err(|_| error())?;
+        Ok(Self::fourteen_bit(channel, number, value, is_registered))
     }
 }
 
@@ -200,7 +832,9 @@ impl<T: ShortMessageFactory> From<ParameterNumberMessage> for [Option<T>; 4] {
 mod tests {
     use super::*;
     use crate::test_util::{channel as ch, controller_number as cn, u14, u7};
-    use crate::RawShortMessage;
+    use crate::ShortMessage;
+    #[cfg(feature = "serde")]
+    use serde_json::json;
 
     #[test]
     fn parameter_number_messages_14_bit() {
@@ -253,4 +887,353 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn parameter_number_messages_relative() {
+        // Given
+        let inc = ParameterNumberMessage::increment(ch(2), u14(421), u7(0), false);
+        let dec = ParameterNumberMessage::decrement(ch(2), u14(421), u7(3), true);
+        // When
+        // Then
+        assert_eq!(inc.kind(), ParameterNumberMessageKind::Increment);
+        assert_eq!(inc.value(), u14(0));
+        assert!(!inc.is_14_bit());
+        assert_eq!(dec.kind(), ParameterNumberMessageKind::Decrement);
+        assert_eq!(dec.value(), u14(3));
+        let inc_msgs: [Option<RawShortMessage>; 4] = inc.to_short_messages();
+        assert_eq!(
+            inc_msgs,
+            [
+                Some(RawShortMessage::control_change(ch(2), cn(99), u7(3))),
+                Some(RawShortMessage::control_change(ch(2), cn(98), u7(37))),
+                Some(RawShortMessage::control_change(ch(2), cn(96), u7(0))),
+                None,
+            ]
+        );
+        let dec_msgs: [Option<RawShortMessage>; 4] = dec.to_short_messages();
+        assert_eq!(
+            dec_msgs[2],
+            Some(RawShortMessage::control_change(ch(2), cn(97), u7(3)))
+        );
+        assert_eq!(dec.describe(), "RPN 421 -= 3 ch 2");
+    }
+
+    #[test]
+    fn to_short_messages_with_all_options() {
+        // Given
+        let msgs = [
+            ParameterNumberMessage::registered_14_bit(ch(0), u14(420), u14(15000)),
+            ParameterNumberMessage::non_registered_14_bit(ch(1), u14(256), u14(15000)),
+            ParameterNumberMessage::registered_7_bit(ch(2), u14(0), u7(2)),
+            ParameterNumberMessage::increment(ch(3), u14(128), u7(0), false),
+        ];
+        // When
+        // Then
+        for msg in msgs.iter() {
+            for value_msb_first in [false, true].iter() {
+                let options = PnMessageEmitOptions {
+                    value_msb_first: *value_msb_first,
+                };
+                let short_msgs: [Option<RawShortMessage>; 4] = msg.to_short_messages_with(options);
+                let some_msgs: Vec<_> = short_msgs.iter().flatten().collect();
+                // All Some messages come first
+                assert!(short_msgs[..some_msgs.len()].iter().all(|m| m.is_some()));
+                let expected_count = if msg.is_14_bit() { 4 } else { 3 };
+                assert_eq!(some_msgs.len(), expected_count);
+                for m in some_msgs.iter() {
+                    assert_eq!(m.channel(), Some(msg.channel()));
+                    assert!(crate::is_parameter_number_controller(
+                        m.controller_number().unwrap()
+                    ));
+                }
+                if msg.is_14_bit() {
+                    let data_entry_cns: Vec<_> = some_msgs[2..]
+                        .iter()
+                        .map(|m| m.controller_number().unwrap())
+                        .collect();
+                    let expected_cns = if *value_msb_first {
+                        vec![cn(6), cn(38)]
+                    } else {
+                        vec![cn(38), cn(6)]
+                    };
+                    assert_eq!(data_entry_cns, expected_cns);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn to_short_messages_with_default_options() {
+        // Given
+        let msg = ParameterNumberMessage::registered_14_bit(ch(0), u14(420), u14(15000));
+        // When
+        let short_msgs: [Option<RawShortMessage>; 4] =
+            msg.to_short_messages_with(PnMessageEmitOptions::default());
+        // Then
+        assert_eq!(short_msgs, msg.to_short_messages());
+    }
+
+    #[test]
+    fn short_messages_with_null() {
+        // Given
+        let msg = ParameterNumberMessage::non_registered_7_bit(ch(2), u14(421), u7(126));
+        // When
+        let short_msgs: Vec<_> = msg.short_messages_with_null().collect();
+        // Then
+        assert_eq!(
+            short_msgs,
+            vec![
+                RawShortMessage::control_change(ch(2), cn(99), u7(3)),
+                RawShortMessage::control_change(ch(2), cn(98), u7(37)),
+                RawShortMessage::control_change(ch(2), cn(6), u7(126)),
+                RawShortMessage::control_change(ch(2), cn(101), u7(127)),
+                RawShortMessage::control_change(ch(2), cn(100), u7(127)),
+            ]
+        );
+    }
+
+    #[test]
+    fn value_bytes() {
+        // Given
+        let msg_7_bit = ParameterNumberMessage::registered_7_bit(ch(0), u14(420), u7(127));
+        let msg_14_bit = ParameterNumberMessage::registered_14_bit(ch(0), u14(420), u14(15000));
+        let msg_relative = ParameterNumberMessage::decrement(ch(0), u14(420), u7(3), true);
+        // When
+        // Then
+        assert_eq!(msg_7_bit.value_msb(), u7(127));
+        assert_eq!(msg_7_bit.value_lsb(), None);
+        assert_eq!(msg_7_bit.as_7_bit_value(), Some(u7(127)));
+        assert_eq!(msg_7_bit.value(), u14(127));
+        assert_eq!(msg_14_bit.value_msb(), u7(117));
+        assert_eq!(msg_14_bit.value_lsb(), Some(u7(24)));
+        assert_eq!(msg_14_bit.as_7_bit_value(), None);
+        assert_eq!(msg_relative.value_msb(), u7(3));
+        assert_eq!(msg_relative.value_lsb(), None);
+        assert_eq!(msg_relative.as_7_bit_value(), Some(u7(3)));
+    }
+
+    #[test]
+    fn describe_named_rpn() {
+        // Given
+        let msg = ParameterNumberMessage::registered_14_bit(ch(1), u14(0), u14(512));
+        // When
+        let description = msg.describe();
+        // Then
+        assert_eq!(
+            description,
+            "RPN Pitch Bend Sensitivity (0) = 512 [14-bit] ch 1"
+        );
+    }
+
+    #[test]
+    fn describe_unnamed_rpn() {
+        // Given
+        let msg = ParameterNumberMessage::registered_7_bit(ch(0), u14(420), u7(5));
+        // When
+        let description = msg.describe();
+        // Then
+        assert_eq!(description, "RPN 420 = 5 [7-bit] ch 0");
+    }
+
+    #[test]
+    fn describe_nrpn() {
+        // Given
+        let msg = ParameterNumberMessage::non_registered_7_bit(ch(3), u14(1234), u7(64));
+        // When
+        let description = msg.describe();
+        // Then
+        assert_eq!(description, "NRPN 1234 = 64 [7-bit] ch 3");
+    }
+
+    #[test]
+    fn to_short_messages_as_14_bit() {
+        // Given
+        let seven_bit = ParameterNumberMessage::registered_7_bit(ch(1), u14(5), u7(64));
+        let fourteen_bit = ParameterNumberMessage::registered_14_bit(ch(1), u14(5), u14(1057));
+        // When
+        let seven_bit_msgs: [Option<RawShortMessage>; 4] = seven_bit.to_short_messages_as_14_bit();
+        let fourteen_bit_msgs: [Option<RawShortMessage>; 4] =
+            fourteen_bit.to_short_messages_as_14_bit();
+        // Then
+        assert_eq!(
+            seven_bit_msgs,
+            [
+                Some(RawShortMessage::control_change(ch(1), cn(101), u7(0))),
+                Some(RawShortMessage::control_change(ch(1), cn(100), u7(5))),
+                Some(RawShortMessage::control_change(ch(1), cn(38), u7(0))),
+                Some(RawShortMessage::control_change(ch(1), cn(6), u7(64))),
+            ]
+        );
+        assert_eq!(fourteen_bit_msgs, fourteen_bit.to_short_messages());
+    }
+
+    #[test]
+    fn to_short_messages_all_channels() {
+        // Given
+        let msg = ParameterNumberMessage::registered_14_bit(ch(5), u14(0), u14(256));
+        // When
+        let msgs: Vec<RawShortMessage> = msg.to_short_messages_all_channels().collect();
+        // Then
+        assert_eq!(msgs.len(), 64);
+        assert_eq!(
+            msgs.first(),
+            Some(&RawShortMessage::control_change(ch(0), cn(101), u7(0)))
+        );
+        assert_eq!(
+            msgs.last(),
+            Some(&RawShortMessage::control_change(ch(15), cn(6), u7(2)))
+        );
+        assert!(msgs[4..8].iter().all(|m| m.channel() == Some(ch(1))));
+    }
+
+    #[test]
+    fn display() {
+        // Given
+        let rpn = ParameterNumberMessage::registered_14_bit(ch(0), u14(420), u14(15000));
+        let nrpn = ParameterNumberMessage::non_registered_7_bit(ch(2), u14(421), u7(126));
+        let increment = ParameterNumberMessage::increment(ch(15), u14(0), u7(3), true);
+        let decrement = ParameterNumberMessage::decrement(ch(1), u14(16383), u7(1), false);
+        // When
+        // Then
+        assert_eq!(rpn.to_string(), "RPN[ch=0, num=420]=15000 (14-bit)");
+        assert_eq!(nrpn.to_string(), "NRPN7[ch=2, num=421]=126");
+        assert_eq!(increment.to_string(), "RPN[ch=15, num=0]+=3");
+        assert_eq!(decrement.to_string(), "NRPN[ch=1, num=16383]-=1");
+    }
+
+    #[test]
+    fn display_from_str_round_trip() {
+        // Given
+        let msgs = [
+            ParameterNumberMessage::registered_7_bit(ch(0), u14(0), u7(0)),
+            ParameterNumberMessage::registered_14_bit(ch(15), u14(16383), u14(16383)),
+            ParameterNumberMessage::non_registered_7_bit(ch(9), u14(128), u7(127)),
+            ParameterNumberMessage::non_registered_14_bit(ch(3), u14(421), u14(8192)),
+            ParameterNumberMessage::increment(ch(4), u14(5), u7(10), true),
+            ParameterNumberMessage::decrement(ch(5), u14(6), u7(20), false),
+        ];
+        // When
+        // Then
+        for msg in msgs.iter() {
+            assert_eq!(msg.to_string().parse(), Ok(*msg));
+        }
+    }
+
+    #[test]
+    fn from_str_errors() {
+        // Given
+        let invalid = [
+            "",
+            "CC[ch=0, num=0]=0 (14-bit)",
+            "RPN[ch=16, num=0]=0 (14-bit)",
+            "RPN[ch=0, num=16384]=0 (14-bit)",
+            "RPN[ch=0, num=0]=16384 (14-bit)",
+            "RPN7[ch=0, num=0]=128",
+            "RPN[ch=0, num=0]+=128",
+            "RPN[ch=0, num=0]=5",
+            "RPN7[ch=0, num=0]=5 (14-bit)",
+            "NRPN7[ch=0 num=0]=5",
+        ];
+        // When
+        // Then
+        for s in invalid.iter() {
+            assert_eq!(
+                s.parse::<ParameterNumberMessage>(),
+                Err(ParseParameterNumberMessageError(())),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        // Given
+        let msgs = [
+            ParameterNumberMessage::non_registered_7_bit(ch(0), u14(0), u7(127)),
+            ParameterNumberMessage::non_registered_14_bit(ch(15), u14(16383), u14(16383)),
+            ParameterNumberMessage::registered_7_bit(ch(7), u14(5), u7(0)),
+            ParameterNumberMessage::registered_14_bit(ch(1), u14(420), u14(15000)),
+            ParameterNumberMessage::increment(ch(2), u14(128), u7(3), true),
+            ParameterNumberMessage::decrement(ch(3), u14(129), u7(4), false),
+        ];
+        // When
+        // Then
+        for msg in msgs.iter() {
+            assert_eq!(ParameterNumberMessage::from_bytes(msg.to_bytes()), Ok(*msg));
+        }
+        assert_eq!(
+            ParameterNumberMessage::from_bytes([0x80, 0, 0, 0, 0]),
+            Err(FromBytesError(()))
+        );
+        assert_eq!(
+            ParameterNumberMessage::from_bytes([0x00, 0x40, 0, 0, 0]),
+            Err(FromBytesError(()))
+        );
+        assert_eq!(
+            ParameterNumberMessage::from_bytes([0x00, 0, 0, 0, 0x80]),
+            Err(FromBytesError(()))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_legacy_format() {
+        // Given
+        let json_14_bit = json! {
+            {
+                "channel": 1,
+                "number": 420,
+                "value": 15000,
+                "is_registered": true,
+                "is_14_bit": true
+            }
+        };
+        let json_7_bit = json! {
+            {
+                "channel": 1,
+                "number": 420,
+                "value": 100,
+                "is_registered": false,
+                "is_14_bit": false
+            }
+        };
+        // When
+        let msg_14_bit: ParameterNumberMessage = serde_json::from_value(json_14_bit).unwrap();
+        let msg_7_bit: ParameterNumberMessage = serde_json::from_value(json_7_bit).unwrap();
+        // Then
+        assert_eq!(
+            msg_14_bit,
+            ParameterNumberMessage::registered_14_bit(ch(1), u14(420), u14(15000))
+        );
+        assert_eq!(
+            msg_7_bit,
+            ParameterNumberMessage::non_registered_7_bit(ch(1), u14(420), u7(100))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_and_back() {
+        // Given
+        let msg = ParameterNumberMessage::decrement(ch(1), u14(420), u7(3), true);
+        // When
+        let j = serde_json::to_value(msg).unwrap();
+        let deserialized: ParameterNumberMessage = serde_json::from_value(j.clone()).unwrap();
+        // Then
+        assert_eq!(
+            j,
+            json! {
+                {
+                    "channel": 1,
+                    "number": 420,
+                    "value": 3,
+                    "is_registered": true,
+                    "is_14_bit": false,
+                    "kind": "Decrement"
+                }
+            }
+        );
+        assert_eq!(deserialized, msg);
+    }
 }
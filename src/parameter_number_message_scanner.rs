@@ -1,10 +1,23 @@
+use crate::bit_util::build_14_bit_value_from_two_7_bit_values;
 use crate::{
-    build_14_bit_value_from_two_7_bit_values, Channel, ParameterNumberMessage, ShortMessage,
-    StructuredShortMessage, U7,
+    is_parameter_number_controller, is_sequence_boundary, Channel, ParameterNumberMessage,
+    ShortMessage, StructuredShortMessage, U14, U7,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Scanner for detecting (N)RPN messages in a stream of short messages.
 ///
+/// Besides Data Entry, Data Increment (controller 96) and Data Decrement (controller 97) are
+/// supported as well. They result in (N)RPN messages of kind
+/// [`ParameterNumberMessageKind::Increment`] or [`ParameterNumberMessageKind::Decrement`] for the
+/// currently selected parameter number, carrying the data byte as is. According to the MIDI
+/// specification, that data byte is usually 0 and should be ignored by receivers which just step
+/// by one. Data Increment and Data Decrement messages which arrive before a parameter number has
+/// been selected are ignored. If [`ChannelScanConfig::accumulate`] is enabled, they result in
+/// absolute (N)RPN messages instead.
+///
 /// # Example
 ///
 /// ```
@@ -30,9 +43,28 @@ use crate::{
 ///     ))
 /// );
 /// ```
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+///
+/// [`ParameterNumberMessageKind::Increment`]:
+/// enum.ParameterNumberMessageKind.html#variant.Increment
+/// [`ParameterNumberMessageKind::Decrement`]:
+/// enum.ParameterNumberMessageKind.html#variant.Decrement
+/// [`ChannelScanConfig::accumulate`]: struct.ChannelScanConfig.html#structfield.accumulate
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParameterNumberMessageScanner {
     scanner_by_channel: [ScannerForOneChannel; 16],
+    boundary_on_system_messages: bool,
+    timeout: Duration,
+}
+
+impl Default for ParameterNumberMessageScanner {
+    fn default() -> Self {
+        ParameterNumberMessageScanner {
+            scanner_by_channel: Default::default(),
+            boundary_on_system_messages: false,
+            timeout: Duration::from_millis(10),
+        }
+    }
 }
 
 impl ParameterNumberMessageScanner {
@@ -41,51 +73,553 @@ impl ParameterNumberMessageScanner {
         Default::default()
     }
 
+    /// Creates a new scanner which discards the parameter number selected so far on a channel as
+    /// soon as a Control Change message on a non-(N)RPN controller arrives on that channel.
+    ///
+    /// This is the same as applying a [`ChannelScanConfig`] with `strict` enabled to all channels.
+    /// It prevents emitting messages the sender never intended because of an aborted parameter
+    /// number selection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::control_change;
+    /// use helgoboss_midi::ParameterNumberMessageScanner;
+    ///
+    /// let mut scanner = ParameterNumberMessageScanner::new_strict();
+    /// scanner.feed(&control_change(0, 99, 3));
+    /// scanner.feed(&control_change(0, 98, 37));
+    /// scanner.feed(&control_change(0, 7, 100));
+    /// assert_eq!(scanner.feed(&control_change(0, 6, 2)), None);
+    /// ```
+    ///
+    /// [`ChannelScanConfig`]: struct.ChannelScanConfig.html
+    pub fn new_strict() -> ParameterNumberMessageScanner {
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.set_config(ChannelScanConfig {
+            strict: true,
+            ..Default::default()
+        });
+        scanner
+    }
+
     /// Feeds the scanner a single short message.
     ///
     /// Returns the (N)RPN message if one has been detected.
     pub fn feed(&mut self, msg: &impl ShortMessage) -> Option<ParameterNumberMessage> {
-        let channel = msg.channel()?;
+        match self.feed_event(msg)? {
+            ParameterNumberEvent::Message(msg) => Some(msg),
+            ParameterNumberEvent::Null { .. } => None,
+        }
+    }
+
+    /// Returns whether feeding the given short message would result in an (N)RPN message.
+    ///
+    /// Doesn't change the scanning progress. Useful for finding out in advance whether a message
+    /// completes a sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::control_change;
+    /// use helgoboss_midi::ParameterNumberMessageScanner;
+    ///
+    /// let mut scanner = ParameterNumberMessageScanner::new();
+    /// scanner.feed(&control_change(0, 99, 3));
+    /// assert!(!scanner.would_complete(&control_change(0, 6, 2)));
+    /// scanner.feed(&control_change(0, 98, 37));
+    /// assert!(scanner.would_complete(&control_change(0, 6, 2)));
+    /// ```
+    pub fn would_complete(&self, msg: &impl ShortMessage) -> bool {
+        let mut scanner = *self;
+        scanner.feed(msg).is_some()
+    }
+
+    /// Feeds the scanner a single short message, also reporting RPN Null.
+    ///
+    /// Works like [`feed`] but additionally returns an event when an RPN Null sequence (RPN
+    /// 127/127) has been detected. The event contains the parameter number which was selected
+    /// before, if any. After that, the selection is cleared, so subsequent Data Entry messages
+    /// are ignored until a new parameter number is selected.
+    ///
+    /// The previously selected parameter number is remembered at the time the parameter number
+    /// MSB arrives, which is the order recommended by the MIDI specification.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{channel, control_change, u14};
+    /// use helgoboss_midi::{ParameterNumberEvent, ParameterNumberMessageScanner};
+    ///
+    /// let mut scanner = ParameterNumberMessageScanner::new();
+    /// scanner.feed_event(&control_change(0, 101, 0));
+    /// scanner.feed_event(&control_change(0, 100, 0));
+    /// scanner.feed_event(&control_change(0, 6, 12));
+    /// scanner.feed_event(&control_change(0, 101, 127));
+    /// assert_eq!(
+    ///     scanner.feed_event(&control_change(0, 100, 127)),
+    ///     Some(ParameterNumberEvent::Null {
+    ///         channel: channel(0),
+    ///         previous_number: Some(u14(0))
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// [`feed`]: #method.feed
+    pub fn feed_event(&mut self, msg: &impl ShortMessage) -> Option<ParameterNumberEvent> {
+        let channel = match msg.channel() {
+            Some(c) => c,
+            None => {
+                if self.boundary_on_system_messages && is_sequence_boundary(msg) {
+                    self.reset();
+                }
+                return None;
+            }
+        };
         self.scanner_by_channel[usize::from(channel)].feed(msg)
     }
 
+    /// Feeds the scanner all short messages of the given iterator, lazily.
+    ///
+    /// Returns an iterator which yields the detected (N)RPN messages while pulling short messages
+    /// from the given iterator. Nothing is collected, so this also works with very long or
+    /// infinite streams. Intermediate scanning progress stays in the scanner, so feeding can be
+    /// continued afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, nrpn};
+    /// use helgoboss_midi::ParameterNumberMessageScanner;
+    ///
+    /// let mut scanner = ParameterNumberMessageScanner::new();
+    /// let msgs = vec![
+    ///     control_change(0, 99, 3),
+    ///     control_change(0, 98, 37),
+    ///     control_change(0, 6, 2),
+    ///     control_change(0, 6, 3),
+    /// ];
+    /// let results: Vec<_> = scanner.feed_iter(msgs).collect();
+    /// assert_eq!(results, vec![nrpn(0, 421, 2), nrpn(0, 421, 3)]);
+    /// ```
+    pub fn feed_iter<'a, I>(
+        &'a mut self,
+        iter: I,
+    ) -> impl Iterator<Item = ParameterNumberMessage> + 'a
+    where
+        I: IntoIterator,
+        I::Item: ShortMessage,
+        I::IntoIter: 'a,
+    {
+        iter.into_iter().filter_map(move |msg| self.feed(&msg))
+    }
+
+    /// Feeds the scanner all given short messages and returns the detected (N)RPN messages in
+    /// order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, nrpn, note_on};
+    /// use helgoboss_midi::ParameterNumberMessageScanner;
+    ///
+    /// let mut scanner = ParameterNumberMessageScanner::new();
+    /// let results = scanner.feed_all(vec![
+    ///     control_change(0, 99, 3),
+    ///     note_on(0, 60, 100),
+    ///     control_change(0, 98, 37),
+    ///     control_change(0, 6, 2),
+    /// ]);
+    /// assert_eq!(results, vec![nrpn(0, 421, 2)]);
+    /// ```
+    pub fn feed_all<I>(&mut self, msgs: I) -> Vec<ParameterNumberMessage>
+    where
+        I: IntoIterator,
+        I::Item: ShortMessage,
+    {
+        let mut results = Vec::new();
+        self.feed_all_into(msgs, &mut results);
+        results
+    }
+
+    /// Feeds the scanner all given short messages and appends the detected (N)RPN messages to the
+    /// given vector in order.
+    ///
+    /// Works like [`feed_all`] but lets the caller reuse an existing vector.
+    ///
+    /// [`feed_all`]: #method.feed_all
+    pub fn feed_all_into<I>(&mut self, msgs: I, out: &mut Vec<ParameterNumberMessage>)
+    where
+        I: IntoIterator,
+        I::Item: ShortMessage,
+    {
+        out.extend(msgs.into_iter().filter_map(|msg| self.feed(&msg)));
+    }
+
+    /// Feeds the scanner a batch of short messages without allocating.
+    ///
+    /// Returns the detected (N)RPN messages in an array with room for `N` messages. The detected
+    /// messages come first, the remaining elements are `None`. If more than `N` messages are
+    /// detected, the excess ones are dropped. Nevertheless, all short messages are fed, so the
+    /// scanning progress is the same as with [`feed`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, nrpn};
+    /// use helgoboss_midi::ParameterNumberMessageScanner;
+    ///
+    /// let mut scanner = ParameterNumberMessageScanner::new();
+    /// let results = scanner.feed_batch::<2>(&[
+    ///     control_change(0, 99, 3),
+    ///     control_change(0, 98, 37),
+    ///     control_change(0, 6, 2),
+    /// ]);
+    /// assert_eq!(results, [Some(nrpn(0, 421, 2)), None]);
+    /// ```
+    ///
+    /// [`feed`]: #method.feed
+    pub fn feed_batch<const N: usize>(
+        &mut self,
+        msgs: &[impl ShortMessage],
+    ) -> [Option<ParameterNumberMessage>; N] {
+        let mut results = [None; N];
+        let mut i = 0;
+        for msg in msgs {
+            if let Some(result) = self.feed(msg) {
+                if i < N {
+                    results[i] = Some(result);
+                    i += 1;
+                }
+            }
+        }
+        results
+    }
+
+    /// Feeds the scanner a single short message which occurred at the given time.
+    ///
+    /// Works like [`feed`] but additionally remembers the time of the last message per channel,
+    /// which is needed by [`poll`].
+    ///
+    /// [`feed`]: #method.feed
+    /// [`poll`]: #method.poll
+    pub fn feed_with_time(
+        &mut self,
+        msg: &impl ShortMessage,
+        time: Duration,
+    ) -> Option<ParameterNumberMessage> {
+        if let Some(channel) = msg.channel() {
+            self.scanner_by_channel[usize::from(channel)].last_time = Some(time);
+        }
+        self.feed(msg)
+    }
+
+    /// Emits a pending (N)RPN message of a channel which has been idle for longer than the
+    /// timeout.
+    ///
+    /// A Data Entry MSB message results in an (N)RPN message immediately. However, a subsequent
+    /// Data Entry LSB message for refining the value doesn't, because the scanner can't tell
+    /// whether another Data Entry MSB message is coming. If no further message arrives on that
+    /// channel within the timeout, this method emits the 14-bit (N)RPN message made up of the
+    /// latest MSB and LSB. Only channels fed via [`feed_with_time`] are considered.
+    ///
+    /// Returns at most one message. Call it repeatedly until it returns `None` in order to get
+    /// the messages of all channels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, nrpn, nrpn_14_bit};
+    /// use helgoboss_midi::ParameterNumberMessageScanner;
+    /// use std::time::Duration;
+    ///
+    /// let mut scanner = ParameterNumberMessageScanner::new();
+    /// let ms = Duration::from_millis;
+    /// scanner.feed_with_time(&control_change(0, 99, 3), ms(0));
+    /// scanner.feed_with_time(&control_change(0, 98, 37), ms(1));
+    /// let result = scanner.feed_with_time(&control_change(0, 6, 2), ms(2));
+    /// assert_eq!(result, Some(nrpn(0, 421, 2)));
+    /// scanner.feed_with_time(&control_change(0, 38, 1), ms(3));
+    /// assert_eq!(scanner.poll(ms(5)), None);
+    /// assert_eq!(scanner.poll(ms(20)), Some(nrpn_14_bit(0, 421, 257)));
+    /// assert_eq!(scanner.poll(ms(20)), None);
+    /// ```
+    ///
+    /// [`feed_with_time`]: #method.feed_with_time
+    pub fn poll(&mut self, now: Duration) -> Option<ParameterNumberMessage> {
+        let timeout = self.timeout;
+        self.scanner_by_channel
+            .iter_mut()
+            .enumerate()
+            .find_map(|(i, p)| p.poll(Channel(i as u8), now, timeout))
+    }
+
+    /// Sets the idle interval after which [`poll`] emits pending (N)RPN messages.
+    ///
+    /// The default is 10 ms. `Duration::MAX` disables emitting on [`poll`].
+    ///
+    /// [`poll`]: #method.poll
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Sets whether System Exclusive, System Common and System Reset messages should end any
+    /// incomplete (N)RPN message.
+    ///
+    /// If enabled, such a message discards all intermediate scanning progress, just like
+    /// [`reset`]. A sender which interrupts a sequence with one of these messages has most likely
+    /// abandoned it. Other System Real Time messages such as Timing Clock never have this effect
+    /// because they may be interleaved with anything.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`reset`]: #method.reset
+    pub fn set_boundary_on_system_messages(&mut self, enabled: bool) {
+        self.boundary_on_system_messages = enabled;
+    }
+
+    /// Applies the given configuration to all channels.
+    pub fn set_config(&mut self, config: ChannelScanConfig) {
+        for p in self.scanner_by_channel.iter_mut() {
+            p.config = config;
+        }
+    }
+
+    /// Applies the given configuration to the given channel only.
+    ///
+    /// Useful if the channels of a multi-timbral device behave differently.
+    pub fn configure_channel(&mut self, channel: Channel, config: ChannelScanConfig) {
+        self.scanner_by_channel[usize::from(channel)].config = config;
+    }
+
+    /// Returns the configuration of the given channel.
+    pub fn channel_config(&self, channel: Channel) -> ChannelScanConfig {
+        self.scanner_by_channel[usize::from(channel)].config
+    }
+
+    /// The maximum number of short messages the scanner may need to receive on one channel before
+    /// it emits an (N)RPN message.
+    ///
+    /// Useful for reasoning about the delay introduced by scanning. A 7-bit value or a relative
+    /// change is complete after 3 messages (parameter number MSB and LSB, Data Entry MSB or Data
+    /// Increment/Decrement). A 14-bit value requires Data Entry LSB in addition, which makes 4.
+    /// Because the scanner can't know in advance which kind of message the sender transmits, the
+    /// value is the same for every [`ChannelScanConfig`]: Even with `eager_emit` enabled, a sender
+    /// which transmits the Data Entry LSB first causes an emission on the 4th message only.
+    /// Messages for a parameter number which stays selected (`reuse_number`) are emitted earlier.
+    ///
+    /// [`ChannelScanConfig`]: struct.ChannelScanConfig.html
+    pub const MAX_LATENCY_MESSAGES: usize = 4;
+
+    /// Returns the maximum number of short messages the scanner may need to receive on one
+    /// channel before it emits an (N)RPN message, given its current configuration.
+    ///
+    /// Currently always [`MAX_LATENCY_MESSAGES`].
+    ///
+    /// [`MAX_LATENCY_MESSAGES`]: #associatedconstant.MAX_LATENCY_MESSAGES
+    pub fn max_latency_messages(&self) -> usize {
+        Self::MAX_LATENCY_MESSAGES
+    }
+
     /// Resets the scanner discarding all intermediate scanning progress.
+    ///
+    /// The configuration is kept.
     pub fn reset(&mut self) {
         for p in self.scanner_by_channel.iter_mut() {
             p.reset();
         }
     }
+
+    /// Resets the given channel only, discarding its intermediate scanning progress.
+    ///
+    /// Useful if the device on one channel has been reset. The other channels are not affected.
+    pub fn reset_channel(&mut self, channel: Channel) {
+        self.scanner_by_channel[usize::from(channel)].reset();
+    }
+
+    /// Discards the intermediate scanning progress of the given channel and returns it.
+    ///
+    /// Useful for cancelling an (N)RPN message which has been entered only partially, e.g. in
+    /// order to display or restore what has been collected so far.
+    pub fn abort_channel(&mut self, channel: Channel) -> PartialParameterNumber {
+        let scanner = &mut self.scanner_by_channel[usize::from(channel)];
+        let partial = PartialParameterNumber {
+            number_msb: scanner.number_msb,
+            number_lsb: scanner.number_lsb,
+            is_registered: scanner.is_registered,
+            value_lsb: scanner.value_lsb,
+        };
+        scanner.reset();
+        partial
+    }
+}
+
+/// The intermediate scanning progress of one channel, as returned by
+/// [`ParameterNumberMessageScanner::abort_channel`].
+///
+/// [`ParameterNumberMessageScanner::abort_channel`]:
+/// struct.ParameterNumberMessageScanner.html#method.abort_channel
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct PartialParameterNumber {
+    number_msb: Option<U7>,
+    number_lsb: Option<U7>,
+    is_registered: bool,
+    value_lsb: Option<U7>,
+}
+
+impl PartialParameterNumber {
+    /// Returns the most significant byte of the parameter number if it has been received.
+    pub fn number_msb(&self) -> Option<U7> {
+        self.number_msb
+    }
+
+    /// Returns the least significant byte of the parameter number if it has been received.
+    pub fn number_lsb(&self) -> Option<U7> {
+        self.number_lsb
+    }
+
+    /// Returns whether the most recently received parameter number byte was a registered one.
+    ///
+    /// Meaningless if no parameter number byte has been received.
+    pub fn is_registered(&self) -> bool {
+        self.is_registered
+    }
+
+    /// Returns the least significant byte of the value if it has been received.
+    pub fn value_lsb(&self) -> Option<U7> {
+        self.value_lsb
+    }
+
+    /// Returns the complete parameter number if both of its bytes have been received.
+    pub fn number(&self) -> Option<U14> {
+        Some(build_14_bit_value_from_two_7_bit_values(
+            self.number_msb?,
+            self.number_lsb?,
+        ))
+    }
+
+    /// Returns whether nothing has been received at all.
+    pub fn is_empty(&self) -> bool {
+        self.number_msb.is_none() && self.number_lsb.is_none() && self.value_lsb.is_none()
+    }
+}
+
+/// Configures how [`ParameterNumberMessageScanner`] scans one channel.
+///
+/// [`ParameterNumberMessageScanner`]: struct.ParameterNumberMessageScanner.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChannelScanConfig {
+    /// If enabled, a Data Entry LSB message which follows a Data Entry MSB message immediately
+    /// results in a 14-bit (N)RPN message made up of both.
+    ///
+    /// Useful for senders which transmit the MSB before the LSB. Such senders cause a 7-bit
+    /// message on the MSB and a 14-bit message on the LSB. Disabled by default, in which case only
+    /// Data Entry MSB messages result in (N)RPN messages.
+    pub eager_emit: bool,
+    /// If enabled, the parameter number stays selected after a message has been emitted, so
+    /// subsequent Data Entry messages result in further messages for the same parameter number.
+    ///
+    /// Enabled by default.
+    pub reuse_number: bool,
+    /// If enabled, a Control Change message on a non-(N)RPN controller discards the parameter
+    /// number selected so far.
+    ///
+    /// Prevents emitting messages the sender never intended, e.g. if a parameter number selection
+    /// was interrupted. Disabled by default.
+    pub strict: bool,
+    /// If enabled, Data Increment and Data Decrement messages result in absolute (N)RPN messages
+    /// instead of relative ones.
+    ///
+    /// Useful for controllers which select a parameter number and set its value via Data Entry
+    /// once, and then sweep it by sending a stream of increments or decrements. The new value is
+    /// calculated from the latest absolute value of the selected parameter number, stepping by the
+    /// amount (or by one if the amount is 0) and clamping at the value range. It keeps the
+    /// resolution of the latest absolute value. Selecting another parameter number restarts
+    /// accumulation from the next Data Entry. As long as no absolute value is known, relative
+    /// messages are emitted. Disabled by default.
+    pub accumulate: bool,
+}
+
+impl Default for ChannelScanConfig {
+    fn default() -> Self {
+        ChannelScanConfig {
+            eager_emit: false,
+            reuse_number: true,
+            strict: false,
+            accumulate: false,
+        }
+    }
+}
+
+/// An event detected by [`ParameterNumberMessageScanner::feed_event`].
+///
+/// [`ParameterNumberMessageScanner::feed_event`]:
+/// struct.ParameterNumberMessageScanner.html#method.feed_event
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ParameterNumberEvent {
+    /// A complete (N)RPN message.
+    Message(ParameterNumberMessage),
+    /// An RPN Null sequence which deselects the current parameter number.
+    Null {
+        channel: Channel,
+        /// The parameter number which was selected before or `None` if there wasn't any.
+        previous_number: Option<U14>,
+    },
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct ScannerForOneChannel {
+    config: ChannelScanConfig,
+    previous_number: Option<U14>,
     number_msb: Option<U7>,
     number_lsb: Option<U7>,
     is_registered: bool,
+    value_msb: Option<U7>,
     value_lsb: Option<U7>,
+    value_lsb_pending: bool,
+    accumulated: Option<ParameterNumberMessage>,
+    last_time: Option<Duration>,
 }
 
 impl ScannerForOneChannel {
-    fn feed(&mut self, msg: &impl ShortMessage) -> Option<ParameterNumberMessage> {
+    fn feed(&mut self, msg: &impl ShortMessage) -> Option<ParameterNumberEvent> {
         match msg.to_structured() {
             StructuredShortMessage::ControlChange {
                 channel,
                 controller_number,
                 control_value,
             } => match controller_number.get() {
-                98 => self.process_number_lsb(control_value, false),
-                99 => self.process_number_msb(control_value, false),
-                100 => self.process_number_lsb(control_value, true),
-                101 => self.process_number_msb(control_value, true),
-                38 => self.process_value_lsb(control_value),
-                6 => self.process_value_msb(channel, control_value),
-                _ => None,
+                98 => self.process_number_lsb(channel, control_value, false),
+                99 => self.process_number_msb(channel, control_value, false),
+                100 => self.process_number_lsb(channel, control_value, true),
+                101 => self.process_number_msb(channel, control_value, true),
+                38 => self
+                    .process_value_lsb(channel, control_value)
+                    .map(ParameterNumberEvent::Message),
+                6 => self
+                    .process_value_msb(channel, control_value)
+                    .map(ParameterNumberEvent::Message),
+                96 => self
+                    .process_relative(channel, control_value, true)
+                    .map(ParameterNumberEvent::Message),
+                97 => self
+                    .process_relative(channel, control_value, false)
+                    .map(ParameterNumberEvent::Message),
+                _ => {
+                    if self.config.strict && !is_parameter_number_controller(controller_number) {
+                        self.reset();
+                    }
+                    None
+                }
             },
             _ => None,
         }
     }
 
     fn reset(&mut self) {
+        self.previous_number = None;
         self.number_msb = None;
         self.number_lsb = None;
         self.is_registered = false;
@@ -94,29 +628,78 @@ impl ScannerForOneChannel {
 
     fn process_number_lsb(
         &mut self,
+        channel: Channel,
         number_lsb: U7,
         is_registered: bool,
-    ) -> Option<ParameterNumberMessage> {
+    ) -> Option<ParameterNumberEvent> {
         self.reset_value();
         self.number_lsb = Some(number_lsb);
         self.is_registered = is_registered;
-        None
+        self.detect_null(channel)
     }
 
     fn process_number_msb(
         &mut self,
+        channel: Channel,
         number_msb: U7,
         is_registered: bool,
-    ) -> Option<ParameterNumberMessage> {
+    ) -> Option<ParameterNumberEvent> {
+        self.previous_number = self.number();
         self.reset_value();
         self.number_msb = Some(number_msb);
         self.is_registered = is_registered;
-        None
+        self.detect_null(channel)
+    }
+
+    fn number(&self) -> Option<U14> {
+        Some(build_14_bit_value_from_two_7_bit_values(
+            self.number_msb?,
+            self.number_lsb?,
+        ))
+    }
+
+    fn detect_null(&mut self, channel: Channel) -> Option<ParameterNumberEvent> {
+        if !self.is_registered || self.number() != Some(U14::MAX) {
+            return None;
+        }
+        let previous_number = self.previous_number;
+        self.reset();
+        Some(ParameterNumberEvent::Null {
+            channel,
+            previous_number,
+        })
+    }
+
+    fn poll(
+        &mut self,
+        channel: Channel,
+        now: Duration,
+        timeout: Duration,
+    ) -> Option<ParameterNumberMessage> {
+        if !self.value_lsb_pending {
+            return None;
+        }
+        // A deadline beyond the representable time, e.g. with `Duration::MAX`, is never due
+        let deadline = self.last_time?.checked_add(timeout)?;
+        if now < deadline {
+            return None;
+        }
+        let value_msb = self.value_msb?;
+        self.emit(channel, value_msb)
     }
 
-    fn process_value_lsb(&mut self, value_lsb: U7) -> Option<ParameterNumberMessage> {
+    fn process_value_lsb(
+        &mut self,
+        channel: Channel,
+        value_lsb: U7,
+    ) -> Option<ParameterNumberMessage> {
         self.value_lsb = Some(value_lsb);
-        None
+        self.value_lsb_pending = true;
+        if !self.config.eager_emit {
+            return None;
+        }
+        let value_msb = self.value_msb?;
+        self.emit(channel, value_msb)
     }
 
     fn process_value_msb(
@@ -124,9 +707,37 @@ impl ScannerForOneChannel {
         channel: Channel,
         value_msb: U7,
     ) -> Option<ParameterNumberMessage> {
+        self.value_msb = Some(value_msb);
+        self.emit(channel, value_msb)
+    }
+
+    fn process_relative(
+        &mut self,
+        channel: Channel,
+        amount: U7,
+        is_increment: bool,
+    ) -> Option<ParameterNumberMessage> {
+        let number = self.number()?;
+        let msg = if let Some(base) = self.accumulated.filter(|_| self.config.accumulate) {
+            let accumulated = accumulate(base, amount, is_increment);
+            self.accumulated = Some(accumulated);
+            accumulated
+        } else if is_increment {
+            ParameterNumberMessage::increment(channel, number, amount, self.is_registered)
+        } else {
+            ParameterNumberMessage::decrement(channel, number, amount, self.is_registered)
+        };
+        if !self.config.reuse_number {
+            self.reset();
+        }
+        Some(msg)
+    }
+
+    fn emit(&mut self, channel: Channel, value_msb: U7) -> Option<ParameterNumberMessage> {
         let number_lsb = self.number_lsb?;
         let number_msb = self.number_msb?;
         let number = build_14_bit_value_from_two_7_bit_values(number_msb, number_lsb);
+        self.value_lsb_pending = false;
         let msg = if self.is_registered {
             match self.value_lsb {
                 Some(value_lsb) => ParameterNumberMessage::registered_14_bit(
@@ -146,11 +757,50 @@ impl ScannerForOneChannel {
                 None => ParameterNumberMessage::non_registered_7_bit(channel, number, value_msb),
             }
         };
+        self.accumulated = Some(msg);
+        if !self.config.reuse_number {
+            self.reset();
+        }
         Some(msg)
     }
 
     fn reset_value(&mut self) {
+        self.value_msb = None;
         self.value_lsb = None;
+        self.value_lsb_pending = false;
+        self.accumulated = None;
+    }
+}
+
+fn accumulate(
+    base: ParameterNumberMessage,
+    amount: U7,
+    is_increment: bool,
+) -> ParameterNumberMessage {
+    let step = amount.get().max(1) as u16;
+    let max = if base.is_14_bit() {
+        U14::MAX.get()
+    } else {
+        U7::MAX.get() as u16
+    };
+    let value = base.value().get();
+    let new_value = if is_increment {
+        value.saturating_add(step).min(max)
+    } else {
+        value.saturating_sub(step)
+    };
+    let (channel, number) = (base.channel(), base.number());
+    match (base.is_registered(), base.is_14_bit()) {
+        (true, true) => ParameterNumberMessage::registered_14_bit(channel, number, U14(new_value)),
+        (true, false) => {
+            ParameterNumberMessage::registered_7_bit(channel, number, U7(new_value as u8))
+        }
+        (false, true) => {
+            ParameterNumberMessage::non_registered_14_bit(channel, number, U14(new_value))
+        }
+        (false, false) => {
+            ParameterNumberMessage::non_registered_7_bit(channel, number, U7(new_value as u8))
+        }
     }
 }
 
@@ -158,7 +808,7 @@ impl ScannerForOneChannel {
 mod tests {
     use super::*;
     use crate::test_util::{channel as ch, controller_number as cn, key_number, u14, u7};
-    use crate::{RawShortMessage, ShortMessageFactory};
+    use crate::{ParameterNumberMessageKind, RawShortMessage, ShortMessageFactory};
 
     #[test]
     fn should_ignore_non_contributing_short_messages() {
@@ -273,4 +923,824 @@ mod tests {
         assert!(!result_3.is_registered());
         assert!(!result_3.is_14_bit());
     }
+
+    fn feed_with_interleaved_controllers(
+        scanner: &mut ParameterNumberMessageScanner,
+    ) -> Vec<ParameterNumberMessage> {
+        [
+            RawShortMessage::control_change(ch(0), cn(99), u7(3)),
+            RawShortMessage::control_change(ch(0), cn(7), u7(100)),
+            RawShortMessage::control_change(ch(0), cn(98), u7(37)),
+            RawShortMessage::control_change(ch(0), cn(10), u7(64)),
+            RawShortMessage::control_change(ch(0), cn(38), u7(1)),
+            RawShortMessage::control_change(ch(0), cn(64), u7(127)),
+            RawShortMessage::control_change(ch(0), cn(6), u7(2)),
+        ]
+        .iter()
+        .filter_map(|msg| scanner.feed(msg))
+        .collect()
+    }
+
+    #[test]
+    fn should_tolerate_interleaved_controllers_by_default() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        let results = feed_with_interleaved_controllers(&mut scanner);
+        // Then
+        assert_eq!(
+            results,
+            vec![ParameterNumberMessage::non_registered_14_bit(
+                ch(0),
+                u14(421),
+                u14(257)
+            )]
+        );
+    }
+
+    #[test]
+    fn should_invalidate_selection_on_interleaved_controllers_if_strict() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new_strict();
+        // When
+        let results = feed_with_interleaved_controllers(&mut scanner);
+        // Then
+        assert_eq!(results, vec![]);
+        assert!(scanner.abort_channel(ch(0)).is_empty());
+    }
+
+    #[test]
+    fn strict_should_not_affect_other_channels() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new_strict();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(99), u7(3)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(98), u7(37)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(1), cn(64), u7(127)));
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(2)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(
+            result_4,
+            Some(ParameterNumberMessage::non_registered_7_bit(
+                ch(0),
+                u14(421),
+                u7(2)
+            ))
+        );
+    }
+
+    #[test]
+    fn poll_should_never_emit_with_max_timeout() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.set_timeout(Duration::MAX);
+        let ms = Duration::from_millis;
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(0), cn(99), u7(3)),
+            ms(0),
+        );
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(0), cn(98), u7(37)),
+            ms(0),
+        );
+        scanner.feed_with_time(&RawShortMessage::control_change(ch(0), cn(6), u7(2)), ms(0));
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(0), cn(38), u7(1)),
+            ms(10),
+        );
+        // When
+        let result_1 = scanner.poll(ms(1000));
+        let result_2 = scanner.poll(Duration::MAX);
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+    }
+
+    #[test]
+    fn poll_should_emit_pending_value_lsb_per_channel_after_timeout() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.set_timeout(Duration::from_millis(100));
+        let ms = Duration::from_millis;
+        for c in [0, 1].iter() {
+            scanner.feed_with_time(
+                &RawShortMessage::control_change(ch(*c), cn(99), u7(3)),
+                ms(0),
+            );
+            scanner.feed_with_time(
+                &RawShortMessage::control_change(ch(*c), cn(98), u7(37)),
+                ms(0),
+            );
+            scanner.feed_with_time(
+                &RawShortMessage::control_change(ch(*c), cn(6), u7(2)),
+                ms(0),
+            );
+        }
+        // When
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(0), cn(38), u7(1)),
+            ms(10),
+        );
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(1), cn(38), u7(2)),
+            ms(50),
+        );
+        let result_1 = scanner.poll(ms(109));
+        let result_2 = scanner.poll(ms(110));
+        let result_3 = scanner.poll(ms(110));
+        let result_4 = scanner.poll(ms(150));
+        let result_5 = scanner.poll(ms(1000));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(ParameterNumberMessage::non_registered_14_bit(
+                ch(0),
+                u14(421),
+                u14(257)
+            ))
+        );
+        assert_eq!(result_3, None);
+        assert_eq!(
+            result_4,
+            Some(ParameterNumberMessage::non_registered_14_bit(
+                ch(1),
+                u14(421),
+                u14(258)
+            ))
+        );
+        assert_eq!(result_5, None);
+    }
+
+    #[test]
+    fn poll_should_not_emit_if_data_entry_msb_follows() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        let ms = Duration::from_millis;
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(0), cn(99), u7(3)),
+            ms(0),
+        );
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(0), cn(98), u7(37)),
+            ms(0),
+        );
+        // When
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(0), cn(38), u7(1)),
+            ms(0),
+        );
+        let result_1 = scanner.poll(ms(100));
+        scanner.feed_with_time(
+            &RawShortMessage::control_change(ch(0), cn(6), u7(2)),
+            ms(100),
+        );
+        let result_2 = scanner.poll(ms(200));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+    }
+
+    #[test]
+    fn feed_all_should_return_messages_in_order() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        let msgs = vec![
+            RawShortMessage::control_change(ch(1), cn(101), u7(0)),
+            RawShortMessage::control_change(ch(0), cn(101), u7(3)),
+            RawShortMessage::control_change(ch(0), cn(100), u7(36)),
+            RawShortMessage::note_on(ch(0), key_number(60), u7(100)),
+            RawShortMessage::control_change(ch(1), cn(100), u7(0)),
+            RawShortMessage::control_change(ch(1), cn(6), u7(2)),
+            RawShortMessage::control_change(ch(0), cn(38), u7(24)),
+            RawShortMessage::control_change(ch(0), cn(6), u7(117)),
+        ];
+        // When
+        let results = scanner.feed_all(msgs.iter().copied());
+        let mut results_into = vec![ParameterNumberMessage::registered_7_bit(
+            ch(9),
+            u14(9),
+            u7(9),
+        )];
+        ParameterNumberMessageScanner::new().feed_all_into(msgs, &mut results_into);
+        // Then
+        let expected = vec![
+            ParameterNumberMessage::registered_7_bit(ch(1), u14(0), u7(2)),
+            ParameterNumberMessage::registered_14_bit(ch(0), u14(420), u14(15000)),
+        ];
+        assert_eq!(results, expected);
+        assert_eq!(results_into[1..], expected[..]);
+    }
+
+    #[test]
+    fn would_complete_should_not_change_progress() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(3)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(36)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(38), u7(24)));
+        let data_entry_msb = RawShortMessage::control_change(ch(0), cn(6), u7(117));
+        // When
+        // Then
+        assert!(!scanner.would_complete(&RawShortMessage::control_change(ch(1), cn(6), u7(117))));
+        assert!(!scanner.would_complete(&RawShortMessage::control_change(ch(0), cn(38), u7(1))));
+        assert!(scanner.would_complete(&data_entry_msb));
+        assert!(scanner.would_complete(&data_entry_msb));
+        assert_eq!(
+            scanner.feed(&data_entry_msb),
+            Some(ParameterNumberMessage::registered_14_bit(
+                ch(0),
+                u14(420),
+                u14(15000)
+            ))
+        );
+    }
+
+    #[test]
+    fn should_continue_sequence_after_system_reset_by_default() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(3)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(36)));
+        scanner.feed(&RawShortMessage::system_reset());
+        let result = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(117)));
+        // Then
+        assert_eq!(
+            result,
+            Some(ParameterNumberMessage::registered_7_bit(
+                ch(0),
+                u14(420),
+                u7(117)
+            ))
+        );
+    }
+
+    #[test]
+    fn should_abandon_sequence_on_system_reset_if_enabled() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.set_boundary_on_system_messages(true);
+        // When
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(3)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(36)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(38), u7(24)));
+        let result_1 = scanner.feed(&RawShortMessage::system_reset());
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(117)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+    }
+
+    #[test]
+    fn should_not_abandon_sequence_on_timing_clock_if_enabled() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.set_boundary_on_system_messages(true);
+        // When
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(3)));
+        scanner.feed(&RawShortMessage::timing_clock());
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(36)));
+        let result = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(117)));
+        // Then
+        assert_eq!(
+            result,
+            Some(ParameterNumberMessage::registered_7_bit(
+                ch(0),
+                u14(420),
+                u7(117)
+            ))
+        );
+    }
+
+    #[test]
+    fn reset_channel_should_leave_other_channels_intact() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(99), u7(3)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(98), u7(37)));
+        scanner.feed(&RawShortMessage::control_change(ch(2), cn(99), u7(3)));
+        scanner.feed(&RawShortMessage::control_change(ch(2), cn(98), u7(37)));
+        // When
+        scanner.reset_channel(ch(0));
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(2)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(2), cn(6), u7(2)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(ParameterNumberMessage::non_registered_7_bit(
+                ch(2),
+                u14(421),
+                u7(2)
+            ))
+        );
+    }
+
+    #[test]
+    fn abort_channel_should_return_partial_data() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.feed(&RawShortMessage::control_change(ch(2), cn(99), u7(3)));
+        scanner.feed(&RawShortMessage::control_change(ch(2), cn(98), u7(37)));
+        scanner.feed(&RawShortMessage::control_change(ch(2), cn(38), u7(65)));
+        scanner.feed(&RawShortMessage::control_change(ch(3), cn(101), u7(1)));
+        // When
+        let partial = scanner.abort_channel(ch(2));
+        // Then
+        assert_eq!(partial.number_msb(), Some(u7(3)));
+        assert_eq!(partial.number_lsb(), Some(u7(37)));
+        assert_eq!(partial.number(), Some(u14(421)));
+        assert!(!partial.is_registered());
+        assert_eq!(partial.value_lsb(), Some(u7(65)));
+        assert!(!partial.is_empty());
+        assert!(scanner.abort_channel(ch(2)).is_empty());
+        assert_eq!(
+            scanner.feed(&RawShortMessage::control_change(ch(2), cn(6), u7(2))),
+            None
+        );
+        let other_partial = scanner.abort_channel(ch(3));
+        assert_eq!(other_partial.number_msb(), Some(u7(1)));
+        assert_eq!(other_partial.number(), None);
+        assert!(other_partial.is_registered());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_continue_scanning_after_checkpoint_restore() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(3)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(36)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(38), u7(24)));
+        // When
+        let checkpoint = serde_json::to_string(&scanner).unwrap();
+        let mut restored: ParameterNumberMessageScanner =
+            serde_json::from_str(&checkpoint).unwrap();
+        let result = restored.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(117)));
+        // Then
+        assert_eq!(restored.abort_channel(ch(0)), scanner.abort_channel(ch(0)));
+        assert_eq!(
+            result,
+            Some(ParameterNumberMessage::registered_14_bit(
+                ch(0),
+                u14(420),
+                u14(15000)
+            ))
+        );
+    }
+
+    #[test]
+    fn feed_iter_should_work_with_infinite_source() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        let source = (0..).flat_map(|i| {
+            let msgs: [Option<RawShortMessage>; 4] =
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(i % 16384), u7(64))
+                    .to_short_messages();
+            IntoIterator::into_iter(msgs).flatten()
+        });
+        // When
+        let results: Vec<_> = scanner.feed_iter(source).take(3).collect();
+        // Then
+        assert_eq!(
+            results,
+            vec![
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(0), u7(64)),
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(1), u7(64)),
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(2), u7(64)),
+            ]
+        );
+    }
+
+    #[test]
+    fn feed_iter_should_keep_partial_state() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        let results_1: Vec<_> = scanner
+            .feed_iter(vec![
+                RawShortMessage::control_change(ch(0), cn(101), u7(3)),
+                RawShortMessage::control_change(ch(0), cn(100), u7(36)),
+            ])
+            .collect();
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(117)));
+        // Then
+        assert_eq!(results_1, vec![]);
+        assert_eq!(
+            result_2,
+            Some(ParameterNumberMessage::registered_7_bit(
+                ch(0),
+                u14(420),
+                u7(117)
+            ))
+        );
+    }
+
+    #[test]
+    fn max_latency_messages() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        let msgs = [
+            RawShortMessage::control_change(ch(0), cn(99), u7(3)),
+            RawShortMessage::control_change(ch(0), cn(98), u7(37)),
+            RawShortMessage::control_change(ch(0), cn(38), u7(65)),
+            RawShortMessage::control_change(ch(0), cn(6), u7(2)),
+        ];
+        // When
+        let results: Vec<_> = msgs.iter().map(|m| scanner.feed(m)).collect();
+        // Then
+        assert_eq!(scanner.max_latency_messages(), 4);
+        assert_eq!(ParameterNumberMessageScanner::MAX_LATENCY_MESSAGES, 4);
+        assert!(results[..3].iter().all(|r| r.is_none()));
+        assert!(results[3].is_some());
+    }
+
+    #[test]
+    fn should_apply_channel_specific_config() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.configure_channel(
+            ch(0),
+            ChannelScanConfig {
+                eager_emit: true,
+                ..Default::default()
+            },
+        );
+        scanner.configure_channel(
+            ch(1),
+            ChannelScanConfig {
+                strict: true,
+                ..Default::default()
+            },
+        );
+        // When
+        let results_0: Vec<_> = scanner
+            .feed_iter(vec![
+                RawShortMessage::control_change(ch(0), cn(99), u7(3)),
+                RawShortMessage::control_change(ch(0), cn(98), u7(37)),
+                RawShortMessage::control_change(ch(0), cn(6), u7(2)),
+                RawShortMessage::control_change(ch(0), cn(38), u7(65)),
+            ])
+            .collect();
+        let results_1: Vec<_> = scanner
+            .feed_iter(vec![
+                RawShortMessage::control_change(ch(1), cn(99), u7(3)),
+                RawShortMessage::control_change(ch(1), cn(98), u7(37)),
+                RawShortMessage::control_change(ch(1), cn(7), u7(100)),
+                RawShortMessage::control_change(ch(1), cn(6), u7(2)),
+                RawShortMessage::control_change(ch(1), cn(38), u7(65)),
+            ])
+            .collect();
+        // Then
+        assert_eq!(
+            results_0,
+            vec![
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(421), u7(2)),
+                ParameterNumberMessage::non_registered_14_bit(ch(0), u14(421), u14(321)),
+            ]
+        );
+        assert_eq!(results_1, vec![]);
+        assert!(scanner.channel_config(ch(0)).eager_emit);
+        assert!(scanner.channel_config(ch(1)).strict);
+        assert_eq!(scanner.channel_config(ch(2)), ChannelScanConfig::default());
+    }
+
+    #[test]
+    fn should_forget_number_after_emit_if_reuse_disabled() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.set_config(ChannelScanConfig {
+            reuse_number: false,
+            ..Default::default()
+        });
+        // When
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(3)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(36)));
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(117)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(118)));
+        // Then
+        assert_eq!(
+            result_1,
+            Some(ParameterNumberMessage::registered_7_bit(
+                ch(0),
+                u14(420),
+                u7(117)
+            ))
+        );
+        assert_eq!(result_2, None);
+    }
+
+    #[test]
+    fn should_report_null_with_previous_number() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        let result_1 = scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(101), u7(0)));
+        let result_2 = scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(100), u7(0)));
+        let result_3 = scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(6), u7(12)));
+        let result_4 =
+            scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(101), u7(127)));
+        let result_5 =
+            scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(100), u7(127)));
+        let result_6 = scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(6), u7(13)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(
+            result_3,
+            Some(ParameterNumberEvent::Message(
+                ParameterNumberMessage::registered_7_bit(ch(0), u14(0), u7(12))
+            ))
+        );
+        assert_eq!(result_4, None);
+        assert_eq!(
+            result_5,
+            Some(ParameterNumberEvent::Null {
+                channel: ch(0),
+                previous_number: Some(u14(0))
+            })
+        );
+        assert_eq!(result_6, None);
+    }
+
+    #[test]
+    fn should_report_null_without_previous_number() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        let result_1 =
+            scanner.feed_event(&RawShortMessage::control_change(ch(4), cn(101), u7(127)));
+        let result_2 =
+            scanner.feed_event(&RawShortMessage::control_change(ch(4), cn(100), u7(127)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(ParameterNumberEvent::Null {
+                channel: ch(4),
+                previous_number: None
+            })
+        );
+    }
+
+    #[test]
+    fn should_handle_null_interleaved_across_channels() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(101), u7(0)));
+        scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(100), u7(0)));
+        scanner.feed_event(&RawShortMessage::control_change(ch(1), cn(101), u7(0)));
+        scanner.feed_event(&RawShortMessage::control_change(ch(1), cn(100), u7(1)));
+        // When
+        let result_1 =
+            scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(101), u7(127)));
+        let result_2 = scanner.feed_event(&RawShortMessage::control_change(ch(1), cn(6), u7(64)));
+        let result_3 =
+            scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(100), u7(127)));
+        let result_4 = scanner.feed_event(&RawShortMessage::control_change(ch(0), cn(6), u7(2)));
+        let result_5 = scanner.feed_event(&RawShortMessage::control_change(ch(1), cn(6), u7(65)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(ParameterNumberEvent::Message(
+                ParameterNumberMessage::registered_7_bit(ch(1), u14(1), u7(64))
+            ))
+        );
+        assert_eq!(
+            result_3,
+            Some(ParameterNumberEvent::Null {
+                channel: ch(0),
+                previous_number: Some(u14(0))
+            })
+        );
+        assert_eq!(result_4, None);
+        assert_eq!(
+            result_5,
+            Some(ParameterNumberEvent::Message(
+                ParameterNumberMessage::registered_7_bit(ch(1), u14(1), u7(65))
+            ))
+        );
+        assert!(scanner.abort_channel(ch(0)).is_empty());
+    }
+
+    #[test]
+    fn should_accept_new_selection_after_null() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(127)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(127)));
+        // When
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(0)));
+        scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(2)));
+        let result = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(64)));
+        // Then
+        assert_eq!(
+            result,
+            Some(ParameterNumberMessage::registered_7_bit(
+                ch(0),
+                u14(2),
+                u7(64)
+            ))
+        );
+    }
+
+    #[test]
+    fn should_return_relative_message_on_data_increment_and_decrement() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(99), u7(3)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(98), u7(37)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(96), u7(0)));
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(97), u7(5)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        let result_3 = result_3.unwrap();
+        assert_eq!(result_3.kind(), ParameterNumberMessageKind::Increment);
+        assert_eq!(result_3.channel(), ch(0));
+        assert_eq!(result_3.number(), u14(421));
+        assert_eq!(result_3.value(), u14(0));
+        assert!(!result_3.is_registered());
+        let result_4 = result_4.unwrap();
+        assert_eq!(result_4.kind(), ParameterNumberMessageKind::Decrement);
+        assert_eq!(result_4.number(), u14(421));
+        assert_eq!(result_4.value(), u14(5));
+    }
+
+    #[test]
+    fn should_ignore_data_increment_without_selected_number() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(96), u7(0)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(99), u7(3)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(97), u7(0)));
+        let result_4 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(127)));
+        let result_5 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(127)));
+        let result_6 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(96), u7(0)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(result_4, None);
+        assert_eq!(result_5, None);
+        assert_eq!(result_6, None);
+    }
+
+    #[test]
+    fn should_accumulate_increment_sweep() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.set_config(ChannelScanConfig {
+            accumulate: true,
+            ..Default::default()
+        });
+        // When
+        let results: Vec<_> = scanner
+            .feed_iter(vec![
+                RawShortMessage::control_change(ch(0), cn(99), u7(9)),
+                RawShortMessage::control_change(ch(0), cn(98), u7(82)),
+                RawShortMessage::control_change(ch(0), cn(6), u7(125)),
+                RawShortMessage::control_change(ch(0), cn(96), u7(0)),
+                RawShortMessage::control_change(ch(0), cn(96), u7(0)),
+                RawShortMessage::control_change(ch(0), cn(96), u7(5)),
+                RawShortMessage::control_change(ch(0), cn(97), u7(3)),
+            ])
+            .collect();
+        // Then
+        assert_eq!(
+            results,
+            vec![
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(1234), u7(125)),
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(1234), u7(126)),
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(1234), u7(127)),
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(1234), u7(127)),
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(1234), u7(124)),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_restart_accumulation_on_new_number() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        scanner.set_config(ChannelScanConfig {
+            accumulate: true,
+            ..Default::default()
+        });
+        // When
+        let results: Vec<_> = scanner
+            .feed_iter(vec![
+                RawShortMessage::control_change(ch(0), cn(99), u7(9)),
+                RawShortMessage::control_change(ch(0), cn(98), u7(82)),
+                RawShortMessage::control_change(ch(0), cn(38), u7(0)),
+                RawShortMessage::control_change(ch(0), cn(6), u7(64)),
+                RawShortMessage::control_change(ch(0), cn(96), u7(0)),
+                RawShortMessage::control_change(ch(0), cn(99), u7(9)),
+                RawShortMessage::control_change(ch(0), cn(98), u7(83)),
+                RawShortMessage::control_change(ch(0), cn(97), u7(0)),
+                RawShortMessage::control_change(ch(0), cn(6), u7(10)),
+                RawShortMessage::control_change(ch(0), cn(97), u7(0)),
+            ])
+            .collect();
+        // Then
+        assert_eq!(
+            results,
+            vec![
+                ParameterNumberMessage::non_registered_14_bit(ch(0), u14(1234), u14(8192)),
+                ParameterNumberMessage::non_registered_14_bit(ch(0), u14(1234), u14(8193)),
+                ParameterNumberMessage::decrement(ch(0), u14(1235), u7(0), false),
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(1235), u7(10)),
+                ParameterNumberMessage::non_registered_7_bit(ch(0), u14(1235), u7(9)),
+            ]
+        );
+    }
+
+    #[test]
+    fn feed_should_not_return_null() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        let result_1 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(101), u7(127)));
+        let result_2 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(100), u7(127)));
+        let result_3 = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(1)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+    }
+
+    #[test]
+    fn feed_batch_should_return_all_completed_messages() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        let msgs = [
+            RawShortMessage::control_change(ch(0), cn(101), u7(3)),
+            RawShortMessage::control_change(ch(0), cn(100), u7(36)),
+            RawShortMessage::control_change(ch(0), cn(6), u7(117)),
+            RawShortMessage::control_change(ch(1), cn(99), u7(3)),
+            RawShortMessage::control_change(ch(1), cn(98), u7(37)),
+            RawShortMessage::control_change(ch(1), cn(38), u7(65)),
+            RawShortMessage::control_change(ch(1), cn(6), u7(2)),
+        ];
+        // When
+        let results = scanner.feed_batch::<4>(&msgs);
+        // Then
+        assert_eq!(
+            results,
+            [
+                Some(ParameterNumberMessage::registered_7_bit(
+                    ch(0),
+                    u14(420),
+                    u7(117)
+                )),
+                Some(ParameterNumberMessage::non_registered_14_bit(
+                    ch(1),
+                    u14(421),
+                    u14(321)
+                )),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn feed_batch_should_drop_excess_messages() {
+        // Given
+        let mut scanner = ParameterNumberMessageScanner::new();
+        let msgs = [
+            RawShortMessage::control_change(ch(0), cn(101), u7(3)),
+            RawShortMessage::control_change(ch(0), cn(100), u7(36)),
+            RawShortMessage::control_change(ch(0), cn(6), u7(117)),
+            RawShortMessage::control_change(ch(0), cn(6), u7(118)),
+            RawShortMessage::control_change(ch(0), cn(38), u7(1)),
+        ];
+        // When
+        let results = scanner.feed_batch::<1>(&msgs);
+        let result = scanner.feed(&RawShortMessage::control_change(ch(0), cn(6), u7(119)));
+        // Then
+        assert_eq!(
+            results,
+            [Some(ParameterNumberMessage::registered_7_bit(
+                ch(0),
+                u14(420),
+                u7(117)
+            ))]
+        );
+        assert_eq!(
+            result,
+            Some(ParameterNumberMessage::registered_14_bit(
+                ch(0),
+                u14(420),
+                u14(119 * 128 + 1)
+            ))
+        );
+    }
 }
@@ -0,0 +1,258 @@
+use crate::bit_util::{
+    extract_high_7_bit_value_from_14_bit_value, extract_low_7_bit_value_from_14_bit_value,
+};
+use crate::{
+    controller_numbers, Channel, ControllerNumber, ParameterNumberMessage, RawShortMessage,
+    ShortMessageFactory, U14, U7,
+};
+
+/// Builder for a complete (N)RPN transaction, that is selecting a parameter number, optionally
+/// changing its value and optionally deselecting it again.
+///
+/// In contrast to [`ParameterNumberMessage`], which always carries exactly one value change, a
+/// transaction can also just select a parameter number, and it can end with the RPN Null
+/// sequence. [`build`] checks that the collected parts are consistent before producing any short
+/// messages.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, u14, u7};
+/// use helgoboss_midi::ParameterNumberTransaction;
+///
+/// let msgs = ParameterNumberTransaction::new(channel(0))
+///     .select_non_registered(u14(1234))
+///     .value_7_bit(u7(100))
+///     .null_tail()
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     msgs,
+///     vec![
+///         control_change(0, 99, 9),
+///         control_change(0, 98, 82),
+///         control_change(0, 6, 100),
+///         control_change(0, 101, 127),
+///         control_change(0, 100, 127),
+///     ]
+/// );
+/// ```
+///
+/// [`ParameterNumberMessage`]: struct.ParameterNumberMessage.html
+/// [`build`]: #method.build
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ParameterNumberTransaction {
+    channel: Channel,
+    number: Option<U14>,
+    is_registered: bool,
+    value: Option<TransactionValue>,
+    relative: Option<(U7, bool)>,
+    null_tail: bool,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum TransactionValue {
+    SevenBit(U7),
+    FourteenBit(U14),
+}
+
+/// An error which can be returned when building an inconsistent [`ParameterNumberTransaction`].
+///
+/// [`ParameterNumberTransaction`]: struct.ParameterNumberTransaction.html
+#[derive(Clone, Eq, PartialEq, Debug, derive_more::Display)]
+#[display(fmt = "inconsistent parameter number transaction")]
+pub struct BuildParameterNumberTransactionError(pub(crate) ());
+
+impl std::error::Error for BuildParameterNumberTransactionError {}
+
+impl ParameterNumberTransaction {
+    /// Starts an empty transaction on the given channel.
+    pub fn new(channel: Channel) -> ParameterNumberTransaction {
+        ParameterNumberTransaction {
+            channel,
+            number: None,
+            is_registered: false,
+            value: None,
+            relative: None,
+            null_tail: false,
+        }
+    }
+
+    /// Selects the given registered parameter number.
+    pub fn select_registered(mut self, number: U14) -> ParameterNumberTransaction {
+        self.number = Some(number);
+        self.is_registered = true;
+        self
+    }
+
+    /// Selects the given non-registered parameter number.
+    pub fn select_non_registered(mut self, number: U14) -> ParameterNumberTransaction {
+        self.number = Some(number);
+        self.is_registered = false;
+        self
+    }
+
+    /// Sets the value of the selected parameter number to the given 7-bit value.
+    pub fn value_7_bit(mut self, value: U7) -> ParameterNumberTransaction {
+        self.value = Some(TransactionValue::SevenBit(value));
+        self
+    }
+
+    /// Sets the value of the selected parameter number to the given 14-bit value.
+    pub fn value_14_bit(mut self, value: U14) -> ParameterNumberTransaction {
+        self.value = Some(TransactionValue::FourteenBit(value));
+        self
+    }
+
+    /// Increments the value of the selected parameter number via Data Increment.
+    pub fn increment(mut self, amount: U7) -> ParameterNumberTransaction {
+        self.relative = Some((amount, true));
+        self
+    }
+
+    /// Decrements the value of the selected parameter number via Data Decrement.
+    pub fn decrement(mut self, amount: U7) -> ParameterNumberTransaction {
+        self.relative = Some((amount, false));
+        self
+    }
+
+    /// Appends the RPN Null sequence, which deselects the parameter number again.
+    pub fn null_tail(mut self) -> ParameterNumberTransaction {
+        self.null_tail = true;
+        self
+    }
+
+    /// Validates the transaction and returns its short messages in the order in which they must
+    /// be sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no parameter number has been selected or if both an absolute value and
+    /// an increment or decrement have been set.
+    pub fn build(&self) -> Result<Vec<RawShortMessage>, BuildParameterNumberTransactionError> {
+        let number = self
+            .number
+            .ok_or(BuildParameterNumberTransactionError(()))?;
+        let msg = match (self.value, self.relative) {
+            (Some(_), Some(_)) => return Err(BuildParameterNumberTransactionError(())),
+            (Some(TransactionValue::SevenBit(value)), None) => Some(if self.is_registered {
+                ParameterNumberMessage::registered_7_bit(self.channel, number, value)
+            } else {
+                ParameterNumberMessage::non_registered_7_bit(self.channel, number, value)
+            }),
+            (Some(TransactionValue::FourteenBit(value)), None) => Some(if self.is_registered {
+                ParameterNumberMessage::registered_14_bit(self.channel, number, value)
+            } else {
+                ParameterNumberMessage::non_registered_14_bit(self.channel, number, value)
+            }),
+            (None, Some((amount, true))) => Some(ParameterNumberMessage::increment(
+                self.channel,
+                number,
+                amount,
+                self.is_registered,
+            )),
+            (None, Some((amount, false))) => Some(ParameterNumberMessage::decrement(
+                self.channel,
+                number,
+                amount,
+                self.is_registered,
+            )),
+            (None, None) => None,
+        };
+        let mut messages: Vec<RawShortMessage> = match msg {
+            Some(msg) => IntoIterator::into_iter(msg.to_short_messages())
+                .flatten()
+                .collect(),
+            None => self.number_messages(number),
+        };
+        if self.null_tail {
+            messages.push(
+                self.control_change(controller_numbers::REGISTERED_PARAMETER_NUMBER_MSB, U7::MAX),
+            );
+            messages.push(
+                self.control_change(controller_numbers::REGISTERED_PARAMETER_NUMBER_LSB, U7::MAX),
+            );
+        }
+        Ok(messages)
+    }
+
+    fn number_messages(&self, number: U14) -> Vec<RawShortMessage> {
+        use controller_numbers::*;
+        let (msb, lsb) = if self.is_registered {
+            (
+                REGISTERED_PARAMETER_NUMBER_MSB,
+                REGISTERED_PARAMETER_NUMBER_LSB,
+            )
+        } else {
+            (
+                NON_REGISTERED_PARAMETER_NUMBER_MSB,
+                NON_REGISTERED_PARAMETER_NUMBER_LSB,
+            )
+        };
+        vec![
+            self.control_change(msb, extract_high_7_bit_value_from_14_bit_value(number)),
+            self.control_change(lsb, extract_low_7_bit_value_from_14_bit_value(number)),
+        ]
+    }
+
+    fn control_change(&self, controller_number: ControllerNumber, value: U7) -> RawShortMessage {
+        RawShortMessage::control_change(self.channel, controller_number, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u14, u7};
+
+    #[test]
+    fn should_fail_if_combining_increment_and_absolute_value() {
+        // Given
+        let transaction = ParameterNumberTransaction::new(ch(0))
+            .select_non_registered(u14(1234))
+            .value_14_bit(u14(1000))
+            .increment(u7(0));
+        // When
+        let result = transaction.build();
+        // Then
+        assert_eq!(result, Err(BuildParameterNumberTransactionError(())));
+    }
+
+    #[test]
+    fn should_fail_if_no_number_selected() {
+        // Given
+        let transaction = ParameterNumberTransaction::new(ch(0)).value_7_bit(u7(5));
+        // When
+        let result = transaction.build();
+        // Then
+        assert_eq!(result, Err(BuildParameterNumberTransactionError(())));
+    }
+
+    #[test]
+    fn should_build_selection_only_and_increment() {
+        // Given
+        let select = ParameterNumberTransaction::new(ch(1)).select_registered(u14(0));
+        let increment = select.decrement(u7(0)).null_tail();
+        // When
+        let select_result = select.build();
+        let increment_result = increment.build();
+        // Then
+        assert_eq!(
+            select_result,
+            Ok(vec![
+                RawShortMessage::control_change(ch(1), cn(101), u7(0)),
+                RawShortMessage::control_change(ch(1), cn(100), u7(0)),
+            ])
+        );
+        assert_eq!(
+            increment_result,
+            Ok(vec![
+                RawShortMessage::control_change(ch(1), cn(101), u7(0)),
+                RawShortMessage::control_change(ch(1), cn(100), u7(0)),
+                RawShortMessage::control_change(ch(1), cn(97), u7(0)),
+                RawShortMessage::control_change(ch(1), cn(101), u7(127)),
+                RawShortMessage::control_change(ch(1), cn(100), u7(127)),
+            ])
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use crate::U14;
+
+/// Pitch bend value which means no bend.
+const CENTER: i32 = 8192;
+
+impl U14 {
+    /// Creates a pitch bend value which bends by the given number of semitones, given the pitch
+    /// bend range of the receiver.
+    ///
+    /// `range_semitones` (often 2 semitones) is the bend which is reached by the maximum and
+    /// minimum pitch bend value. Bends exceeding the range are clamped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::U14;
+    ///
+    /// assert_eq!(U14::from_pitch_bend_semitones(0.0, 2.0), U14::new(8192));
+    /// assert_eq!(U14::from_pitch_bend_semitones(-1.0, 2.0), U14::new(4096));
+    /// assert_eq!(U14::from_pitch_bend_semitones(5.0, 2.0), U14::MAX);
+    /// ```
+    pub fn from_pitch_bend_semitones(semitones: f64, range_semitones: f64) -> U14 {
+        let max_offset = if semitones < 0.0 { CENTER } else { CENTER - 1 };
+        let offset = (semitones / range_semitones * max_offset as f64).round();
+        let value = (CENTER as f64 + offset).clamp(0.0, U14::MAX.get() as f64);
+        U14(value as u16)
+    }
+
+    /// Interprets this value as pitch bend value and converts it to semitones, given the pitch
+    /// bend range of the receiver.
+    ///
+    /// The center value 8192 means no bend, 0 means `-range_semitones` and 16383 means
+    /// `+range_semitones`.
+    pub fn to_pitch_bend_semitones(self, range_semitones: f64) -> f64 {
+        let offset = self.0 as i32 - CENTER;
+        let max_offset = if offset < 0 { CENTER } else { CENTER - 1 };
+        offset as f64 / max_offset as f64 * range_semitones
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::u14;
+    use crate::U14;
+
+    #[test]
+    fn center() {
+        // Given
+        // When
+        // Then
+        assert_eq!(u14(8192).to_pitch_bend_semitones(2.0), 0.0);
+        assert_eq!(U14::from_pitch_bend_semitones(0.0, 2.0), u14(8192));
+    }
+
+    #[test]
+    fn full_up_and_down() {
+        // Given
+        // When
+        // Then
+        assert_eq!(u14(16383).to_pitch_bend_semitones(2.0), 2.0);
+        assert_eq!(u14(0).to_pitch_bend_semitones(2.0), -2.0);
+        assert_eq!(U14::from_pitch_bend_semitones(2.0, 2.0), u14(16383));
+        assert_eq!(U14::from_pitch_bend_semitones(-2.0, 2.0), u14(0));
+        assert_eq!(U14::from_pitch_bend_semitones(2.5, 2.0), u14(16383));
+        assert_eq!(U14::from_pitch_bend_semitones(-2.5, 2.0), u14(0));
+    }
+
+    #[test]
+    fn round_trip() {
+        // Given
+        // When
+        // Then
+        for value in (0..=16383).step_by(7) {
+            let semitones = u14(value).to_pitch_bend_semitones(12.0);
+            assert_eq!(U14::from_pitch_bend_semitones(semitones, 12.0), u14(value));
+        }
+    }
+}
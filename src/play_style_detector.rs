@@ -0,0 +1,208 @@
+use crate::{Channel, NoteStateTracker, ShortMessage, ShortMessageType};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The playing style detected by [`PlayStyleDetector`].
+///
+/// [`PlayStyleDetector`]: struct.PlayStyleDetector.html
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PlayStyle {
+    /// Notes are played one after another (including legato).
+    Monophonic,
+    /// Notes overlap, e.g. chords.
+    Polyphonic,
+}
+
+/// Detector for finding out whether notes are played monophonically or polyphonically.
+///
+/// Notes overlap if at least 2 notes are held at the same time (on any channel) for longer than
+/// the legato time. Short overlaps are typical for legato playing and therefore still count as
+/// monophonic. The playing style is polyphonic if an overlap has been detected within the window
+/// and monophonic otherwise. Because the detector works with the times of the fed messages only,
+/// an overlap is noticed as soon as a message arrives after the legato time has passed, e.g. the
+/// Note Off of one of the overlapping notes.
+///
+/// By default, the legato time is 50 ms and the window is 2 s.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{note_off, note_on};
+/// use helgoboss_midi::{PlayStyle, PlayStyleDetector};
+/// use std::time::Duration;
+///
+/// let mut detector = PlayStyleDetector::new();
+/// let ms = Duration::from_millis;
+/// assert_eq!(detector.feed(ms(0), &note_on(0, 60, 100)), Some(PlayStyle::Monophonic));
+/// assert_eq!(detector.feed(ms(1), &note_on(0, 64, 100)), None);
+/// assert_eq!(detector.feed(ms(500), &note_off(0, 60, 0)), Some(PlayStyle::Polyphonic));
+/// ```
+///
+/// [`PlayStyle`]: enum.PlayStyle.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PlayStyleDetector {
+    window: Duration,
+    legato_time: Duration,
+    note_state_tracker: NoteStateTracker,
+    overlap_start_time: Option<Duration>,
+    last_overlap_time: Option<Duration>,
+    play_style: Option<PlayStyle>,
+}
+
+impl Default for PlayStyleDetector {
+    fn default() -> Self {
+        PlayStyleDetector {
+            window: Duration::from_secs(2),
+            legato_time: Duration::from_millis(50),
+            note_state_tracker: Default::default(),
+            overlap_start_time: None,
+            last_overlap_time: None,
+            play_style: None,
+        }
+    }
+}
+
+impl PlayStyleDetector {
+    /// Creates a new detector with a window of 2 s and a legato time of 50 ms.
+    pub fn new() -> PlayStyleDetector {
+        Default::default()
+    }
+
+    /// Sets how long the playing style stays polyphonic after the last overlap.
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Sets how long notes may overlap without being considered polyphonic.
+    pub fn set_legato_time(&mut self, legato_time: Duration) {
+        self.legato_time = legato_time;
+    }
+
+    /// Returns the currently detected playing style or `None` if no note has been played yet.
+    pub fn play_style(&self) -> Option<PlayStyle> {
+        self.play_style
+    }
+
+    /// Feeds the detector a single short message which occurred at the given time.
+    ///
+    /// Returns the new playing style if it has changed. Times must not decrease.
+    pub fn feed(&mut self, time: Duration, msg: &impl ShortMessage) -> Option<PlayStyle> {
+        self.note_state_tracker.feed(msg);
+        let held_note_count: usize = (0..16)
+            .map(|i| self.note_state_tracker.held_notes(Channel(i)).count())
+            .sum();
+        // The overlap is checked one last time when it ends
+        let overlap_start_time = if held_note_count >= 2 {
+            Some(*self.overlap_start_time.get_or_insert(time))
+        } else {
+            self.overlap_start_time.take()
+        };
+        if let Some(t) = overlap_start_time {
+            if time - t > self.legato_time {
+                self.last_overlap_time = Some(time);
+            }
+        }
+        if self.play_style.is_none() && msg.r#type() != ShortMessageType::NoteOn {
+            return None;
+        }
+        let play_style = match self.last_overlap_time {
+            Some(t) if time - t <= self.window => PlayStyle::Polyphonic,
+            _ => PlayStyle::Monophonic,
+        };
+        if self.play_style == Some(play_style) {
+            return None;
+        }
+        self.play_style = Some(play_style);
+        Some(play_style)
+    }
+
+    /// Resets the detector forgetting all notes and the detected playing style.
+    pub fn reset(&mut self) {
+        *self = PlayStyleDetector {
+            window: self.window,
+            legato_time: self.legato_time,
+            ..Default::default()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, key_number as kn, u7};
+    use crate::{RawShortMessage, ShortMessageFactory};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn note_on(key_number: u8) -> RawShortMessage {
+        RawShortMessage::note_on(ch(0), kn(key_number), u7(100))
+    }
+
+    fn note_off(key_number: u8) -> RawShortMessage {
+        RawShortMessage::note_off(ch(0), kn(key_number), u7(0))
+    }
+
+    #[test]
+    fn should_detect_overlapping_notes_as_polyphonic() {
+        // Given
+        let mut detector = PlayStyleDetector::new();
+        // When
+        let result_1 = detector.feed(ms(0), &note_on(60));
+        let result_2 = detector.feed(ms(5), &note_on(64));
+        let result_3 = detector.feed(ms(10), &note_on(67));
+        let result_4 = detector.feed(ms(400), &note_off(60));
+        let result_5 = detector.feed(ms(410), &note_off(64));
+        let result_6 = detector.feed(ms(420), &note_off(67));
+        // Then
+        assert_eq!(result_1, Some(PlayStyle::Monophonic));
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(result_4, Some(PlayStyle::Polyphonic));
+        assert_eq!(result_5, None);
+        assert_eq!(result_6, None);
+        assert_eq!(detector.play_style(), Some(PlayStyle::Polyphonic));
+    }
+
+    #[test]
+    fn should_detect_sequential_and_legato_notes_as_monophonic() {
+        // Given
+        let mut detector = PlayStyleDetector::new();
+        // When
+        let result_1 = detector.feed(ms(0), &note_on(60));
+        let result_2 = detector.feed(ms(200), &note_off(60));
+        let result_3 = detector.feed(ms(300), &note_on(62));
+        // Legato: next note starts shortly before the previous one is released
+        let result_4 = detector.feed(ms(480), &note_on(64));
+        let result_5 = detector.feed(ms(500), &note_off(62));
+        let result_6 = detector.feed(ms(700), &note_off(64));
+        // Then
+        assert_eq!(result_1, Some(PlayStyle::Monophonic));
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(result_4, None);
+        assert_eq!(result_5, None);
+        assert_eq!(result_6, None);
+        assert_eq!(detector.play_style(), Some(PlayStyle::Monophonic));
+    }
+
+    #[test]
+    fn should_switch_back_to_monophonic_after_window() {
+        // Given
+        let mut detector = PlayStyleDetector::new();
+        detector.feed(ms(0), &note_on(60));
+        detector.feed(ms(0), &note_on(64));
+        detector.feed(ms(100), &note_off(60));
+        detector.feed(ms(100), &note_off(64));
+        assert_eq!(detector.play_style(), Some(PlayStyle::Polyphonic));
+        // When
+        let result_1 = detector.feed(ms(1000), &note_on(60));
+        let result_2 = detector.feed(ms(2200), &note_on(62));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, Some(PlayStyle::Monophonic));
+    }
+}
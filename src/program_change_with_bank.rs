@@ -0,0 +1,132 @@
+use crate::bit_util::{
+    extract_high_7_bit_value_from_14_bit_value, extract_low_7_bit_value_from_14_bit_value,
+};
+use crate::{controller_numbers, Channel, ShortMessageFactory, U14, U7};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A Program Change message which is optionally preceded by a Bank Select.
+///
+/// Selecting a patch usually means sending Bank Select MSB (controller 0), Bank Select LSB
+/// (controller 32) and then Program Change. The [`ProgramChangeWithBankScanner`] can be used to
+/// extract such messages from a stream of [`ShortMessage`]s.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, program_change, u14, u7};
+/// use helgoboss_midi::{ProgramChangeWithBank, RawShortMessage};
+///
+/// let msg = ProgramChangeWithBank::new(channel(0), Some(u14(130)), u7(5));
+/// let short_messages: [Option<RawShortMessage>; 3] = msg.to_short_messages();
+/// assert_eq!(
+///     short_messages,
+///     [
+///         Some(control_change(0, 0, 1)),
+///         Some(control_change(0, 32, 2)),
+///         Some(program_change(0, 5))
+///     ]
+/// );
+/// ```
+///
+/// [`ShortMessage`]: trait.ShortMessage.html
+/// [`ProgramChangeWithBankScanner`]: struct.ProgramChangeWithBankScanner.html
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProgramChangeWithBank {
+    channel: Channel,
+    bank: Option<U14>,
+    program: U7,
+}
+
+impl ProgramChangeWithBank {
+    /// Creates a Program Change message, preceded by a Bank Select if `bank` is given.
+    pub fn new(channel: Channel, bank: Option<U14>, program: U7) -> ProgramChangeWithBank {
+        ProgramChangeWithBank {
+            channel,
+            bank,
+            program,
+        }
+    }
+
+    /// Returns the channel of this message.
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    /// Returns the selected bank or `None` if this is a bare Program Change.
+    pub fn bank(&self) -> Option<U14> {
+        self.bank
+    }
+
+    /// Returns the program number.
+    pub fn program(&self) -> U7 {
+        self.program
+    }
+
+    /// Translates this message into up to 3 short messages, which need to be sent in a row.
+    ///
+    /// If there's no bank, the first 2 messages are `None` and only the Program Change is sent.
+    pub fn to_short_messages<T: ShortMessageFactory>(&self) -> [Option<T>; 3] {
+        let (bank_msb, bank_lsb) = match self.bank {
+            Some(bank) => (
+                Some(T::control_change(
+                    self.channel,
+                    controller_numbers::BANK_SELECT,
+                    extract_high_7_bit_value_from_14_bit_value(bank),
+                )),
+                Some(T::control_change(
+                    self.channel,
+                    controller_numbers::BANK_SELECT_LSB,
+                    extract_low_7_bit_value_from_14_bit_value(bank),
+                )),
+            ),
+            None => (None, None),
+        };
+        [
+            bank_msb,
+            bank_lsb,
+            Some(T::program_change(self.channel, self.program)),
+        ]
+    }
+}
+
+impl<T: ShortMessageFactory> From<ProgramChangeWithBank> for [Option<T>; 3] {
+    fn from(msg: ProgramChangeWithBank) -> Self {
+        msg.to_short_messages()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, control_change, program_change, u14, u7};
+    use crate::RawShortMessage;
+
+    #[test]
+    fn full_triple() {
+        // Given
+        let msg = ProgramChangeWithBank::new(ch(9), Some(u14(16383)), u7(127));
+        // When
+        let short_messages: [Option<RawShortMessage>; 3] = msg.to_short_messages();
+        // Then
+        assert_eq!(
+            short_messages,
+            [
+                Some(control_change(9, 0, 127)),
+                Some(control_change(9, 32, 127)),
+                Some(program_change(9, 127))
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_program_change() {
+        // Given
+        let msg = ProgramChangeWithBank::new(ch(1), None, u7(3));
+        // When
+        let short_messages: [Option<RawShortMessage>; 3] = msg.into();
+        // Then
+        assert_eq!(short_messages, [None, None, Some(program_change(1, 3))]);
+    }
+}
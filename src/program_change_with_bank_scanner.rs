@@ -0,0 +1,155 @@
+use crate::bit_util::build_14_bit_value_from_two_7_bit_values;
+use crate::{controller_numbers, ProgramChangeWithBank, ShortMessage, StructuredShortMessage, U7};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Scanner for detecting Program Change messages together with their preceding Bank Select in a
+/// stream of short MIDI messages.
+///
+/// Bank Select messages (controllers 0 and 32) are collected until the next Program Change on
+/// the same channel, which completes the sequence and consumes them. A Program Change without
+/// preceding Bank Select results in a message without bank. If only the bank MSB has been
+/// received, the LSB is assumed to be 0 and vice versa.
+///
+/// In contrast to [`BankTracker`], the bank is not remembered across Program Change messages.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, program_change, u14, u7};
+/// use helgoboss_midi::{ProgramChangeWithBank, ProgramChangeWithBankScanner};
+///
+/// let mut scanner = ProgramChangeWithBankScanner::new();
+/// assert_eq!(scanner.feed(&control_change(0, 0, 1)), None);
+/// assert_eq!(scanner.feed(&control_change(0, 32, 2)), None);
+/// assert_eq!(
+///     scanner.feed(&program_change(0, 5)),
+///     Some(ProgramChangeWithBank::new(channel(0), Some(u14(130)), u7(5)))
+/// );
+/// assert_eq!(
+///     scanner.feed(&program_change(0, 6)),
+///     Some(ProgramChangeWithBank::new(channel(0), None, u7(6)))
+/// );
+/// ```
+///
+/// [`BankTracker`]: struct.BankTracker.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProgramChangeWithBankScanner {
+    scanner_by_channel: [ScannerForOneChannel; 16],
+}
+
+impl ProgramChangeWithBankScanner {
+    /// Creates a new scanner.
+    pub fn new() -> ProgramChangeWithBankScanner {
+        Default::default()
+    }
+
+    /// Feeds the scanner a single short message.
+    ///
+    /// Returns the Program Change message together with its bank if the message is a Program
+    /// Change message.
+    pub fn feed(&mut self, msg: &impl ShortMessage) -> Option<ProgramChangeWithBank> {
+        let channel = msg.channel()?;
+        self.scanner_by_channel[usize::from(channel)].feed(msg)
+    }
+
+    /// Resets the scanner discarding all intermediate scanning progress.
+    pub fn reset(&mut self) {
+        for s in self.scanner_by_channel.iter_mut() {
+            *s = Default::default();
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct ScannerForOneChannel {
+    bank_msb: Option<U7>,
+    bank_lsb: Option<U7>,
+}
+
+impl ScannerForOneChannel {
+    fn feed(&mut self, msg: &impl ShortMessage) -> Option<ProgramChangeWithBank> {
+        match msg.to_structured() {
+            StructuredShortMessage::ControlChange {
+                controller_number,
+                control_value,
+                ..
+            } => {
+                if controller_number == controller_numbers::BANK_SELECT {
+                    self.bank_msb = Some(control_value);
+                } else if controller_number == controller_numbers::BANK_SELECT_LSB {
+                    self.bank_lsb = Some(control_value);
+                }
+                None
+            }
+            StructuredShortMessage::ProgramChange {
+                channel,
+                program_number,
+            } => {
+                let bank = if self.bank_msb.is_none() && self.bank_lsb.is_none() {
+                    None
+                } else {
+                    Some(build_14_bit_value_from_two_7_bit_values(
+                        self.bank_msb.unwrap_or_default(),
+                        self.bank_lsb.unwrap_or_default(),
+                    ))
+                };
+                *self = Default::default();
+                Some(ProgramChangeWithBank::new(channel, bank, program_number))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, control_change, note_on, program_change, u14, u7};
+
+    #[test]
+    fn should_detect_full_triple() {
+        // Given
+        let mut scanner = ProgramChangeWithBankScanner::new();
+        // When
+        let result_1 = scanner.feed(&control_change(4, 0, 2));
+        let result_2 = scanner.feed(&note_on(4, 60, 100));
+        let result_3 = scanner.feed(&control_change(4, 32, 3));
+        let result_4 = scanner.feed(&program_change(4, 10));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(result_3, None);
+        assert_eq!(
+            result_4,
+            Some(ProgramChangeWithBank::new(ch(4), Some(u14(259)), u7(10)))
+        );
+    }
+
+    #[test]
+    fn should_detect_bare_program_change() {
+        // Given
+        let mut scanner = ProgramChangeWithBankScanner::new();
+        // When
+        let result_1 = scanner.feed(&control_change(0, 0, 1));
+        let result_2 = scanner.feed(&program_change(1, 7));
+        let result_3 = scanner.feed(&program_change(0, 8));
+        let result_4 = scanner.feed(&program_change(0, 9));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(
+            result_2,
+            Some(ProgramChangeWithBank::new(ch(1), None, u7(7)))
+        );
+        assert_eq!(
+            result_3,
+            Some(ProgramChangeWithBank::new(ch(0), Some(u14(128)), u7(8)))
+        );
+        assert_eq!(
+            result_4,
+            Some(ProgramChangeWithBank::new(ch(0), None, u7(9)))
+        );
+    }
+}
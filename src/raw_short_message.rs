@@ -1,4 +1,7 @@
-use crate::{FromBytesError, ShortMessage, ShortMessageFactory, U7};
+use crate::{
+    data_byte_count, extract_type_from_status_byte, Channel, ControllerNumber, FromBytesError,
+    KeyNumber, ShortMessage, ShortMessageFactory, U14, U7,
+};
 use derive_more::Into;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -40,6 +43,131 @@ impl ShortMessageFactory for RawShortMessage {
     }
 }
 
+impl RawShortMessage {
+    /// Converts the given MIDI 2.0 Channel Voice message (Universal MIDI Packet message type 0x4,
+    /// two 32-bit words) into the closest MIDI 1.0 short message.
+    ///
+    /// Supports Note Off, Note On, Polyphonic Key Pressure, Control Change, Program Change,
+    /// Channel Pressure and Pitch Bend Change. Returns `None` for other statuses (e.g. (N)RPN or
+    /// per-note messages, which have no single short message equivalent) and for other message
+    /// types. The group is ignored. For Program Change, the optional bank is ignored.
+    ///
+    /// Values are scaled down by simply dropping the least significant bits, e.g. a 32-bit
+    /// controller value is shifted right by 25 bits to obtain a 7-bit value. This is the
+    /// algorithm recommended by the MIDI 2.0 specification. It preserves the minimum, center and
+    /// maximum values, e.g. 0x00000000, 0x80000000 and 0xFFFFFFFF become 0, 64 and 127. Because a
+    /// Note On with velocity 0 means Note Off in MIDI 1.0, a Note On velocity which would be
+    /// scaled down to 0 becomes 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::control_change;
+    /// use helgoboss_midi::RawShortMessage;
+    ///
+    /// // MIDI 2.0 Control Change on group 0, channel 2, controller 7 with center value
+    /// let msg = RawShortMessage::from_ump_64([0x40B2_0700, 0x8000_0000]);
+    /// assert_eq!(msg, Some(control_change(2, 7, 64)));
+    /// ```
+    pub fn from_ump_64(words: [u32; 2]) -> Option<RawShortMessage> {
+        let [word_1, word_2] = words;
+        if word_1 >> 28 != 0x4 {
+            return None;
+        }
+        let channel = Channel(((word_1 >> 16) & 0x0f) as u8);
+        let index = U7(((word_1 >> 8) & 0x7f) as u8);
+        // Works for both 32-bit values and 16-bit velocities (which occupy the upper half)
+        let seven_bit_value = U7((word_2 >> 25) as u8);
+        let msg = match (word_1 >> 20) & 0x0f {
+            0x8 => Self::note_off(channel, KeyNumber(index.0), seven_bit_value),
+            0x9 => Self::note_on(channel, KeyNumber(index.0), seven_bit_value.max(U7(1))),
+            0xa => Self::polyphonic_key_pressure(channel, KeyNumber(index.0), seven_bit_value),
+            0xb => Self::control_change(channel, ControllerNumber(index.0), seven_bit_value),
+            0xc => Self::program_change(channel, U7(((word_2 >> 24) & 0x7f) as u8)),
+            0xd => Self::channel_pressure(channel, seven_bit_value),
+            0xe => Self::pitch_bend_change(channel, U14((word_2 >> 18) as u16)),
+            _ => return None,
+        };
+        Some(msg)
+    }
+
+    /// Creates a short message from the given raw bytes, as they arrive from a MIDI driver.
+    ///
+    /// In contrast to [`from_bytes`], this also validates the data bytes. Data bytes which are not
+    /// used by the message type (e.g. data byte 2 of a Program Change message) are ignored and
+    /// set to 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the status byte is invalid or if a used data byte is greater than 127.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{note_on, program_change};
+    /// use helgoboss_midi::RawShortMessage;
+    ///
+    /// assert_eq!(
+    ///     RawShortMessage::from_raw_bytes(0x90, 64, 100),
+    ///     Ok(note_on(0, 64, 100))
+    /// );
+    /// assert_eq!(
+    ///     RawShortMessage::from_raw_bytes(0xc1, 5, 200),
+    ///     Ok(program_change(1, 5))
+    /// );
+    /// assert!(RawShortMessage::from_raw_bytes(0x90, 200, 100).is_err());
+    /// ```
+    ///
+    /// [`from_bytes`]: trait.ShortMessageFactory.html#method.from_bytes
+    pub fn from_raw_bytes(
+        status_byte: u8,
+        data_byte_1: u8,
+        data_byte_2: u8,
+    ) -> Result<RawShortMessage, FromBytesError> {
+        let r#type = extract_type_from_status_byte(status_byte).map_err(|_| FromBytesError(()))?;
+        let count = data_byte_count(r#type);
+        let data_byte = |index: usize, byte: u8| {
+            if index >= count {
+                Ok(U7::MIN)
+            } else {
+                U7::try_from(byte).map_err(|_| FromBytesError(()))
+            }
+        };
+        Ok(RawShortMessage((
+            status_byte,
+            data_byte(0, data_byte_1)?,
+            data_byte(1, data_byte_2)?,
+        )))
+    }
+
+    /// Creates a short message from the given slice of raw bytes, starting with the status byte.
+    ///
+    /// The slice may be shorter than 3 bytes if the message type uses fewer data bytes. Otherwise
+    /// this works like [`from_raw_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the slice is longer than 3 bytes, if it doesn't contain all data bytes
+    /// used by the message type or for the same reasons as [`from_raw_bytes`].
+    ///
+    /// [`from_raw_bytes`]: #method.from_raw_bytes
+    pub fn from_byte_slice(bytes: &[u8]) -> Result<RawShortMessage, FromBytesError> {
+        let (status_byte, data_bytes) = match bytes {
+            [status_byte, data_bytes @ ..] if data_bytes.len() <= 2 => (*status_byte, data_bytes),
+            _ => return Err(FromBytesError(())),
+        };
+        let r#type = extract_type_from_status_byte(status_byte).map_err(|_| FromBytesError(()))?;
+        if data_bytes.len() < data_byte_count(r#type) {
+            return Err(FromBytesError(()));
+        }
+        Self::from_raw_bytes(
+            status_byte,
+            data_bytes.first().copied().unwrap_or_default(),
+            data_bytes.get(1).copied().unwrap_or_default(),
+        )
+    }
+}
+
 impl TryFrom<(u8, U7, U7)> for RawShortMessage {
     type Error = FromBytesError;
 
@@ -60,4 +188,160 @@ impl ShortMessage for RawShortMessage {
     fn data_byte_2(&self) -> U7 {
         (self.0).2
     }
+
+    // Slight optimization
+    fn to_raw(&self) -> RawShortMessage {
+        *self
+    }
+}
+
+/// Removes consecutive exact duplicates from the given messages, e.g. repeated identical Control
+/// Change values.
+///
+/// The order of the remaining messages is preserved. Duplicates which are not adjacent are kept.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::dedup_consecutive;
+/// use helgoboss_midi::test_util::control_change;
+///
+/// let mut msgs = vec![
+///     control_change(0, 7, 100),
+///     control_change(0, 7, 100),
+///     control_change(0, 7, 90),
+/// ];
+/// dedup_consecutive(&mut msgs);
+/// assert_eq!(msgs, vec![control_change(0, 7, 100), control_change(0, 7, 90)]);
+/// ```
+pub fn dedup_consecutive(msgs: &mut Vec<RawShortMessage>) {
+    msgs.dedup();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, key_number as kn, u14, u7};
+
+    #[test]
+    fn from_ump_64_control_change() {
+        // Given
+        // When
+        let min = RawShortMessage::from_ump_64([0x4fb0_4000, 0x0000_0000]);
+        let center = RawShortMessage::from_ump_64([0x40b5_4000, 0x8000_0000]);
+        let max = RawShortMessage::from_ump_64([0x40bf_4000, 0xffff_ffff]);
+        // Then
+        assert_eq!(
+            min,
+            Some(RawShortMessage::control_change(ch(0), cn(64), u7(0)))
+        );
+        assert_eq!(
+            center,
+            Some(RawShortMessage::control_change(ch(5), cn(64), u7(64)))
+        );
+        assert_eq!(
+            max,
+            Some(RawShortMessage::control_change(ch(15), cn(64), u7(127)))
+        );
+    }
+
+    #[test]
+    fn from_ump_64_other_statuses() {
+        // Given
+        // When
+        // Then
+        assert_eq!(
+            RawShortMessage::from_ump_64([0x4091_3c00, 0x0100_0000]),
+            Some(RawShortMessage::note_on(ch(1), kn(60), u7(1)))
+        );
+        assert_eq!(
+            RawShortMessage::from_ump_64([0x4081_3c00, 0xffff_0000]),
+            Some(RawShortMessage::note_off(ch(1), kn(60), u7(127)))
+        );
+        assert_eq!(
+            RawShortMessage::from_ump_64([0x40c3_0001, 0x0500_1234]),
+            Some(RawShortMessage::program_change(ch(3), u7(5)))
+        );
+        assert_eq!(
+            RawShortMessage::from_ump_64([0x40e0_0000, 0x8000_0000]),
+            Some(RawShortMessage::pitch_bend_change(ch(0), u14(8192)))
+        );
+        assert_eq!(
+            RawShortMessage::from_ump_64([0x40d0_0000, 0xffff_ffff]),
+            Some(RawShortMessage::channel_pressure(ch(0), u7(127)))
+        );
+        // RPN
+        assert_eq!(RawShortMessage::from_ump_64([0x4020_0000, 0]), None);
+        // MIDI 1.0 Channel Voice message type
+        assert_eq!(RawShortMessage::from_ump_64([0x20b0_0740, 0]), None);
+    }
+
+    #[test]
+    fn dedup_consecutive_interleaved() {
+        // Given
+        let cc_1 = RawShortMessage::control_change(ch(0), cn(7), u7(100));
+        let cc_2 = RawShortMessage::control_change(ch(0), cn(7), u7(101));
+        let cc_3 = RawShortMessage::control_change(ch(1), cn(7), u7(100));
+        let note = RawShortMessage::note_on(ch(0), kn(60), u7(100));
+        let mut msgs = vec![cc_1, cc_1, cc_1, cc_2, cc_1, note, note, cc_3, cc_1, cc_1];
+        // When
+        dedup_consecutive(&mut msgs);
+        // Then
+        assert_eq!(msgs, vec![cc_1, cc_2, cc_1, note, cc_3, cc_1]);
+    }
+
+    #[test]
+    fn from_raw_bytes() {
+        // Given
+        // When
+        let note_on = RawShortMessage::from_raw_bytes(0x93, 60, 100);
+        let invalid_status = RawShortMessage::from_raw_bytes(0x00, 60, 100);
+        let invalid_data = RawShortMessage::from_raw_bytes(0x93, 60, 200);
+        let program_change = RawShortMessage::from_raw_bytes(0xc3, 5, 200);
+        let timing_clock = RawShortMessage::from_raw_bytes(0xf8, 200, 200);
+        // Then
+        assert_eq!(
+            note_on,
+            Ok(RawShortMessage::note_on(ch(3), kn(60), u7(100)))
+        );
+        assert_eq!(invalid_status, Err(FromBytesError(())));
+        assert_eq!(invalid_data, Err(FromBytesError(())));
+        assert_eq!(
+            program_change,
+            Ok(RawShortMessage::program_change(ch(3), u7(5)))
+        );
+        assert_eq!(timing_clock, Ok(RawShortMessage::timing_clock()));
+    }
+
+    #[test]
+    fn from_byte_slice() {
+        // Given
+        // When
+        // Then
+        assert_eq!(
+            RawShortMessage::from_byte_slice(&[0x93, 60, 100]),
+            Ok(RawShortMessage::note_on(ch(3), kn(60), u7(100)))
+        );
+        assert_eq!(
+            RawShortMessage::from_byte_slice(&[0xc3, 5]),
+            Ok(RawShortMessage::program_change(ch(3), u7(5)))
+        );
+        assert_eq!(
+            RawShortMessage::from_byte_slice(&[0xf8]),
+            Ok(RawShortMessage::timing_clock())
+        );
+        let invalid: [&[u8]; 5] = [
+            &[],
+            &[0x93, 60],
+            &[0x00, 60, 100],
+            &[0x93, 200, 100],
+            &[0x93, 60, 100, 0],
+        ];
+        for bytes in invalid.iter() {
+            assert_eq!(
+                RawShortMessage::from_byte_slice(bytes),
+                Err(FromBytesError(()))
+            );
+        }
+    }
 }
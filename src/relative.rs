@@ -0,0 +1,229 @@
+use crate::{Channel, ControllerNumber, RawShortMessage, ShortMessageFactory, U7};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An encoding for transmitting relative changes (increments and decrements) via Control Change
+/// values.
+///
+/// Used by endless rotary encoders. The MIDI specification doesn't define such an encoding, so
+/// several ones have established themselves.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RelativeMode {
+    /// 1 to 63 mean +1 to +63, 127 to 64 mean -1 to -64.
+    TwosComplement,
+    /// 1 to 63 mean +1 to +63, 65 to 127 mean -1 to -63 (bit 6 is the sign).
+    SignMagnitude,
+    /// 65 to 127 mean +1 to +63, 63 to 0 mean -1 to -64 (64 means no change).
+    BinaryOffset,
+}
+
+impl RelativeMode {
+    /// Encodes the given change as Control Change value.
+    ///
+    /// Changes which exceed the range of this encoding are clamped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::{RelativeMode, U7};
+    ///
+    /// assert_eq!(RelativeMode::TwosComplement.encode(-1), U7::new(127));
+    /// assert_eq!(RelativeMode::SignMagnitude.encode(-1), U7::new(65));
+    /// assert_eq!(RelativeMode::BinaryOffset.encode(-1), U7::new(63));
+    /// ```
+    pub fn encode(self, delta: i32) -> U7 {
+        use RelativeMode::*;
+        let value = match self {
+            TwosComplement => delta.clamp(-64, 63).rem_euclid(128),
+            SignMagnitude => {
+                let magnitude = delta.clamp(-63, 63).abs();
+                if delta < 0 {
+                    64 + magnitude
+                } else {
+                    magnitude
+                }
+            }
+            BinaryOffset => delta.clamp(-64, 63) + 64,
+        };
+        U7(value as u8)
+    }
+
+    /// Decodes the given Control Change value to a change.
+    pub fn decode(self, value: U7) -> i32 {
+        use RelativeMode::*;
+        let value = value.get() as i32;
+        match self {
+            TwosComplement => {
+                if value < 64 {
+                    value
+                } else {
+                    value - 128
+                }
+            }
+            SignMagnitude => {
+                if value < 64 {
+                    value
+                } else {
+                    -(value - 64)
+                }
+            }
+            BinaryOffset => value - 64,
+        }
+    }
+}
+
+/// Converter for turning absolute Control Change values into relative changes.
+///
+/// Useful for bridging an absolute controller to a destination which only understands relative
+/// changes. The previous value is remembered per channel and controller.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, controller_number, u7};
+/// use helgoboss_midi::{AbsoluteToRelative, RelativeMode};
+///
+/// let mut converter = AbsoluteToRelative::new();
+/// let (ch, cn) = (channel(0), controller_number(16));
+/// assert_eq!(converter.process(ch, cn, u7(50), RelativeMode::TwosComplement), None);
+/// assert_eq!(
+///     converter.process(ch, cn, u7(52), RelativeMode::TwosComplement),
+///     Some(control_change(0, 16, 2))
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct AbsoluteToRelative {
+    previous_values: [[Option<U7>; 128]; 16],
+}
+
+impl Default for AbsoluteToRelative {
+    fn default() -> Self {
+        AbsoluteToRelative {
+            previous_values: [[None; 128]; 16],
+        }
+    }
+}
+
+impl AbsoluteToRelative {
+    /// Creates a new converter which doesn't know any previous values yet.
+    pub fn new() -> AbsoluteToRelative {
+        Default::default()
+    }
+
+    /// Processes the given absolute value and returns the Control Change message which encodes
+    /// the change from the previous value of that controller.
+    ///
+    /// Returns `None` if there's no previous value yet or if the value hasn't changed.
+    pub fn process(
+        &mut self,
+        channel: Channel,
+        controller_number: ControllerNumber,
+        value: U7,
+        mode: RelativeMode,
+    ) -> Option<RawShortMessage> {
+        let previous_value = self.previous_values[usize::from(channel)]
+            [usize::from(controller_number)]
+        .replace(value)?;
+        let delta = value.get() as i32 - previous_value.get() as i32;
+        if delta == 0 {
+            return None;
+        }
+        Some(RawShortMessage::control_change(
+            channel,
+            controller_number,
+            mode.encode(delta),
+        ))
+    }
+
+    /// Resets the converter forgetting all previous values.
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u7};
+
+    #[test]
+    fn encode_and_decode() {
+        // Given
+        let modes = [
+            RelativeMode::TwosComplement,
+            RelativeMode::SignMagnitude,
+            RelativeMode::BinaryOffset,
+        ];
+        // When
+        // Then
+        for mode in modes.iter() {
+            for delta in -63..=63 {
+                assert_eq!(mode.decode(mode.encode(delta)), delta);
+            }
+        }
+        assert_eq!(RelativeMode::TwosComplement.encode(-64), u7(64));
+        assert_eq!(RelativeMode::TwosComplement.encode(-100), u7(64));
+        assert_eq!(RelativeMode::SignMagnitude.encode(-100), u7(127));
+        assert_eq!(RelativeMode::BinaryOffset.encode(100), u7(127));
+        assert_eq!(RelativeMode::BinaryOffset.encode(-100), u7(0));
+        for mode in modes.iter() {
+            assert_eq!(mode.decode(mode.encode(i32::MAX)), 63);
+        }
+        assert_eq!(RelativeMode::TwosComplement.encode(i32::MIN), u7(64));
+        assert_eq!(RelativeMode::SignMagnitude.encode(i32::MIN), u7(127));
+        assert_eq!(RelativeMode::BinaryOffset.encode(i32::MIN), u7(0));
+    }
+
+    #[test]
+    fn plus_and_minus_one_for_each_mode() {
+        // Given
+        let expectations = [
+            (RelativeMode::TwosComplement, 1, 127),
+            (RelativeMode::SignMagnitude, 1, 65),
+            (RelativeMode::BinaryOffset, 65, 63),
+        ];
+        // When
+        // Then
+        for (mode, plus_one, minus_one) in expectations.iter() {
+            let mut converter = AbsoluteToRelative::new();
+            assert_eq!(converter.process(ch(1), cn(16), u7(64), *mode), None);
+            assert_eq!(
+                converter.process(ch(1), cn(16), u7(65), *mode),
+                Some(RawShortMessage::control_change(
+                    ch(1),
+                    cn(16),
+                    u7(*plus_one)
+                ))
+            );
+            assert_eq!(
+                converter.process(ch(1), cn(16), u7(64), *mode),
+                Some(RawShortMessage::control_change(
+                    ch(1),
+                    cn(16),
+                    u7(*minus_one)
+                ))
+            );
+            assert_eq!(converter.process(ch(1), cn(16), u7(64), *mode), None);
+        }
+    }
+
+    #[test]
+    fn controllers_are_independent() {
+        // Given
+        let mut converter = AbsoluteToRelative::new();
+        let mode = RelativeMode::TwosComplement;
+        // When
+        converter.process(ch(0), cn(16), u7(10), mode);
+        let result_1 = converter.process(ch(0), cn(17), u7(20), mode);
+        let result_2 = converter.process(ch(1), cn(16), u7(20), mode);
+        let result_3 = converter.process(ch(0), cn(16), u7(0), mode);
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(
+            result_3,
+            Some(RawShortMessage::control_change(ch(0), cn(16), u7(118)))
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Contains the Registered Parameter Numbers (RPNs) standardized by the MIDI 1.0 specification.
+//!
+//! # Example
+//!
+//! ```
+//! use helgoboss_midi::rpn::{self, StandardRpn};
+//!
+//! assert_eq!(StandardRpn::PitchBendSensitivity.number(), rpn::PITCH_BEND_SENSITIVITY);
+//! assert_eq!(
+//!     StandardRpn::from_number(rpn::COARSE_TUNING),
+//!     Some(StandardRpn::CoarseTuning)
+//! );
+//! ```
+use crate::U14;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub const PITCH_BEND_SENSITIVITY: U14 = U14(0x0000);
+pub const FINE_TUNING: U14 = U14(0x0001);
+pub const COARSE_TUNING: U14 = U14(0x0002);
+pub const TUNING_PROGRAM: U14 = U14(0x0003);
+pub const TUNING_BANK: U14 = U14(0x0004);
+pub const MODULATION_DEPTH_RANGE: U14 = U14(0x0005);
+
+/// A standardized Registered Parameter Number.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StandardRpn {
+    /// RPN 0. The MSB of the value is the range in semitones, the LSB the range in cents.
+    PitchBendSensitivity,
+    /// RPN 1. Value 8192 means no detuning.
+    FineTuning,
+    /// RPN 2. Only the MSB of the value is used, 64 means no detuning.
+    CoarseTuning,
+    /// RPN 3. Selects a tuning program (see MIDI Tuning Standard).
+    TuningProgram,
+    /// RPN 4. Selects a tuning bank (see MIDI Tuning Standard).
+    TuningBank,
+    /// RPN 5.
+    ModulationDepthRange,
+}
+
+impl StandardRpn {
+    /// Returns the parameter number.
+    pub fn number(self) -> U14 {
+        use StandardRpn::*;
+        match self {
+            PitchBendSensitivity => PITCH_BEND_SENSITIVITY,
+            FineTuning => FINE_TUNING,
+            CoarseTuning => COARSE_TUNING,
+            TuningProgram => TUNING_PROGRAM,
+            TuningBank => TUNING_BANK,
+            ModulationDepthRange => MODULATION_DEPTH_RANGE,
+        }
+    }
+
+    /// Returns the standardized RPN with the given parameter number or `None` if the number is
+    /// not one of them.
+    pub fn from_number(number: U14) -> Option<StandardRpn> {
+        use StandardRpn::*;
+        let rpn = match number {
+            PITCH_BEND_SENSITIVITY => PitchBendSensitivity,
+            FINE_TUNING => FineTuning,
+            COARSE_TUNING => CoarseTuning,
+            TUNING_PROGRAM => TuningProgram,
+            TUNING_BANK => TuningBank,
+            MODULATION_DEPTH_RANGE => ModulationDepthRange,
+            _ => return None,
+        };
+        Some(rpn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::u14;
+
+    #[test]
+    fn number_round_trip() {
+        // Given
+        // When
+        // Then
+        for n in 0..6 {
+            let rpn = StandardRpn::from_number(u14(n)).unwrap();
+            assert_eq!(rpn.number(), u14(n));
+        }
+        assert_eq!(StandardRpn::from_number(u14(6)), None);
+        assert_eq!(StandardRpn::from_number(u14(16383)), None);
+    }
+}
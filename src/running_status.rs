@@ -0,0 +1,352 @@
+use crate::{
+    data_byte_count, extract_type_from_status_byte, FuzzyMessageSuperType, ShortMessage,
+    ShortMessageFactory, StructuredShortMessage, U7,
+};
+
+/// Encoder for serializing short messages to bytes using running status.
+///
+/// If a channel message has the same status byte as the previous channel message, the status byte
+/// is omitted, which saves bandwidth on slow connections such as 5-pin DIN. System Common and
+/// System Exclusive messages cancel running status, System Real Time messages don't affect it
+/// because they may be interleaved with anything.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{control_change, note_on};
+/// use helgoboss_midi::RunningStatusEncoder;
+///
+/// let mut encoder = RunningStatusEncoder::new();
+/// let mut bytes = Vec::new();
+/// encoder.encode(&note_on(0, 60, 100), &mut bytes);
+/// encoder.encode(&note_on(0, 64, 100), &mut bytes);
+/// encoder.encode(&control_change(0, 64, 127), &mut bytes);
+/// assert_eq!(bytes, vec![0x90, 60, 100, 64, 100, 0xb0, 64, 127]);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct RunningStatusEncoder {
+    running_status: Option<u8>,
+}
+
+impl RunningStatusEncoder {
+    /// Creates a new encoder which sends the status byte of the first message.
+    pub fn new() -> RunningStatusEncoder {
+        Default::default()
+    }
+
+    /// Appends the bytes of the given message to `out`, omitting the status byte if running
+    /// status applies.
+    pub fn encode(&mut self, msg: &impl ShortMessage, out: &mut Vec<u8>) {
+        let status_byte = msg.status_byte();
+        let r#type = msg.r#type();
+        match r#type.super_type() {
+            FuzzyMessageSuperType::Channel => {
+                if self.running_status.replace(status_byte) != Some(status_byte) {
+                    out.push(status_byte);
+                }
+            }
+            FuzzyMessageSuperType::SystemRealTime => out.push(status_byte),
+            FuzzyMessageSuperType::SystemCommon | FuzzyMessageSuperType::SystemExclusive => {
+                self.running_status = None;
+                out.push(status_byte);
+            }
+        }
+        let data_bytes = [msg.data_byte_1().get(), msg.data_byte_2().get()];
+        out.extend_from_slice(&data_bytes[..data_byte_count(r#type)]);
+    }
+
+    /// Resets the encoder so that the next message is sent with status byte.
+    ///
+    /// Should be called whenever the receiver might have lost track of the running status, e.g.
+    /// after other bytes have been sent in between.
+    pub fn reset(&mut self) {
+        self.running_status = None;
+    }
+}
+
+/// Decoder for parsing a byte stream which may use running status into short messages.
+///
+/// Data bytes without a preceding status byte reuse the status byte of the last channel message.
+/// System Real Time messages may appear anywhere, even between the data bytes of another message,
+/// without affecting it. The data bytes of System Exclusive messages are skipped, only the
+/// System Exclusive Start and End messages are returned. Data bytes which can't be assigned to a
+/// status byte are ignored.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{note_on, timing_clock};
+/// use helgoboss_midi::{RunningStatusDecoder, ShortMessage};
+///
+/// let mut decoder = RunningStatusDecoder::new();
+/// let msgs: Vec<_> = [0x90, 60, 0xf8, 100, 64, 100]
+///     .iter()
+///     .filter_map(|b| decoder.push(*b))
+///     .collect();
+/// assert_eq!(
+///     msgs,
+///     vec![
+///         timing_clock().to_structured(),
+///         note_on(0, 60, 100).to_structured(),
+///         note_on(0, 64, 100).to_structured(),
+///     ]
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct RunningStatusDecoder {
+    status_byte: Option<u8>,
+    data_bytes: [u8; 2],
+    data_byte_index: usize,
+    is_in_system_exclusive: bool,
+    normalize_note_off: bool,
+}
+
+impl RunningStatusDecoder {
+    /// Creates a new decoder which waits for the first status byte.
+    pub fn new() -> RunningStatusDecoder {
+        Default::default()
+    }
+
+    /// Feeds the decoder a single byte.
+    ///
+    /// Returns a message if this byte completes one.
+    pub fn push(&mut self, byte: u8) -> Option<StructuredShortMessage> {
+        if byte < 0x80 {
+            return self.process_data_byte(byte);
+        }
+        let r#type = extract_type_from_status_byte(byte).ok()?;
+        match r#type.super_type() {
+            FuzzyMessageSuperType::SystemRealTime => return build_message(byte, [0, 0]),
+            FuzzyMessageSuperType::SystemExclusive => {
+                self.reset();
+                self.is_in_system_exclusive = true;
+                return build_message(byte, [0, 0]);
+            }
+            FuzzyMessageSuperType::SystemCommon => {
+                self.reset();
+            }
+            FuzzyMessageSuperType::Channel => {
+                self.is_in_system_exclusive = false;
+            }
+        }
+        self.status_byte = Some(byte);
+        self.data_byte_index = 0;
+        if data_byte_count(r#type) > 0 {
+            return None;
+        }
+        self.status_byte = None;
+        build_message(byte, [0, 0])
+    }
+
+    /// Enables or disables turning Note On messages with velocity 0 into Note Off messages.
+    ///
+    /// Senders using running status often transmit Note On with velocity 0 instead of Note Off
+    /// because it saves the status byte. If enabled, the decoder emits Note Off messages for them
+    /// (see [`StructuredShortMessage::normalize_note_off`]), so downstream code doesn't need to
+    /// handle both. Disabled by default.
+    ///
+    /// [`StructuredShortMessage::normalize_note_off`]:
+    /// enum.StructuredShortMessage.html#method.normalize_note_off
+    pub fn set_normalize_note_off(&mut self, enabled: bool) {
+        self.normalize_note_off = enabled;
+    }
+
+    /// Resets the decoder forgetting the running status and any incomplete message.
+    ///
+    /// The configuration is kept.
+    pub fn reset(&mut self) {
+        *self = RunningStatusDecoder {
+            normalize_note_off: self.normalize_note_off,
+            ..Default::default()
+        };
+    }
+
+    fn process_data_byte(&mut self, byte: u8) -> Option<StructuredShortMessage> {
+        if self.is_in_system_exclusive {
+            return None;
+        }
+        let status_byte = self.status_byte?;
+        let r#type = extract_type_from_status_byte(status_byte).ok()?;
+        self.data_bytes[self.data_byte_index] = byte;
+        self.data_byte_index += 1;
+        if self.data_byte_index < data_byte_count(r#type) {
+            return None;
+        }
+        self.data_byte_index = 0;
+        if r#type.super_type() != FuzzyMessageSuperType::Channel {
+            // Running status only applies to channel messages
+            self.status_byte = None;
+        }
+        let data_bytes = self.data_bytes;
+        self.data_bytes = [0, 0];
+        let msg = build_message(status_byte, data_bytes)?;
+        if self.normalize_note_off {
+            Some(msg.normalize_note_off())
+        } else {
+            Some(msg)
+        }
+    }
+}
+
+fn build_message(status_byte: u8, data_bytes: [u8; 2]) -> Option<StructuredShortMessage> {
+    StructuredShortMessage::from_bytes((status_byte, U7(data_bytes[0]), U7(data_bytes[1]))).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{
+        channel as ch, control_change, key_number as kn, note_on, program_change, timing_clock, u7,
+    };
+    use crate::RawShortMessage;
+
+    #[test]
+    fn same_status_byte_sent_once() {
+        // Given
+        let mut encoder = RunningStatusEncoder::new();
+        let mut bytes = Vec::new();
+        // When
+        encoder.encode(&note_on(3, 60, 100), &mut bytes);
+        encoder.encode(&note_on(3, 64, 90), &mut bytes);
+        encoder.encode(&note_on(3, 67, 0), &mut bytes);
+        // Then
+        assert_eq!(bytes, vec![0x93, 60, 100, 64, 90, 67, 0]);
+    }
+
+    #[test]
+    fn system_messages() {
+        // Given
+        let mut encoder = RunningStatusEncoder::new();
+        let mut bytes = Vec::new();
+        // When
+        encoder.encode(&program_change(0, 5), &mut bytes);
+        encoder.encode(&timing_clock(), &mut bytes);
+        encoder.encode(&program_change(0, 6), &mut bytes);
+        encoder.encode(&RawShortMessage::song_select(u7(2)), &mut bytes);
+        encoder.encode(&program_change(0, 7), &mut bytes);
+        // Then
+        assert_eq!(bytes, vec![0xc0, 5, 0xf8, 6, 0xf3, 2, 0xc0, 7]);
+    }
+
+    #[test]
+    fn reset() {
+        // Given
+        let mut encoder = RunningStatusEncoder::new();
+        let mut bytes = Vec::new();
+        // When
+        encoder.encode(&control_change(1, 7, 100), &mut bytes);
+        encoder.reset();
+        encoder.encode(&control_change(1, 7, 90), &mut bytes);
+        encoder.encode(&RawShortMessage::note_off(ch(1), kn(60), u7(0)), &mut bytes);
+        // Then
+        assert_eq!(bytes, vec![0xb1, 7, 100, 0xb1, 7, 90, 0x81, 60, 0]);
+    }
+
+    fn decode(bytes: &[u8]) -> Vec<StructuredShortMessage> {
+        let mut decoder = RunningStatusDecoder::new();
+        bytes.iter().filter_map(|b| decoder.push(*b)).collect()
+    }
+
+    #[test]
+    fn decode_running_status() {
+        // Given
+        let bytes = [0x93, 60, 100, 64, 90, 67, 0, 72, 1];
+        // When
+        let msgs = decode(&bytes);
+        // Then
+        assert_eq!(
+            msgs,
+            vec![
+                note_on(3, 60, 100).to_structured(),
+                note_on(3, 64, 90).to_structured(),
+                note_on(3, 67, 0).to_structured(),
+                note_on(3, 72, 1).to_structured(),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_real_time_within_note() {
+        // Given
+        let bytes = [0x90, 60, 0xf8, 100, 0xfa, 62, 0xf8, 100];
+        // When
+        let msgs = decode(&bytes);
+        // Then
+        assert_eq!(
+            msgs,
+            vec![
+                timing_clock().to_structured(),
+                note_on(0, 60, 100).to_structured(),
+                StructuredShortMessage::Start,
+                timing_clock().to_structured(),
+                note_on(0, 62, 100).to_structured(),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_system_messages() {
+        // Given
+        let bytes = [
+            0xc0, 5, 6, 0xf3, 2, 3, 0xf6, 0xf0, 0x7e, 0x01, 0xf7, 7, 0xc1, 8,
+        ];
+        // When
+        let msgs = decode(&bytes);
+        // Then
+        assert_eq!(
+            msgs,
+            vec![
+                program_change(0, 5).to_structured(),
+                program_change(0, 6).to_structured(),
+                RawShortMessage::song_select(u7(2)).to_structured(),
+                StructuredShortMessage::TuneRequest,
+                StructuredShortMessage::SystemExclusiveStart,
+                StructuredShortMessage::SystemExclusiveEnd,
+                program_change(1, 8).to_structured(),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_with_normalize_note_off() {
+        // Given
+        let mut decoder = RunningStatusDecoder::new();
+        decoder.set_normalize_note_off(true);
+        let bytes = [0x93, 60, 100, 60, 0, 0xf8, 64, 0];
+        // When
+        let msgs: Vec<_> = bytes.iter().filter_map(|b| decoder.push(*b)).collect();
+        // Then
+        assert_eq!(
+            msgs,
+            vec![
+                note_on(3, 60, 100).to_structured(),
+                RawShortMessage::note_off(ch(3), kn(60), u7(0)).to_structured(),
+                timing_clock().to_structured(),
+                RawShortMessage::note_off(ch(3), kn(64), u7(0)).to_structured(),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        // Given
+        let msgs = [
+            note_on(0, 60, 100),
+            note_on(0, 64, 100),
+            timing_clock(),
+            control_change(0, 64, 127),
+            RawShortMessage::song_position_pointer(crate::test_util::u14(1000)),
+            control_change(0, 64, 0),
+        ];
+        let mut encoder = RunningStatusEncoder::new();
+        let mut bytes = Vec::new();
+        // When
+        for msg in msgs.iter() {
+            encoder.encode(msg, &mut bytes);
+        }
+        let decoded = decode(&bytes);
+        // Then
+        let expected: Vec<_> = msgs.iter().map(|m| m.to_structured()).collect();
+        assert_eq!(decoded, expected);
+    }
+}
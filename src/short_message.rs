@@ -1,6 +1,7 @@
+use crate::bit_util::build_14_bit_value_from_two_7_bit_values;
 use crate::{
-    build_14_bit_value_from_two_7_bit_values, extract_channel_from_status_byte, Channel,
-    ControllerNumber, KeyNumber, ShortMessageFactory, StructuredShortMessage, U14, U4, U7,
+    extract_channel_from_status_byte, Channel, ChannelModeMessage, ControllerNumber, KeyNumber,
+    RawShortMessage, ShortMessageFactory, StructuredShortMessage, U14, U4, U7,
 };
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 #[cfg(feature = "serde")]
@@ -96,6 +97,44 @@ pub trait ShortMessage {
         (self.status_byte(), self.data_byte_1(), self.data_byte_2())
     }
 
+    /// Returns the bytes which need to be sent over the wire, together with the number of
+    /// significant bytes.
+    ///
+    /// Unlike [`to_bytes`], this takes into account that not every message type uses both data
+    /// bytes. E.g. a Program Change message consists of 2 bytes only and a System Real Time
+    /// message of just 1 byte. Insignificant bytes are 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::program_change;
+    /// use helgoboss_midi::ShortMessage;
+    ///
+    /// let (bytes, len) = program_change(2, 5).to_byte_array();
+    /// assert_eq!(&bytes[..len], &[0xc2, 5]);
+    /// ```
+    ///
+    /// [`to_bytes`]: #method.to_bytes
+    fn to_byte_array(&self) -> ([u8; 3], usize) {
+        let (status_byte, data_byte_1, data_byte_2) = self.to_bytes();
+        let len = 1 + data_byte_count(self.r#type());
+        let mut bytes = [status_byte, data_byte_1.get(), data_byte_2.get()];
+        for b in bytes[len..].iter_mut() {
+            *b = 0;
+        }
+        (bytes, len)
+    }
+
+    /// Appends the bytes which need to be sent over the wire to the given vector.
+    ///
+    /// See [`to_byte_array`] for details.
+    ///
+    /// [`to_byte_array`]: #method.to_byte_array
+    fn write_to(&self, out: &mut Vec<u8>) {
+        let (bytes, len) = self.to_byte_array();
+        out.extend_from_slice(&bytes[..len]);
+    }
+
     /// Converts this message to a short message of another type.
     fn to_other<O: ShortMessageFactory>(&self) -> O {
         let bytes = self.to_bytes();
@@ -107,6 +146,20 @@ pub trait ShortMessage {
         self.to_other()
     }
 
+    /// Converts this message to a [`StructuredShortMessage`] like [`to_structured`], but
+    /// represents a Note On message with velocity 0 as Note Off message.
+    ///
+    /// [`to_structured`]: #method.to_structured
+    fn to_structured_normalized(&self) -> StructuredShortMessage {
+        self.to_structured().normalize_note_off()
+    }
+
+    /// Converts this message to a [`RawShortMessage`], which is ideal for storing an owned copy of
+    /// a message of unknown type.
+    fn to_raw(&self) -> RawShortMessage {
+        self.to_other()
+    }
+
     /// Returns the type of this message.
     fn r#type(&self) -> ShortMessageType {
         extract_type_from_status_byte(self.status_byte()).expect("invalid status byte detected")
@@ -188,6 +241,49 @@ pub trait ShortMessage {
         }
     }
 
+    /// Returns whether this message is a Control Change message.
+    ///
+    /// This includes Control Change messages which encode Channel Mode messages.
+    fn is_control_change(&self) -> bool {
+        self.r#type() == ShortMessageType::ControlChange
+    }
+
+    /// Returns whether this message is a Channel Voice message.
+    fn is_channel_voice(&self) -> bool {
+        self.super_type() == MessageSuperType::ChannelVoice
+    }
+
+    /// Returns whether this message is a System message, that is a System Common, System Real Time
+    /// or System Exclusive message.
+    fn is_system_message(&self) -> bool {
+        self.main_category() == MessageMainCategory::System
+    }
+
+    /// Returns whether this message can start a note, keep it sounding or bend its pitch.
+    ///
+    /// That's the case for note-ons (in a practical sense, see [`is_note_on`]), for switching on
+    /// the damper pedal (sustain) or sostenuto and for Pitch Bend Change messages. Useful for
+    /// finding out which state needs to be cleaned up in order to prevent hanging notes.
+    ///
+    /// [`is_note_on`]: #method.is_note_on
+    fn can_sound_note(&self) -> bool {
+        use crate::controller_numbers::{DAMPER_PEDAL_ON_OFF, SOSTENUTO_ON_OFF};
+        use StructuredShortMessage::*;
+        match self.to_structured() {
+            NoteOn { velocity, .. } => velocity > U7::MIN,
+            ControlChange {
+                controller_number,
+                control_value,
+                ..
+            } => {
+                (controller_number == DAMPER_PEDAL_ON_OFF || controller_number == SOSTENUTO_ON_OFF)
+                    && control_value >= U7(64)
+            }
+            PitchBendChange { .. } => true,
+            _ => false,
+        }
+    }
+
     /// Returns the channel of this message if applicable.
     fn channel(&self) -> Option<Channel> {
         if self.main_category() != MessageMainCategory::Channel {
@@ -205,6 +301,18 @@ pub trait ShortMessage {
         }
     }
 
+    /// Returns the key number of this message if it's a note-on or note-off.
+    ///
+    /// In contrast to [`key_number`], this returns `None` for Polyphonic Key Pressure messages.
+    ///
+    /// [`key_number`]: #method.key_number
+    fn note_number(&self) -> Option<KeyNumber> {
+        if !self.is_note() {
+            return None;
+        }
+        self.key_number()
+    }
+
     /// Returns the velocity of this message if applicable.
     fn velocity(&self) -> Option<U7> {
         use ShortMessageType::*;
@@ -258,6 +366,11 @@ pub trait ShortMessage {
             self.data_byte_1(),
         ))
     }
+
+    /// Returns the Channel Mode message if this is a Control Change message which encodes one.
+    fn to_channel_mode_message(&self) -> Option<ChannelModeMessage> {
+        ChannelModeMessage::from_control_change(self.controller_number()?, self.control_value()?)
+    }
 }
 
 /// The most fine-grained classification of short MIDI messages.
@@ -489,6 +602,36 @@ pub enum TimeCodeType {
     Fps30NonDrop = 3,
 }
 
+/// Returns whether the given message should be considered as boundary which ends any incomplete
+/// sequence of short messages (e.g. an (N)RPN message which is still being transmitted).
+///
+/// This applies to System Exclusive, System Common and System Reset messages. Other System Real
+/// Time messages are not boundaries because they may be interleaved with anything.
+pub(crate) fn is_sequence_boundary(msg: &impl ShortMessage) -> bool {
+    use MessageSuperType::*;
+    match msg.super_type() {
+        SystemExclusive | SystemCommon => true,
+        SystemRealTime => msg.r#type() == ShortMessageType::SystemReset,
+        ChannelVoice | ChannelMode => false,
+    }
+}
+
+/// Returns the number of data bytes which follow the status byte of the given message type on
+/// the wire.
+pub(crate) fn data_byte_count(r#type: ShortMessageType) -> usize {
+    use ShortMessageType::*;
+    match r#type {
+        NoteOff
+        | NoteOn
+        | PolyphonicKeyPressure
+        | ControlChange
+        | PitchBendChange
+        | SongPositionPointer => 2,
+        ProgramChange | ChannelPressure | TimeCodeQuarterFrame | SongSelect => 1,
+        _ => 0,
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct InvalidStatusByteError;
 
@@ -1022,6 +1165,27 @@ mod tests {
         assert!(!msg.is_note_off());
     }
 
+    #[test]
+    fn to_byte_array() {
+        // Given
+        let note_on = RawShortMessage::note_on(ch(1), key_number(64), u7(100));
+        let program_change = StructuredShortMessage::ProgramChange {
+            channel: ch(1),
+            program_number: u7(5),
+        };
+        let timing_clock = RawShortMessage::timing_clock();
+        // When
+        let mut out = vec![];
+        note_on.write_to(&mut out);
+        program_change.write_to(&mut out);
+        timing_clock.write_to(&mut out);
+        // Then
+        assert_eq!(note_on.to_byte_array(), ([0x91, 64, 100], 3));
+        assert_eq!(program_change.to_byte_array(), ([0xc1, 5, 0], 2));
+        assert_eq!(timing_clock.to_byte_array(), ([0xf8, 0, 0], 1));
+        assert_eq!(out, vec![0x91, 64, 100, 0xc1, 5, 0xf8]);
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn structured_serialize() {
@@ -1101,6 +1265,121 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_sound_note() {
+        // Given
+        // When
+        // Then
+        assert!(RawShortMessage::note_on(ch(1), key_number(64), u7(100)).can_sound_note());
+        assert!(!RawShortMessage::note_on(ch(1), key_number(64), u7(0)).can_sound_note());
+        assert!(!RawShortMessage::note_off(ch(1), key_number(64), u7(100)).can_sound_note());
+        assert!(
+            RawShortMessage::control_change(ch(1), controller_number(64), u7(127)).can_sound_note()
+        );
+        assert!(
+            !RawShortMessage::control_change(ch(1), controller_number(64), u7(0)).can_sound_note()
+        );
+        assert!(
+            RawShortMessage::control_change(ch(1), controller_number(66), u7(64)).can_sound_note()
+        );
+        assert!(
+            !RawShortMessage::control_change(ch(1), controller_number(7), u7(127)).can_sound_note()
+        );
+        assert!(RawShortMessage::pitch_bend_change(ch(1), u14(0)).can_sound_note());
+        assert!(!RawShortMessage::program_change(ch(1), u7(5)).can_sound_note());
+        assert!(!RawShortMessage::timing_clock().can_sound_note());
+    }
+
+    #[test]
+    fn inspection_helpers() {
+        // Given
+        let note_on = RawShortMessage::note_on(ch(1), key_number(64), u7(100));
+        let note_off = RawShortMessage::note_on(ch(1), key_number(64), u7(0));
+        let cc = RawShortMessage::control_change(ch(1), controller_number(7), u7(100));
+        let all_notes_off = RawShortMessage::control_change(ch(1), controller_number(123), u7(0));
+        let poly_pressure = RawShortMessage::polyphonic_key_pressure(ch(1), key_number(64), u7(50));
+        let clock = RawShortMessage::timing_clock();
+        // When
+        // Then
+        assert!(note_on.is_note_on() && !note_on.is_note_off());
+        assert!(note_off.is_note_off() && !note_off.is_note_on());
+        assert!(!note_on.is_control_change());
+        assert!(cc.is_control_change());
+        assert!(all_notes_off.is_control_change());
+        assert!(note_on.is_channel_voice());
+        assert!(cc.is_channel_voice());
+        assert!(!all_notes_off.is_channel_voice());
+        assert!(!clock.is_channel_voice());
+        assert!(clock.is_system_message());
+        assert!(!note_on.is_system_message());
+        assert!(!all_notes_off.is_system_message());
+        assert_eq!(note_on.note_number(), Some(key_number(64)));
+        assert_eq!(note_off.note_number(), Some(key_number(64)));
+        assert_eq!(poly_pressure.note_number(), None);
+        assert_eq!(cc.note_number(), None);
+        assert_eq!(clock.note_number(), None);
+        assert_eq!(note_on.velocity(), Some(u7(100)));
+        assert_eq!(cc.velocity(), None);
+        assert_eq!(clock.velocity(), None);
+    }
+
+    #[test]
+    fn to_structured_normalized() {
+        // Given
+        let zero_velocity_note_on = RawShortMessage::note_on(ch(3), key_number(64), u7(0));
+        let note_on = RawShortMessage::note_on(ch(3), key_number(64), u7(1));
+        // When
+        let normalized_1 = zero_velocity_note_on.to_structured_normalized();
+        let normalized_2 = note_on.to_structured_normalized();
+        // Then
+        assert_eq!(
+            normalized_1,
+            StructuredShortMessage::NoteOff {
+                channel: ch(3),
+                key_number: key_number(64),
+                velocity: u7(0),
+            }
+        );
+        assert_eq!(normalized_2, note_on.to_structured());
+        assert_eq!(
+            zero_velocity_note_on.to_structured().r#type(),
+            ShortMessageType::NoteOn
+        );
+    }
+
+    #[test]
+    fn to_raw() {
+        // Given
+        struct CustomShortMessage {
+            bytes: [u8; 3],
+        }
+        impl ShortMessage for CustomShortMessage {
+            fn status_byte(&self) -> u8 {
+                self.bytes[0]
+            }
+
+            fn data_byte_1(&self) -> U7 {
+                U7::new(self.bytes[1])
+            }
+
+            fn data_byte_2(&self) -> U7 {
+                U7::new(self.bytes[2])
+            }
+        }
+        let custom_msg = CustomShortMessage {
+            bytes: [0xb1, 50, 2],
+        };
+        // When
+        let raw_msg = custom_msg.to_raw();
+        // Then
+        assert_eq!(
+            raw_msg,
+            RawShortMessage::control_change(ch(1), controller_number(50), u7(2))
+        );
+        assert_eq!(raw_msg.to_structured(), custom_msg.to_structured());
+        assert_equal_results(&raw_msg, &custom_msg);
+    }
+
     fn assert_equal_results(first: &impl ShortMessage, second: &impl ShortMessage) {
         assert_eq!(first.status_byte(), second.status_byte());
         assert_eq!(first.data_byte_1(), second.data_byte_1());
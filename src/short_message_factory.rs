@@ -130,6 +130,37 @@ pub trait ShortMessageFactory: ShortMessage + Sized {
         }
     }
 
+    /// Creates the 2 Control Change messages which make up a 14-bit Control Change message.
+    ///
+    /// The first one carries the most significant byte on the given controller, the second one
+    /// the least significant byte on the corresponding LSB controller. Shortcut for
+    /// [`high_res::control_change_14_bit`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `msb_controller_number` can't serve as controller number for
+    /// transmitting the most significant byte of a 14-bit Control Change message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{channel, control_change, controller_number, u14};
+    /// use helgoboss_midi::{RawShortMessage, ShortMessageFactory};
+    ///
+    /// let msgs =
+    ///     RawShortMessage::control_change_14_bit(channel(0), controller_number(2), u14(1057));
+    /// assert_eq!(msgs, [control_change(0, 2, 8), control_change(0, 34, 33)]);
+    /// ```
+    ///
+    /// [`high_res::control_change_14_bit`]: high_res/fn.control_change_14_bit.html
+    fn control_change_14_bit(
+        channel: Channel,
+        msb_controller_number: ControllerNumber,
+        value: U14,
+    ) -> [Self; 2] {
+        crate::high_res::control_change_14_bit(channel, msb_controller_number, value)
+    }
+
     /// Creates a Program Change message.
     fn program_change(channel: Channel, program_number: U7) -> Self {
         unsafe {
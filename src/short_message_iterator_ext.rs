@@ -0,0 +1,94 @@
+use crate::{ParameterNumberMessage, ParameterNumberMessageScanner, ShortMessage};
+
+/// Extension trait for iterators over short messages.
+pub trait ShortMessageIteratorExt: Iterator + Sized
+where
+    Self::Item: ShortMessage,
+{
+    /// Scans the short messages lazily for (N)RPN messages and yields only the detected ones.
+    ///
+    /// Uses a [`ParameterNumberMessageScanner`] with default settings internally.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{control_change, nrpn};
+    /// use helgoboss_midi::{ShortMessage, ShortMessageIteratorExt};
+    ///
+    /// let msgs = vec![
+    ///     control_change(0, 99, 3),
+    ///     control_change(1, 99, 3),
+    ///     control_change(0, 98, 37),
+    ///     control_change(0, 6, 126),
+    /// ];
+    /// let result: Vec<_> = msgs
+    ///     .into_iter()
+    ///     .filter(|msg| msg.channel().map(|c| c.get()) == Some(0))
+    ///     .scan_parameter_numbers()
+    ///     .collect();
+    /// assert_eq!(result, vec![nrpn(0, 421, 126)]);
+    /// ```
+    ///
+    /// [`ParameterNumberMessageScanner`]: struct.ParameterNumberMessageScanner.html
+    fn scan_parameter_numbers(self) -> ParameterNumberScan<Self> {
+        ParameterNumberScan {
+            iter: self,
+            scanner: ParameterNumberMessageScanner::new(),
+        }
+    }
+}
+
+impl<I: Iterator> ShortMessageIteratorExt for I where I::Item: ShortMessage {}
+
+/// Iterator returned by [`ShortMessageIteratorExt::scan_parameter_numbers`].
+///
+/// [`ShortMessageIteratorExt::scan_parameter_numbers`]:
+/// trait.ShortMessageIteratorExt.html#method.scan_parameter_numbers
+#[derive(Clone, Debug)]
+pub struct ParameterNumberScan<I> {
+    iter: I,
+    scanner: ParameterNumberMessageScanner,
+}
+
+impl<I: Iterator> Iterator for ParameterNumberScan<I>
+where
+    I::Item: ShortMessage,
+{
+    type Item = ParameterNumberMessage;
+
+    fn next(&mut self) -> Option<ParameterNumberMessage> {
+        let scanner = &mut self.scanner;
+        self.iter.find_map(|msg| scanner.feed(&msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, u14, u7};
+    use crate::{RawShortMessage, ShortMessageFactory};
+
+    #[test]
+    fn scan_parameter_numbers() {
+        // Given
+        let msgs = vec![
+            RawShortMessage::control_change(ch(0), cn(101), u7(3)),
+            RawShortMessage::control_change(ch(0), cn(100), u7(36)),
+            RawShortMessage::control_change(ch(0), cn(38), u7(24)),
+            RawShortMessage::control_change(ch(0), cn(6), u7(117)),
+            RawShortMessage::control_change(ch(0), cn(7), u7(100)),
+            RawShortMessage::control_change(ch(0), cn(6), u7(2)),
+        ];
+        // When
+        let result: Vec<_> = msgs.into_iter().scan_parameter_numbers().collect();
+        // Then
+        assert_eq!(
+            result,
+            vec![
+                ParameterNumberMessage::registered_14_bit(ch(0), u14(420), u14(15000)),
+                // Data Entry LSB is still in effect
+                ParameterNumberMessage::registered_14_bit(ch(0), u14(420), u14(280)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{extract_type_from_status_byte, FromBytesError, ShortMessage, U7};
+
+/// A short message implemented as a borrowed slice of bytes.
+///
+/// Reads the bytes directly from the slice without copying them, so it's suited for parsing
+/// incoming bytes with minimum overhead.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::{Channel, ShortMessage, ShortMessageRef, U7};
+///
+/// let bytes = [0x95, 64, 123];
+/// let msg = ShortMessageRef::new(&bytes).unwrap();
+/// assert_eq!(msg.channel(), Some(Channel::new(5)));
+/// assert_eq!(msg.velocity(), Some(U7::new(123)));
+/// assert!(ShortMessageRef::new(&bytes[..2]).is_err());
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ShortMessageRef<'a>(&'a [u8]);
+
+impl<'a> ShortMessageRef<'a> {
+    /// Creates a view on the given bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the slice doesn't consist of exactly 3 bytes, if the first one is not a
+    /// valid status byte or if one of the other ones is not a valid data byte.
+    pub fn new(bytes: &'a [u8]) -> Result<ShortMessageRef<'a>, FromBytesError> {
+        if bytes.len() != 3 {
+            return Err(FromBytesError(()));
+        }
+        extract_type_from_status_byte(bytes[0]).map_err(|_| FromBytesError(()))?;
+        if bytes[1] > U7::MAX.get() || bytes[2] > U7::MAX.get() {
+            return Err(FromBytesError(()));
+        }
+        Ok(ShortMessageRef(bytes))
+    }
+
+    /// Returns the underlying bytes.
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a> ShortMessage for ShortMessageRef<'a> {
+    fn status_byte(&self) -> u8 {
+        self.0[0]
+    }
+
+    fn data_byte_1(&self) -> U7 {
+        U7(self.0[1])
+    }
+
+    fn data_byte_2(&self) -> U7 {
+        U7(self.0[2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, u14, u7};
+    use crate::{ParameterNumberMessage, ParameterNumberMessageScanner};
+
+    #[test]
+    fn new_validates_bytes() {
+        // Given
+        // When
+        // Then
+        assert!(ShortMessageRef::new(&[0x90, 64, 100]).is_ok());
+        assert!(ShortMessageRef::new(&[0x90, 64]).is_err());
+        assert!(ShortMessageRef::new(&[0x90, 64, 100, 0]).is_err());
+        assert!(ShortMessageRef::new(&[0x40, 64, 100]).is_err());
+        assert!(ShortMessageRef::new(&[0x90, 128, 100]).is_err());
+        assert!(ShortMessageRef::new(&[0x90, 64, 200]).is_err());
+    }
+
+    #[test]
+    fn feed_scanner() {
+        // Given
+        let bytes: [u8; 12] = [0xb0, 101, 3, 0xb0, 100, 36, 0xb0, 38, 24, 0xb0, 6, 117];
+        let mut scanner = ParameterNumberMessageScanner::new();
+        // When
+        let results: Vec<_> = bytes
+            .chunks(3)
+            .filter_map(|chunk| scanner.feed(&ShortMessageRef::new(chunk).unwrap()))
+            .collect();
+        // Then
+        assert_eq!(
+            results,
+            vec![ParameterNumberMessage::registered_14_bit(
+                ch(0),
+                u14(420),
+                u14(15000)
+            )]
+        );
+        assert_eq!(
+            ShortMessageRef::new(&bytes[9..]).unwrap().data_byte_2(),
+            u7(117)
+        );
+    }
+}
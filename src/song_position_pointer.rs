@@ -0,0 +1,128 @@
+use crate::{ShortMessage, ShortMessageFactory, StructuredShortMessage, U14};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Number of MIDI beats (sixteenth notes) per whole note.
+const MIDI_BEATS_PER_WHOLE_NOTE: f64 = 16.0;
+
+/// A Song Position Pointer message, which tells the receiver where to continue playing.
+///
+/// The position is counted in MIDI beats since the start of the song. One MIDI beat is a
+/// sixteenth note, that is 6 Timing Clock messages.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{u14, u7};
+/// use helgoboss_midi::{RawShortMessage, ShortMessage, SongPositionPointer};
+///
+/// let spp = SongPositionPointer::new(u14(3000));
+/// let msg: RawShortMessage = spp.to_short_message();
+/// assert_eq!(msg.to_bytes(), (0xf2, u7(56), u7(23)));
+/// assert_eq!(SongPositionPointer::from_short_message(&msg), Some(spp));
+/// // In 4/4, a bar has 16 MIDI beats
+/// assert_eq!(spp.bars(4, 4), 187.5);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SongPositionPointer {
+    beats: U14,
+}
+
+impl SongPositionPointer {
+    /// Creates a Song Position Pointer pointing to the given MIDI beat.
+    pub fn new(beats: U14) -> SongPositionPointer {
+        SongPositionPointer { beats }
+    }
+
+    /// Returns the Song Position Pointer carried by the given message or `None` if it's not a
+    /// Song Position Pointer message.
+    ///
+    /// Works with any short message, including [`StructuredShortMessage`].
+    ///
+    /// [`StructuredShortMessage`]: enum.StructuredShortMessage.html
+    pub fn from_short_message(msg: &impl ShortMessage) -> Option<SongPositionPointer> {
+        match msg.to_structured() {
+            StructuredShortMessage::SongPositionPointer { position } => Some(Self::new(position)),
+            _ => None,
+        }
+    }
+
+    /// Returns the position in MIDI beats (sixteenth notes).
+    pub fn beats(&self) -> U14 {
+        self.beats
+    }
+
+    /// Creates the short message which sends this Song Position Pointer.
+    pub fn to_short_message<T: ShortMessageFactory>(&self) -> T {
+        T::song_position_pointer(self.beats)
+    }
+
+    /// Creates a Song Position Pointer pointing to the given (zero-based, possibly fractional)
+    /// bar, given the time signature.
+    ///
+    /// The position is rounded to the nearest MIDI beat. Returns `None` if the position can't be
+    /// represented.
+    pub fn from_bars(bars: f64, numerator: u8, denominator: u8) -> Option<SongPositionPointer> {
+        let beats = (bars * midi_beats_per_bar(numerator, denominator)).round();
+        if !(0.0..=U14::MAX.get() as f64).contains(&beats) {
+            return None;
+        }
+        Some(Self::new(U14(beats as u16)))
+    }
+
+    /// Returns the position in (zero-based, possibly fractional) bars, given the time signature.
+    pub fn bars(&self, numerator: u8, denominator: u8) -> f64 {
+        self.beats.get() as f64 / midi_beats_per_bar(numerator, denominator)
+    }
+}
+
+fn midi_beats_per_bar(numerator: u8, denominator: u8) -> f64 {
+    numerator as f64 * MIDI_BEATS_PER_WHOLE_NOTE / denominator as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{u14, u7};
+    use crate::RawShortMessage;
+
+    #[test]
+    fn split_and_join() {
+        // Given
+        let spp = SongPositionPointer::new(u14(3000));
+        // When
+        let msg: RawShortMessage = spp.to_short_message();
+        let structured: StructuredShortMessage = spp.to_short_message();
+        // Then
+        assert_eq!(msg.to_bytes(), (0xf2, u7(56), u7(23)));
+        assert_eq!(SongPositionPointer::from_short_message(&msg), Some(spp));
+        assert_eq!(
+            SongPositionPointer::from_short_message(&structured),
+            Some(spp)
+        );
+        assert_eq!(
+            SongPositionPointer::from_short_message(&RawShortMessage::timing_clock()),
+            None
+        );
+    }
+
+    #[test]
+    fn bars() {
+        // Given
+        // When
+        // Then
+        assert_eq!(SongPositionPointer::new(u14(48)).bars(3, 4), 4.0);
+        assert_eq!(SongPositionPointer::new(u14(18)).bars(6, 8), 1.5);
+        assert_eq!(
+            SongPositionPointer::from_bars(4.0, 3, 4),
+            Some(SongPositionPointer::new(u14(48)))
+        );
+        assert_eq!(
+            SongPositionPointer::from_bars(2.5, 7, 8),
+            Some(SongPositionPointer::new(u14(35)))
+        );
+        assert_eq!(SongPositionPointer::from_bars(1024.0, 4, 4), None);
+        assert_eq!(SongPositionPointer::from_bars(-1.0, 4, 4), None);
+    }
+}
@@ -1,8 +1,11 @@
+use crate::bit_util::{
+    build_14_bit_value_from_two_7_bit_values, extract_high_7_bit_value_from_14_bit_value,
+    extract_low_7_bit_value_from_14_bit_value,
+};
 use crate::{
-    build_14_bit_value_from_two_7_bit_values, build_status_byte, extract_channel_from_status_byte,
-    extract_high_7_bit_value_from_14_bit_value, extract_low_7_bit_value_from_14_bit_value,
-    extract_type_from_status_byte, Channel, ControllerNumber, KeyNumber, ShortMessage,
-    ShortMessageFactory, ShortMessageType, TimeCodeQuarterFrame, U14, U7,
+    build_status_byte, extract_channel_from_status_byte, extract_type_from_status_byte, Channel,
+    ControllerNumber, KeyNumber, ShortMessage, ShortMessageFactory, ShortMessageType,
+    TimeCodeQuarterFrame, U14, U7,
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -106,6 +109,44 @@ pub enum StructuredShortMessage {
     SystemRealTimeUndefined2,
 }
 
+impl StructuredShortMessage {
+    /// Rewrites a Note On message with velocity 0 into a Note Off message with velocity 0 on the
+    /// same channel and key number.
+    ///
+    /// According to the MIDI specification, both have the same meaning. All other messages are
+    /// returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::{channel, key_number, note_on, u7};
+    /// use helgoboss_midi::{ShortMessage, StructuredShortMessage};
+    ///
+    /// assert_eq!(
+    ///     note_on(2, 60, 0).to_structured().normalize_note_off(),
+    ///     StructuredShortMessage::NoteOff {
+    ///         channel: channel(2),
+    ///         key_number: key_number(60),
+    ///         velocity: u7(0),
+    ///     }
+    /// );
+    /// ```
+    pub fn normalize_note_off(self) -> StructuredShortMessage {
+        match self {
+            StructuredShortMessage::NoteOn {
+                channel,
+                key_number,
+                velocity,
+            } if velocity == U7::MIN => StructuredShortMessage::NoteOff {
+                channel,
+                key_number,
+                velocity,
+            },
+            msg => msg,
+        }
+    }
+}
+
 impl ShortMessageFactory for StructuredShortMessage {
     unsafe fn from_bytes_unchecked((status_byte, data_byte_1, data_byte_2): (u8, U7, U7)) -> Self {
         use ShortMessageType::*;
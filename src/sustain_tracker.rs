@@ -0,0 +1,242 @@
+use crate::{controller_numbers, Channel, KeyNumber, ShortMessage, StructuredShortMessage, U7};
+
+/// Tracker for finding out which notes are still sounding, taking the damper pedal (sustain) into
+/// account.
+///
+/// A note which is released while the damper pedal is down is not silenced but *sustained* until
+/// the pedal is released. The tracker also respects All Notes Off (notes are sustained if the pedal
+/// is down), All Sound Off (everything is silenced) and Reset All Controllers (pedal goes up).
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, control_change, key_number, note_off, note_on};
+/// use helgoboss_midi::SustainTracker;
+///
+/// let mut tracker = SustainTracker::new();
+/// tracker.feed(&note_on(0, 60, 100));
+/// tracker.feed(&control_change(0, 64, 127));
+/// tracker.feed(&note_off(0, 60, 0));
+/// assert!(tracker.is_note_sustained(channel(0), key_number(60)));
+/// tracker.feed(&control_change(0, 64, 0));
+/// assert!(!tracker.is_note_sounding(channel(0), key_number(60)));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct SustainTracker {
+    tracker_by_channel: [TrackerForOneChannel; 16],
+}
+
+impl SustainTracker {
+    /// Creates a new tracker.
+    pub fn new() -> SustainTracker {
+        Default::default()
+    }
+
+    /// Feeds the tracker a single short message.
+    pub fn feed(&mut self, msg: &impl ShortMessage) {
+        if let Some(channel) = msg.channel() {
+            self.tracker_by_channel[usize::from(channel)].feed(msg);
+        }
+    }
+
+    /// Returns whether the damper pedal is currently down on the given channel.
+    pub fn is_pedal_down(&self, channel: Channel) -> bool {
+        self.tracker_by_channel[usize::from(channel)].pedal_down
+    }
+
+    /// Returns whether the given note is currently held by a key.
+    pub fn is_note_held(&self, channel: Channel, key_number: KeyNumber) -> bool {
+        self.tracker_by_channel[usize::from(channel)].held & key_bit(key_number) != 0
+    }
+
+    /// Returns whether the given note has been released but is still sounding because of the
+    /// damper pedal.
+    pub fn is_note_sustained(&self, channel: Channel, key_number: KeyNumber) -> bool {
+        self.tracker_by_channel[usize::from(channel)].sustained & key_bit(key_number) != 0
+    }
+
+    /// Returns whether the given note is currently sounding, either held or sustained.
+    pub fn is_note_sounding(&self, channel: Channel, key_number: KeyNumber) -> bool {
+        self.is_note_held(channel, key_number) || self.is_note_sustained(channel, key_number)
+    }
+
+    /// Returns all notes which are currently sounding on the given channel, either held or
+    /// sustained.
+    pub fn sounding_notes(&self, channel: Channel) -> impl Iterator<Item = KeyNumber> {
+        let t = self.tracker_by_channel[usize::from(channel)];
+        key_numbers(t.held | t.sustained)
+    }
+
+    pub(crate) fn held_notes(&self, channel: Channel) -> impl Iterator<Item = KeyNumber> {
+        key_numbers(self.tracker_by_channel[usize::from(channel)].held)
+    }
+
+    pub(crate) fn sustained_notes(&self, channel: Channel) -> impl Iterator<Item = KeyNumber> {
+        key_numbers(self.tracker_by_channel[usize::from(channel)].sustained)
+    }
+
+    pub(crate) fn release_pedals(&mut self) {
+        for t in self.tracker_by_channel.iter_mut() {
+            t.release_pedal();
+        }
+    }
+
+    /// Resets the tracker forgetting all notes and pedal states.
+    pub fn reset(&mut self) {
+        for t in self.tracker_by_channel.iter_mut() {
+            *t = Default::default();
+        }
+    }
+}
+
+fn key_bit(key_number: KeyNumber) -> u128 {
+    1 << key_number.get()
+}
+
+fn key_numbers(bits: u128) -> impl Iterator<Item = KeyNumber> {
+    (0..128u8)
+        .filter(move |i| bits & (1 << i) != 0)
+        .map(KeyNumber)
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+struct TrackerForOneChannel {
+    pedal_down: bool,
+    held: u128,
+    sustained: u128,
+}
+
+impl TrackerForOneChannel {
+    fn feed(&mut self, msg: &impl ShortMessage) {
+        use StructuredShortMessage::*;
+        match msg.to_structured() {
+            NoteOn {
+                key_number,
+                velocity,
+                ..
+            } if velocity > U7::MIN => {
+                self.held |= key_bit(key_number);
+                self.sustained &= !key_bit(key_number);
+            }
+            NoteOn { key_number, .. } | NoteOff { key_number, .. } => {
+                self.release(key_bit(key_number));
+            }
+            ControlChange {
+                controller_number,
+                control_value,
+                ..
+            } => match controller_number {
+                controller_numbers::DAMPER_PEDAL_ON_OFF => {
+                    if control_value >= U7(64) {
+                        self.pedal_down = true;
+                    } else {
+                        self.release_pedal();
+                    }
+                }
+                controller_numbers::ALL_NOTES_OFF => self.release(self.held),
+                controller_numbers::ALL_SOUND_OFF => {
+                    self.held = 0;
+                    self.sustained = 0;
+                }
+                controller_numbers::RESET_ALL_CONTROLLERS => self.release_pedal(),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn release(&mut self, keys: u128) {
+        self.held &= !keys;
+        if self.pedal_down {
+            self.sustained |= keys;
+        }
+    }
+
+    fn release_pedal(&mut self) {
+        self.pedal_down = false;
+        self.sustained = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, key_number as kn, u7};
+    use crate::{RawShortMessage, ShortMessageFactory};
+
+    #[test]
+    fn should_keep_notes_sounding_until_pedal_up() {
+        // Given
+        let mut tracker = SustainTracker::new();
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(64), u7(127)));
+        tracker.feed(&RawShortMessage::note_on(ch(0), kn(60), u7(100)));
+        tracker.feed(&RawShortMessage::note_on(ch(0), kn(64), u7(100)));
+        tracker.feed(&RawShortMessage::note_off(ch(0), kn(60), u7(0)));
+        tracker.feed(&RawShortMessage::note_on(ch(0), kn(64), u7(0)));
+        // Then
+        assert!(tracker.is_pedal_down(ch(0)));
+        assert!(!tracker.is_note_held(ch(0), kn(60)));
+        assert!(tracker.is_note_sustained(ch(0), kn(60)));
+        assert!(tracker.is_note_sustained(ch(0), kn(64)));
+        assert_eq!(
+            tracker.sounding_notes(ch(0)).collect::<Vec<_>>(),
+            vec![kn(60), kn(64)]
+        );
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(64), u7(0)));
+        // Then
+        assert!(!tracker.is_pedal_down(ch(0)));
+        assert!(!tracker.is_note_sounding(ch(0), kn(60)));
+        assert!(!tracker.is_note_sounding(ch(0), kn(64)));
+        assert_eq!(tracker.sounding_notes(ch(0)).count(), 0);
+    }
+
+    #[test]
+    fn should_release_notes_without_pedal_immediately() {
+        // Given
+        let mut tracker = SustainTracker::new();
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(1), cn(64), u7(127)));
+        tracker.feed(&RawShortMessage::note_on(ch(0), kn(60), u7(100)));
+        tracker.feed(&RawShortMessage::note_off(ch(0), kn(60), u7(0)));
+        // Then
+        assert!(!tracker.is_note_sounding(ch(0), kn(60)));
+    }
+
+    #[test]
+    fn should_handle_retriggering_sustained_note() {
+        // Given
+        let mut tracker = SustainTracker::new();
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(64), u7(127)));
+        tracker.feed(&RawShortMessage::note_on(ch(0), kn(60), u7(100)));
+        tracker.feed(&RawShortMessage::note_off(ch(0), kn(60), u7(0)));
+        tracker.feed(&RawShortMessage::note_on(ch(0), kn(60), u7(100)));
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(64), u7(0)));
+        // Then
+        assert!(tracker.is_note_held(ch(0), kn(60)));
+        assert!(!tracker.is_note_sustained(ch(0), kn(60)));
+    }
+
+    #[test]
+    fn should_respect_channel_mode_messages() {
+        // Given
+        let mut tracker = SustainTracker::new();
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(64), u7(127)));
+        tracker.feed(&RawShortMessage::note_on(ch(0), kn(60), u7(100)));
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(123), u7(0)));
+        // Then
+        assert!(tracker.is_note_sustained(ch(0), kn(60)));
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(120), u7(0)));
+        // Then
+        assert!(!tracker.is_note_sounding(ch(0), kn(60)));
+        assert!(tracker.is_pedal_down(ch(0)));
+        // When
+        tracker.feed(&RawShortMessage::control_change(ch(0), cn(121), u7(0)));
+        // Then
+        assert!(!tracker.is_pedal_down(ch(0)));
+    }
+}
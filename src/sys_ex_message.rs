@@ -0,0 +1,184 @@
+use crate::U7;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An error which can occur when trying to create a [`SysExMessage`] from raw bytes.
+///
+/// [`SysExMessage`]: struct.SysExMessage.html
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[display(fmt = "invalid System Exclusive message")]
+pub struct SysExError(pub(crate) ());
+
+impl std::error::Error for SysExError {}
+
+/// The ID which identifies the manufacturer (or the universal category) of a System Exclusive
+/// message.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ManufacturerId {
+    /// A 1-byte ID, e.g. 0x41 (Roland) or 0x7E (Universal Non-Real Time).
+    OneByte(U7),
+    /// A 3-byte ID, which is transmitted as 0x00 followed by these 2 bytes.
+    ThreeBytes(U7, U7),
+}
+
+impl ManufacturerId {
+    /// ID of Universal Non-Real Time System Exclusive messages.
+    pub const UNIVERSAL_NON_REAL_TIME: ManufacturerId = ManufacturerId::OneByte(U7(0x7e));
+
+    /// ID of Universal Real Time System Exclusive messages.
+    pub const UNIVERSAL_REAL_TIME: ManufacturerId = ManufacturerId::OneByte(U7(0x7f));
+
+    /// Returns whether this is the ID of Universal (Non-)Real Time messages rather than of a
+    /// manufacturer.
+    pub fn is_universal(&self) -> bool {
+        *self == Self::UNIVERSAL_NON_REAL_TIME || *self == Self::UNIVERSAL_REAL_TIME
+    }
+}
+
+/// A complete System Exclusive message, that is a manufacturer ID followed by any number of data
+/// bytes.
+///
+/// In contrast to short messages, the length of System Exclusive messages is not fixed, so this
+/// type allocates.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::u7;
+/// use helgoboss_midi::{ManufacturerId, SysExMessage};
+///
+/// // Identity Request
+/// let bytes = [0xf0, 0x7e, 0x7f, 0x06, 0x01, 0xf7];
+/// let msg = SysExMessage::from_bytes(&bytes).unwrap();
+/// assert_eq!(msg.manufacturer_id(), ManufacturerId::UNIVERSAL_NON_REAL_TIME);
+/// assert_eq!(msg.payload(), &[u7(0x7f), u7(0x06), u7(0x01)]);
+/// assert_eq!(msg.to_bytes(), bytes);
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SysExMessage {
+    manufacturer_id: ManufacturerId,
+    payload: Vec<U7>,
+}
+
+const SYSTEM_EXCLUSIVE_START: u8 = 0xf0;
+const SYSTEM_EXCLUSIVE_END: u8 = 0xf7;
+
+impl SysExMessage {
+    /// Creates a System Exclusive message with the given manufacturer ID and data bytes.
+    pub fn new(manufacturer_id: ManufacturerId, payload: Vec<U7>) -> SysExMessage {
+        SysExMessage {
+            manufacturer_id,
+            payload,
+        }
+    }
+
+    /// Parses the given complete System Exclusive message, including start (0xF0) and end (0xF7)
+    /// byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the start or end byte is missing, if there's no complete manufacturer
+    /// ID or if any data byte is greater than 127.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SysExMessage, SysExError> {
+        let inner = match bytes {
+            [SYSTEM_EXCLUSIVE_START, inner @ .., SYSTEM_EXCLUSIVE_END] => inner,
+            _ => return Err(SysExError(())),
+        };
+        if inner.iter().any(|b| *b > U7::MAX.get()) {
+            return Err(SysExError(()));
+        }
+        let (manufacturer_id, payload) = match inner {
+            [0, b1, b2, payload @ ..] => (ManufacturerId::ThreeBytes(U7(*b1), U7(*b2)), payload),
+            [0, ..] | [] => return Err(SysExError(())),
+            [b0, payload @ ..] => (ManufacturerId::OneByte(U7(*b0)), payload),
+        };
+        Ok(SysExMessage {
+            manufacturer_id,
+            payload: payload.iter().map(|b| U7(*b)).collect(),
+        })
+    }
+
+    /// Returns the manufacturer ID.
+    pub fn manufacturer_id(&self) -> ManufacturerId {
+        self.manufacturer_id
+    }
+
+    /// Returns the data bytes following the manufacturer ID.
+    pub fn payload(&self) -> &[U7] {
+        &self.payload
+    }
+
+    /// Returns the complete bytes of this message, including start (0xF0) and end (0xF7) byte.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.payload.len() + 5);
+        bytes.push(SYSTEM_EXCLUSIVE_START);
+        match self.manufacturer_id {
+            ManufacturerId::OneByte(id) => bytes.push(id.get()),
+            ManufacturerId::ThreeBytes(b1, b2) => bytes.extend_from_slice(&[0, b1.get(), b2.get()]),
+        }
+        bytes.extend(self.payload.iter().map(|b| b.get()));
+        bytes.push(SYSTEM_EXCLUSIVE_END);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::u7;
+
+    #[test]
+    fn universal_non_real_time() {
+        // Given
+        // GM System On
+        let bytes = [0xf0, 0x7e, 0x7f, 0x09, 0x01, 0xf7];
+        // When
+        let msg = SysExMessage::from_bytes(&bytes).unwrap();
+        // Then
+        assert_eq!(
+            msg.manufacturer_id(),
+            ManufacturerId::UNIVERSAL_NON_REAL_TIME
+        );
+        assert!(msg.manufacturer_id().is_universal());
+        assert_eq!(msg.payload(), &[u7(0x7f), u7(0x09), u7(0x01)]);
+        assert_eq!(msg.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn extended_manufacturer_id() {
+        // Given
+        let bytes = [0xf0, 0x00, 0x20, 0x29, 0x02, 0x0c, 0xf7];
+        // When
+        let msg = SysExMessage::from_bytes(&bytes).unwrap();
+        // Then
+        assert_eq!(
+            msg.manufacturer_id(),
+            ManufacturerId::ThreeBytes(u7(0x20), u7(0x29))
+        );
+        assert!(!msg.manufacturer_id().is_universal());
+        assert_eq!(msg.payload(), &[u7(0x02), u7(0x0c)]);
+        assert_eq!(msg.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn reject_invalid() {
+        // Given
+        let invalid: [&[u8]; 7] = [
+            &[],
+            &[0xf0, 0xf7],
+            &[0xf0, 0x41, 0x10],
+            &[0x41, 0x10, 0xf7],
+            &[0xf0, 0x00, 0x20, 0xf7],
+            &[0xf0, 0x41, 0x80, 0xf7],
+            &[0xf0, 0x41, 0xf7, 0xf7],
+        ];
+        // When
+        // Then
+        for bytes in invalid.iter() {
+            assert_eq!(SysExMessage::from_bytes(bytes), Err(SysExError(())));
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use crate::SysExMessage;
+
+const SYSTEM_EXCLUSIVE_START: u8 = 0xf0;
+const SYSTEM_EXCLUSIVE_END: u8 = 0xf7;
+
+/// Scanner for reassembling System Exclusive messages from chunks of bytes.
+///
+/// MIDI drivers usually deliver long System Exclusive messages in several chunks. This scanner
+/// buffers the bytes across calls until the end byte (0xF7) arrives. System Real Time bytes
+/// interleaved with the message are skipped. If another status byte arrives before the end byte,
+/// the incomplete message is discarded. The same happens if the message gets longer than the
+/// maximum length (64 KiB by default), which protects against unbounded memory usage.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::SysExScanner;
+///
+/// let mut scanner = SysExScanner::new();
+/// assert_eq!(scanner.feed(&[0xf0, 0x7e]), None);
+/// assert_eq!(scanner.feed(&[0x7f, 0x06]), None);
+/// let msg = scanner.feed(&[0x01, 0xf7]).unwrap();
+/// assert_eq!(msg.to_bytes(), vec![0xf0, 0x7e, 0x7f, 0x06, 0x01, 0xf7]);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SysExScanner {
+    max_length: usize,
+    buffer: Vec<u8>,
+    is_receiving: bool,
+    has_overflown: bool,
+    unprocessed_bytes: Vec<u8>,
+}
+
+impl Default for SysExScanner {
+    fn default() -> Self {
+        SysExScanner {
+            max_length: 64 * 1024,
+            buffer: Vec::new(),
+            is_receiving: false,
+            has_overflown: false,
+            unprocessed_bytes: Vec::new(),
+        }
+    }
+}
+
+impl SysExScanner {
+    /// Creates a new scanner with a maximum message length of 64 KiB.
+    pub fn new() -> SysExScanner {
+        Default::default()
+    }
+
+    /// Sets the maximum number of bytes of one message, including start and end byte.
+    ///
+    /// Longer messages are discarded.
+    pub fn set_max_length(&mut self, max_length: usize) {
+        self.max_length = max_length;
+    }
+
+    /// Feeds the scanner the next chunk of bytes.
+    ///
+    /// Returns the System Exclusive message if this chunk completes one. Each call returns at most
+    /// one message. If the chunk contains bytes after the end of the returned message, they are
+    /// kept and processed on the next call, so calling this method with an empty chunk returns
+    /// messages completed by previously fed bytes.
+    pub fn feed(&mut self, bytes: &[u8]) -> Option<SysExMessage> {
+        self.unprocessed_bytes.extend_from_slice(bytes);
+        let mut processed_count = 0;
+        let mut result = None;
+        for i in 0..self.unprocessed_bytes.len() {
+            processed_count += 1;
+            result = self.process_byte(self.unprocessed_bytes[i]);
+            if result.is_some() {
+                break;
+            }
+        }
+        self.unprocessed_bytes.drain(..processed_count);
+        result
+    }
+
+    /// Resets the scanner discarding any incomplete message and unprocessed bytes.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.is_receiving = false;
+        self.has_overflown = false;
+        self.unprocessed_bytes.clear();
+    }
+
+    fn process_byte(&mut self, byte: u8) -> Option<SysExMessage> {
+        match byte {
+            SYSTEM_EXCLUSIVE_START => {
+                self.buffer.clear();
+                self.is_receiving = true;
+                self.has_overflown = false;
+                self.push_to_buffer(byte);
+                None
+            }
+            _ if !self.is_receiving => None,
+            // System Real Time messages may be interleaved with anything
+            0xf8..=0xff => None,
+            SYSTEM_EXCLUSIVE_END => {
+                self.push_to_buffer(byte);
+                self.is_receiving = false;
+                let result = if self.has_overflown {
+                    None
+                } else {
+                    SysExMessage::from_bytes(&self.buffer).ok()
+                };
+                self.buffer.clear();
+                result
+            }
+            0x80..=0xff => {
+                self.buffer.clear();
+                self.is_receiving = false;
+                None
+            }
+            _ => {
+                self.push_to_buffer(byte);
+                None
+            }
+        }
+    }
+
+    fn push_to_buffer(&mut self, byte: u8) {
+        if self.has_overflown {
+            return;
+        }
+        if self.buffer.len() >= self.max_length {
+            self.has_overflown = true;
+            self.buffer = Vec::new();
+            return;
+        }
+        self.buffer.push(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::u7;
+    use crate::ManufacturerId;
+
+    #[test]
+    fn should_reassemble_three_chunks() {
+        // Given
+        let mut scanner = SysExScanner::new();
+        // When
+        let result_1 = scanner.feed(&[0xf0]);
+        let result_2 = scanner.feed(&[0x41, 0x10, 0x42, 0xf8, 0x12]);
+        let result_3 = scanner.feed(&[0x40, 0x00, 0x7f, 0x00, 0x41, 0xf7]);
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(
+            result_3,
+            Some(SysExMessage::new(
+                ManufacturerId::OneByte(u7(0x41)),
+                vec![0x10, 0x42, 0x12, 0x40, 0x00, 0x7f, 0x00, 0x41]
+                    .into_iter()
+                    .map(u7)
+                    .collect()
+            ))
+        );
+    }
+
+    #[test]
+    fn should_handle_end_many_chunks_later() {
+        // Given
+        let mut scanner = SysExScanner::new();
+        // When
+        let start_result = scanner.feed(&[0xf0]);
+        let data_results: Vec<_> = (0..100).map(|_| scanner.feed(&[0x7e, 0x01])).collect();
+        let end_result = scanner.feed(&[0xf7]);
+        // Then
+        assert_eq!(start_result, None);
+        assert!(data_results.iter().all(|r| r.is_none()));
+        let msg = end_result.unwrap();
+        assert_eq!(
+            msg.manufacturer_id(),
+            ManufacturerId::UNIVERSAL_NON_REAL_TIME
+        );
+        assert_eq!(msg.payload().len(), 199);
+    }
+
+    #[test]
+    fn should_keep_bytes_after_end() {
+        // Given
+        let mut scanner = SysExScanner::new();
+        // When
+        let result_1 = scanner.feed(&[0xf0, 0x41, 0x01, 0xf7, 0xf0, 0x42, 0x02, 0xf7, 0xf0]);
+        let result_2 = scanner.feed(&[]);
+        let result_3 = scanner.feed(&[0x43, 0xf7]);
+        // Then
+        assert_eq!(result_1.unwrap().to_bytes(), vec![0xf0, 0x41, 0x01, 0xf7]);
+        assert_eq!(result_2.unwrap().to_bytes(), vec![0xf0, 0x42, 0x02, 0xf7]);
+        assert_eq!(result_3.unwrap().to_bytes(), vec![0xf0, 0x43, 0xf7]);
+    }
+
+    #[test]
+    fn should_discard_too_long_and_interrupted_messages() {
+        // Given
+        let mut scanner = SysExScanner::new();
+        scanner.set_max_length(5);
+        // When
+        let result_1 = scanner.feed(&[0xf0, 0x41, 0x01, 0x02, 0x03, 0xf7]);
+        let result_2 = scanner.feed(&[0xf0, 0x41, 0x90, 0x40, 0x40, 0xf7]);
+        let result_3 = scanner.feed(&[0xf0, 0x41, 0x01, 0x02, 0xf7]);
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(
+            result_3.unwrap().to_bytes(),
+            vec![0xf0, 0x41, 0x01, 0x02, 0xf7]
+        );
+    }
+}
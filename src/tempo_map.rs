@@ -0,0 +1,265 @@
+use crate::{MetaEvent, ShortMessage, ShortMessageType, Timed};
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// Tempo which applies as long as no tempo has been set, as defined by the Standard MIDI File
+/// specification (120 BPM).
+const DEFAULT_MICROSECONDS_PER_QUARTER_NOTE: u32 = 500_000;
+
+/// Number of Timing Clock messages per quarter note.
+const TIMING_CLOCKS_PER_QUARTER_NOTE: u32 = 24;
+
+/// Map of tempo changes for converting musical time (ticks) to wall-clock time.
+///
+/// A tick is the smallest unit of musical time, its length in relation to a quarter note is
+/// defined by the resolution of the map. Until the first tempo change, the tempo is 120 BPM.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::{MetaEvent, TempoMap};
+///
+/// let events = vec![(
+///     960,
+///     MetaEvent::SetTempo {
+///         microseconds_per_quarter_note: 1_000_000,
+///     },
+/// )];
+/// let map = TempoMap::from_events(480, events);
+/// assert_eq!(map.tempo_at(0), 120.0);
+/// assert_eq!(map.tempo_at(960), 60.0);
+/// assert_eq!(map.ticks_to_seconds(1440), 2.0);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TempoMap {
+    ticks_per_quarter_note: u32,
+    segments: Vec<TempoSegment>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct TempoSegment {
+    start_tick: u64,
+    start_seconds: f64,
+    microseconds_per_quarter_note: u32,
+}
+
+impl TempoMap {
+    /// Creates a tempo map from the given SetTempo meta events, each one together with the tick at
+    /// which it occurs.
+    ///
+    /// Other meta events are ignored. The events don't need to be ordered. If several tempo
+    /// changes occur at the same tick, the last one wins.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ticks_per_quarter_note` is 0.
+    pub fn from_events(
+        ticks_per_quarter_note: u32,
+        events: impl IntoIterator<Item = (u64, MetaEvent)>,
+    ) -> TempoMap {
+        let mut tempo_changes: Vec<_> = events
+            .into_iter()
+            .filter_map(|(tick, event)| match event {
+                MetaEvent::SetTempo {
+                    microseconds_per_quarter_note,
+                } => Some((tick, microseconds_per_quarter_note)),
+                _ => None,
+            })
+            .collect();
+        tempo_changes.sort_by_key(|(tick, _)| *tick);
+        Self::from_tempo_changes(ticks_per_quarter_note, tempo_changes)
+    }
+
+    /// Creates a tempo map from the given Timing Clock messages, each one together with the time
+    /// at which it occurred.
+    ///
+    /// Each Timing Clock message is one tick, so the resolution of the resulting map is 24 ticks
+    /// per quarter note and tick 0 is the first Timing Clock message. The interval to the next
+    /// Timing Clock message determines the tempo. Other messages are ignored. The messages must be
+    /// ordered by time.
+    ///
+    /// Intervals of zero, e.g. caused by duplicate messages, keep the previous tempo. Intervals
+    /// longer than about 3 minutes, e.g. caused by stopping the clock, result in the slowest
+    /// representable tempo.
+    pub fn from_timing_clocks<T: ShortMessage>(
+        msgs: impl IntoIterator<Item = Timed<T>>,
+    ) -> TempoMap {
+        let clock_times: Vec<_> = msgs
+            .into_iter()
+            .filter(|msg| msg.value.r#type() == ShortMessageType::TimingClock)
+            .map(|msg| msg.time)
+            .collect();
+        let tempo_changes = clock_times
+            .windows(2)
+            .enumerate()
+            .filter_map(|(tick, times)| {
+                let interval = times[1] - times[0];
+                if interval == Duration::ZERO {
+                    return None;
+                }
+                let microseconds = interval.as_micros() * TIMING_CLOCKS_PER_QUARTER_NOTE as u128;
+                Some((tick as u64, u32::try_from(microseconds).unwrap_or(u32::MAX)))
+            });
+        Self::from_tempo_changes(TIMING_CLOCKS_PER_QUARTER_NOTE, tempo_changes)
+    }
+
+    fn from_tempo_changes(
+        ticks_per_quarter_note: u32,
+        tempo_changes: impl IntoIterator<Item = (u64, u32)>,
+    ) -> TempoMap {
+        assert!(
+            ticks_per_quarter_note > 0,
+            "ticks_per_quarter_note must be greater than 0"
+        );
+        let mut map = TempoMap {
+            ticks_per_quarter_note,
+            segments: vec![TempoSegment {
+                start_tick: 0,
+                start_seconds: 0.0,
+                microseconds_per_quarter_note: DEFAULT_MICROSECONDS_PER_QUARTER_NOTE,
+            }],
+        };
+        for (tick, microseconds_per_quarter_note) in tempo_changes {
+            let last = *map.segments.last().expect("there's always one segment");
+            if last.microseconds_per_quarter_note == microseconds_per_quarter_note {
+                continue;
+            }
+            let segment = TempoSegment {
+                start_tick: tick,
+                start_seconds: map.ticks_to_seconds(tick),
+                microseconds_per_quarter_note,
+            };
+            if last.start_tick == tick {
+                *map.segments.last_mut().expect("there's always one segment") = segment;
+            } else {
+                map.segments.push(segment);
+            }
+        }
+        map
+    }
+
+    /// Returns the resolution of this map.
+    pub fn ticks_per_quarter_note(&self) -> u32 {
+        self.ticks_per_quarter_note
+    }
+
+    /// Returns the tempo at the given tick in beats (quarter notes) per minute.
+    pub fn tempo_at(&self, ticks: u64) -> f64 {
+        60_000_000.0 / self.segment_at(ticks).microseconds_per_quarter_note as f64
+    }
+
+    /// Converts the given musical time to wall-clock time in seconds, taking all tempo changes
+    /// into account.
+    pub fn ticks_to_seconds(&self, ticks: u64) -> f64 {
+        let segment = self.segment_at(ticks);
+        let quarter_notes =
+            (ticks - segment.start_tick) as f64 / self.ticks_per_quarter_note as f64;
+        segment.start_seconds
+            + quarter_notes * segment.microseconds_per_quarter_note as f64 / 1_000_000.0
+    }
+
+    fn segment_at(&self, ticks: u64) -> &TempoSegment {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.start_tick <= ticks);
+        &self.segments[index - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{note_on, timing_clock};
+
+    fn set_tempo(bpm: u32) -> MetaEvent {
+        MetaEvent::SetTempo {
+            microseconds_per_quarter_note: 60_000_000 / bpm,
+        }
+    }
+
+    #[test]
+    fn two_tempo_segments() {
+        // Given
+        let events = vec![
+            (0, set_tempo(60)),
+            (0, MetaEvent::TrackName("Drums".to_string())),
+            (400, set_tempo(120)),
+        ];
+        // When
+        let map = TempoMap::from_events(100, events);
+        // Then
+        assert_eq!(map.tempo_at(0), 60.0);
+        assert_eq!(map.tempo_at(399), 60.0);
+        assert_eq!(map.ticks_to_seconds(200), 2.0);
+        assert_eq!(map.tempo_at(400), 120.0);
+        assert_eq!(map.tempo_at(10_000), 120.0);
+        assert_eq!(map.ticks_to_seconds(400), 4.0);
+        assert_eq!(map.ticks_to_seconds(600), 5.0);
+    }
+
+    #[test]
+    fn default_tempo() {
+        // Given
+        let events = vec![(240, set_tempo(240))];
+        // When
+        let map = TempoMap::from_events(120, events);
+        // Then
+        assert_eq!(map.tempo_at(0), 120.0);
+        assert_eq!(map.ticks_to_seconds(240), 1.0);
+        assert_eq!(map.ticks_to_seconds(480), 1.5);
+    }
+
+    #[test]
+    fn timing_clocks() {
+        // Given
+        // 2 clocks at 125 BPM (20 ms), 2 clocks at 100 BPM (25 ms)
+        let msgs = vec![
+            Timed::new(Duration::from_millis(1000), timing_clock()),
+            Timed::new(Duration::from_millis(1010), note_on(0, 60, 100)),
+            Timed::new(Duration::from_millis(1020), timing_clock()),
+            Timed::new(Duration::from_millis(1040), timing_clock()),
+            Timed::new(Duration::from_millis(1065), timing_clock()),
+            Timed::new(Duration::from_millis(1090), timing_clock()),
+        ];
+        // When
+        let map = TempoMap::from_timing_clocks(msgs);
+        // Then
+        assert_eq!(map.ticks_per_quarter_note(), 24);
+        assert_eq!(map.tempo_at(1), 125.0);
+        assert_eq!(map.tempo_at(2), 100.0);
+        assert!((map.ticks_to_seconds(4) - 0.09).abs() < 1e-9);
+    }
+    #[test]
+    fn timing_clocks_with_zero_interval() {
+        // Given
+        let msgs = vec![
+            Timed::new(Duration::from_millis(0), timing_clock()),
+            Timed::new(Duration::from_millis(20), timing_clock()),
+            Timed::new(Duration::from_millis(20), timing_clock()),
+            Timed::new(Duration::from_millis(40), timing_clock()),
+        ];
+        // When
+        let map = TempoMap::from_timing_clocks(msgs);
+        // Then
+        assert_eq!(map.tempo_at(0), 125.0);
+        assert_eq!(map.tempo_at(1), 125.0);
+        assert_eq!(map.tempo_at(2), 125.0);
+    }
+
+    #[test]
+    fn timing_clocks_with_long_interval() {
+        // Given
+        let msgs = vec![
+            Timed::new(Duration::from_millis(0), timing_clock()),
+            Timed::new(Duration::from_millis(20), timing_clock()),
+            Timed::new(Duration::from_secs(300), timing_clock()),
+            Timed::new(Duration::from_millis(300_020), timing_clock()),
+        ];
+        // When
+        let map = TempoMap::from_timing_clocks(msgs);
+        // Then
+        assert_eq!(map.tempo_at(0), 125.0);
+        assert_eq!(map.tempo_at(1), 60_000_000.0 / u32::MAX as f64);
+        assert_eq!(map.tempo_at(2), 125.0);
+    }
+}
@@ -269,3 +269,43 @@ pub fn rpn(channel: u8, number: u16, value: u8) -> ParameterNumberMessage {
 pub fn rpn_14_bit(channel: u8, number: u16, value: u16) -> ParameterNumberMessage {
     ParameterNumberMessage::registered_14_bit(ch(channel), u14(number), u14(value))
 }
+
+/// Creates a glissando, that is a Note On/Note Off pair for each key from `from` to `to`
+/// (inclusive).
+///
+/// Sweeps upwards if `from` is lower than `to` and downwards otherwise. The Note Off messages
+/// have a velocity of 0.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::*;
+///
+/// let msgs = glissando(channel(0), key_number(62), key_number(60), u7(100));
+/// assert_eq!(
+///     msgs,
+///     vec![
+///         note_on(0, 62, 100),
+///         note_off(0, 62, 0),
+///         note_on(0, 61, 100),
+///         note_off(0, 61, 0),
+///         note_on(0, 60, 100),
+///         note_off(0, 60, 0),
+///     ]
+/// );
+/// let msgs = glissando(channel(0), key_number(0), key_number(127), u7(100));
+/// assert_eq!(msgs.len(), 256);
+/// assert_eq!(msgs[2], note_on(0, 1, 100));
+/// ```
+pub fn glissando(channel: Channel, from: KeyNumber, to: KeyNumber, velocity: U7) -> Vec<Msg> {
+    let mut keys: Vec<u8> = (from.min(to).get()..=from.max(to).get()).collect();
+    if from > to {
+        keys.reverse();
+    }
+    let mut msgs = Vec::with_capacity(keys.len() * 2);
+    for key in keys {
+        msgs.push(Msg::note_on(channel, KeyNumber(key), velocity));
+        msgs.push(Msg::note_off(channel, KeyNumber(key), U7::MIN));
+    }
+    msgs
+}
@@ -0,0 +1,157 @@
+use std::iter::Peekable;
+use std::time::Duration;
+
+/// A value (usually a short message) together with the time at which it occurred.
+///
+/// The time is relative to an arbitrary reference point, e.g. the start of a recording.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Timed<T> {
+    pub time: Duration,
+    pub value: T,
+}
+
+impl<T> Timed<T> {
+    /// Creates a new timed value.
+    pub fn new(time: Duration, value: T) -> Timed<T> {
+        Timed { time, value }
+    }
+}
+
+/// Extension trait for iterators over [`Timed`] values.
+///
+/// [`Timed`]: struct.Timed.html
+pub trait TimedIteratorExt<T>: Iterator<Item = Timed<T>> + Sized {
+    /// Groups values which occur simultaneously, e.g. the Note On messages of a chord.
+    ///
+    /// Each batch starts with the next value and contains all following values whose time is at
+    /// most `window` after the time of the batch's first value. A `window` of zero groups only
+    /// values with exactly the same time, `Duration::MAX` groups all values into one batch. The
+    /// values must be ordered by time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::test_util::note_on;
+    /// use helgoboss_midi::{Timed, TimedIteratorExt};
+    /// use std::time::Duration;
+    ///
+    /// let msgs = vec![
+    ///     Timed::new(Duration::from_millis(0), note_on(0, 60, 100)),
+    ///     Timed::new(Duration::from_millis(1), note_on(0, 64, 100)),
+    ///     Timed::new(Duration::from_millis(500), note_on(0, 67, 100)),
+    /// ];
+    /// let batches: Vec<_> = msgs
+    ///     .into_iter()
+    ///     .group_by_time(Duration::from_millis(5))
+    ///     .collect();
+    /// assert_eq!(
+    ///     batches,
+    ///     vec![
+    ///         vec![note_on(0, 60, 100), note_on(0, 64, 100)],
+    ///         vec![note_on(0, 67, 100)],
+    ///     ]
+    /// );
+    /// ```
+    fn group_by_time(self, window: Duration) -> GroupByTime<Self> {
+        GroupByTime {
+            iter: self.peekable(),
+            window,
+        }
+    }
+}
+
+impl<T, I: Iterator<Item = Timed<T>>> TimedIteratorExt<T> for I {}
+
+/// Iterator returned by [`TimedIteratorExt::group_by_time`].
+///
+/// [`TimedIteratorExt::group_by_time`]: trait.TimedIteratorExt.html#method.group_by_time
+pub struct GroupByTime<I: Iterator> {
+    iter: Peekable<I>,
+    window: Duration,
+}
+
+impl<T, I: Iterator<Item = Timed<T>>> Iterator for GroupByTime<I> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let first = self.iter.next()?;
+        let batch_start = first.time;
+        let window = self.window;
+        let mut batch = vec![first.value];
+        // Subtracting instead of adding the window to the start can't overflow
+        while let Some(next) = self
+            .iter
+            .next_if(|next| next.time.saturating_sub(batch_start) <= window)
+        {
+            batch.push(next.value);
+        }
+        Some(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, key_number as kn, u7};
+    use crate::{RawShortMessage, ShortMessageFactory};
+
+    #[test]
+    fn group_same_time() {
+        // Given
+        let time = Duration::from_millis(100);
+        let msgs = vec![
+            Timed::new(time, RawShortMessage::note_on(ch(0), kn(60), u7(100))),
+            Timed::new(time, RawShortMessage::note_on(ch(0), kn(64), u7(100))),
+            Timed::new(time, RawShortMessage::note_on(ch(0), kn(67), u7(100))),
+            Timed::new(
+                Duration::from_millis(101),
+                RawShortMessage::note_off(ch(0), kn(60), u7(0)),
+            ),
+        ];
+        // When
+        let batches: Vec<_> = msgs.into_iter().group_by_time(Duration::ZERO).collect();
+        // Then
+        assert_eq!(
+            batches,
+            vec![
+                vec![
+                    RawShortMessage::note_on(ch(0), kn(60), u7(100)),
+                    RawShortMessage::note_on(ch(0), kn(64), u7(100)),
+                    RawShortMessage::note_on(ch(0), kn(67), u7(100)),
+                ],
+                vec![RawShortMessage::note_off(ch(0), kn(60), u7(0))],
+            ]
+        );
+    }
+
+    #[test]
+    fn window_is_measured_from_first_value_of_batch() {
+        // Given
+        let msgs = vec![
+            Timed::new(Duration::from_millis(0), 1),
+            Timed::new(Duration::from_millis(4), 2),
+            Timed::new(Duration::from_millis(8), 3),
+        ];
+        // When
+        let batches: Vec<_> = msgs
+            .into_iter()
+            .group_by_time(Duration::from_millis(5))
+            .collect();
+        // Then
+        assert_eq!(batches, vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn max_window_groups_everything() {
+        // Given
+        let msgs = vec![
+            Timed::new(Duration::from_millis(1), 1),
+            Timed::new(Duration::from_secs(1000), 2),
+            Timed::new(Duration::MAX, 3),
+        ];
+        // When
+        let batches: Vec<_> = msgs.into_iter().group_by_time(Duration::MAX).collect();
+        // Then
+        assert_eq!(batches, vec![vec![1, 2, 3]]);
+    }
+}
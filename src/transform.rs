@@ -0,0 +1,278 @@
+//! Contains functions for transforming short messages, e.g. in a MIDI filter.
+//!
+//! All transforms return an `Option`. `None` means that the message should be dropped.
+//!
+//! # Example
+//!
+//! ```
+//! use helgoboss_midi::test_util::{control_change, note_on, u7};
+//! use helgoboss_midi::transform;
+//!
+//! assert_eq!(
+//!     transform::fixed_velocity(&note_on(0, 64, 30), u7(100)),
+//!     Some(note_on(0, 64, 100))
+//! );
+//! assert_eq!(
+//!     transform::fixed_velocity(&control_change(0, 7, 30), u7(100)),
+//!     Some(control_change(0, 7, 30))
+//! );
+//! ```
+use crate::{
+    Channel, RawShortMessage, ShortMessage, ShortMessageFactory, StructuredShortMessage,
+    VelocityCurve, U7,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Replaces the velocity of Note On messages with the given one.
+///
+/// Note On messages with velocity 0 are left alone because they are effectively Note Off
+/// messages. For the same reason, a given velocity of 0 is raised to 1. All other messages are
+/// passed through unchanged.
+pub fn fixed_velocity(msg: &impl ShortMessage, velocity: U7) -> Option<RawShortMessage> {
+    match msg.to_structured() {
+        StructuredShortMessage::NoteOn {
+            channel,
+            key_number,
+            velocity: original_velocity,
+        } if original_velocity > U7::MIN => Some(RawShortMessage::note_on(
+            channel,
+            key_number,
+            velocity.max(U7(1)),
+        )),
+        _ => Some(msg.to_raw()),
+    }
+}
+
+/// Maps the velocity of Note On messages through the given curve.
+///
+/// Note On messages with velocity 0 are left alone because they are effectively Note Off
+/// messages. For the same reason, a curve output of 0 is raised to 1. All other messages are
+/// passed through unchanged.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{note_on, u7};
+/// use helgoboss_midi::{transform, VelocityCurve};
+///
+/// assert_eq!(
+///     transform::velocity_curve(&note_on(0, 64, 30), &VelocityCurve::Fixed(u7(100))),
+///     Some(note_on(0, 64, 100))
+/// );
+/// ```
+pub fn velocity_curve(msg: &impl ShortMessage, curve: &VelocityCurve) -> Option<RawShortMessage> {
+    match msg.to_structured() {
+        StructuredShortMessage::NoteOn {
+            channel,
+            key_number,
+            velocity,
+        } if velocity > U7::MIN => Some(RawShortMessage::note_on(
+            channel,
+            key_number,
+            curve.apply(velocity).max(U7(1)),
+        )),
+        _ => Some(msg.to_raw()),
+    }
+}
+
+/// Table which maps each of the 16 channels to another channel or drops its messages, for use
+/// with [`remap_channel`].
+///
+/// The default map leaves all channels unchanged.
+///
+/// [`remap_channel`]: fn.remap_channel.html
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChannelMap {
+    targets: [Option<Channel>; 16],
+}
+
+impl Default for ChannelMap {
+    fn default() -> Self {
+        let mut targets = [None; 16];
+        for (target, channel) in targets.iter_mut().zip(Channel::all()) {
+            *target = Some(channel);
+        }
+        ChannelMap { targets }
+    }
+}
+
+impl ChannelMap {
+    /// Creates a map which leaves all channels unchanged.
+    pub fn new() -> ChannelMap {
+        Default::default()
+    }
+
+    /// Maps the given channel to the given target channel. `None` drops messages on that channel.
+    pub fn set(&mut self, channel: Channel, target: Option<Channel>) {
+        self.targets[usize::from(channel)] = target;
+    }
+
+    /// Returns the target channel of the given channel or `None` if its messages are dropped.
+    pub fn get(&self, channel: Channel) -> Option<Channel> {
+        self.targets[usize::from(channel)]
+    }
+}
+
+/// Moves channel messages to the channel defined by the given map.
+///
+/// Returns `None` if the map drops messages on the channel of the given message. System messages
+/// are passed through unchanged.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{channel, note_on};
+/// use helgoboss_midi::transform::{self, ChannelMap};
+///
+/// let mut map = ChannelMap::new();
+/// map.set(channel(0), Some(channel(9)));
+/// assert_eq!(
+///     transform::remap_channel(&note_on(0, 36, 100), &map),
+///     Some(note_on(9, 36, 100))
+/// );
+/// ```
+pub fn remap_channel(msg: &impl ShortMessage, map: &ChannelMap) -> Option<RawShortMessage> {
+    let channel = match msg.channel() {
+        None => return Some(msg.to_raw()),
+        Some(c) => c,
+    };
+    let target = map.get(channel)?;
+    Some(RawShortMessage::channel_message(
+        msg.r#type(),
+        target,
+        msg.data_byte_1(),
+        msg.data_byte_2(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, controller_number as cn, key_number as kn, u7};
+
+    #[test]
+    fn fixed_velocity_replaces_note_on_velocity() {
+        // Given
+        let msg = RawShortMessage::note_on(ch(2), kn(60), u7(100));
+        // When
+        let result = fixed_velocity(&msg, u7(80));
+        // Then
+        assert_eq!(
+            result,
+            Some(RawShortMessage::note_on(ch(2), kn(60), u7(80)))
+        );
+    }
+
+    #[test]
+    fn fixed_velocity_leaves_note_off_alone() {
+        // Given
+        let note_on_0 = RawShortMessage::note_on(ch(2), kn(60), u7(0));
+        let note_off = RawShortMessage::note_off(ch(2), kn(60), u7(64));
+        // When
+        let result_1 = fixed_velocity(&note_on_0, u7(80));
+        let result_2 = fixed_velocity(&note_off, u7(80));
+        // Then
+        assert_eq!(result_1, Some(note_on_0));
+        assert_eq!(result_2, Some(note_off));
+    }
+
+    #[test]
+    fn fixed_velocity_never_turns_note_on_into_note_off() {
+        // Given
+        let msg = RawShortMessage::note_on(ch(2), kn(60), u7(100));
+        // When
+        let result = fixed_velocity(&msg, u7(0));
+        // Then
+        assert_eq!(result, Some(RawShortMessage::note_on(ch(2), kn(60), u7(1))));
+    }
+
+    #[test]
+    fn fixed_velocity_passes_through_other_messages() {
+        // Given
+        let msg = RawShortMessage::control_change(ch(2), cn(7), u7(100));
+        // When
+        let result = fixed_velocity(&msg, u7(80));
+        // Then
+        assert_eq!(result, Some(msg));
+    }
+
+    #[test]
+    fn linear_velocity_curve_is_identity() {
+        // Given
+        // When
+        // Then
+        for v in 1..=127 {
+            let msg = RawShortMessage::note_on(ch(0), kn(60), u7(v));
+            assert_eq!(velocity_curve(&msg, &VelocityCurve::Linear), Some(msg));
+        }
+    }
+
+    #[test]
+    fn fixed_velocity_curve_affects_note_on_only() {
+        // Given
+        let curve = VelocityCurve::Fixed(u7(90));
+        let note_on = RawShortMessage::note_on(ch(2), kn(60), u7(10));
+        let note_on_0 = RawShortMessage::note_on(ch(2), kn(60), u7(0));
+        let note_off = RawShortMessage::note_off(ch(2), kn(60), u7(10));
+        let poly_pressure = RawShortMessage::polyphonic_key_pressure(ch(2), kn(60), u7(10));
+        // When
+        let result_1 = velocity_curve(&note_on, &curve);
+        let result_2 = velocity_curve(&note_on_0, &curve);
+        let result_3 = velocity_curve(&note_off, &curve);
+        let result_4 = velocity_curve(&poly_pressure, &curve);
+        // Then
+        assert_eq!(
+            result_1,
+            Some(RawShortMessage::note_on(ch(2), kn(60), u7(90)))
+        );
+        assert_eq!(result_2, Some(note_on_0));
+        assert_eq!(result_3, Some(note_off));
+        assert_eq!(result_4, Some(poly_pressure));
+    }
+
+    #[test]
+    fn velocity_curve_never_turns_note_on_into_note_off() {
+        // Given
+        let msg = RawShortMessage::note_on(ch(0), kn(60), u7(1));
+        // When
+        let result = velocity_curve(&msg, &VelocityCurve::Exponential(4.0));
+        // Then
+        assert_eq!(result, Some(msg));
+    }
+
+    #[test]
+    fn remap_channel_moves_and_drops() {
+        // Given
+        let mut map = ChannelMap::new();
+        map.set(ch(0), Some(ch(9)));
+        map.set(ch(5), None);
+        // When
+        let result_1 = remap_channel(&RawShortMessage::note_on(ch(0), kn(60), u7(100)), &map);
+        let result_2 = remap_channel(&RawShortMessage::note_on(ch(5), kn(60), u7(100)), &map);
+        let result_3 = remap_channel(&RawShortMessage::program_change(ch(1), u7(3)), &map);
+        // Then
+        assert_eq!(
+            result_1,
+            Some(RawShortMessage::note_on(ch(9), kn(60), u7(100)))
+        );
+        assert_eq!(result_2, None);
+        assert_eq!(
+            result_3,
+            Some(RawShortMessage::program_change(ch(1), u7(3)))
+        );
+    }
+
+    #[test]
+    fn remap_channel_passes_through_system_messages() {
+        // Given
+        let mut map = ChannelMap::new();
+        map.set(ch(0), None);
+        let msg = RawShortMessage::timing_clock();
+        // When
+        let result = remap_channel(&msg, &map);
+        // Then
+        assert_eq!(result, Some(msg));
+    }
+}
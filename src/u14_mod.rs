@@ -1,12 +1,17 @@
+use crate::U7;
+
 // Basic newtype definition
 newtype! {
     #[doc = r"A 14-bit integer (0 - 16383)."]
     name = U14, repr = u16, max = 16383
 }
 
+// Arithmetic
+impl_arithmetic!(U14);
+
 // From lower newtypes to this newtype
 impl_from_newtype_to_newtype!(crate::U4, U14);
-impl_from_newtype_to_newtype!(crate::U7, U14);
+impl_from_newtype_to_newtype!(U7, U14);
 
 // From lower primitives to this newtype
 impl_from_primitive_to_newtype!(u8, U14);
@@ -36,3 +41,262 @@ impl_try_from_primitive_to_newtype!(i64, U14);
 impl_try_from_primitive_to_newtype!(u128, U14);
 impl_try_from_primitive_to_newtype!(i128, U14);
 impl_try_from_primitive_to_newtype!(usize, U14);
+
+/// An error which can occur when converting a run of 7-bit values to 14-bit values.
+#[derive(Clone, Eq, PartialEq, Debug, derive_more::Display)]
+#[display(fmt = "odd number of 7-bit values, can't be grouped into MSB/LSB pairs")]
+pub struct FromU7PairsError(pub(crate) ());
+
+impl std::error::Error for FromU7PairsError {}
+
+impl U14 {
+    /// Splits this value into its most significant 7 bits (MSB) and its least significant 7 bits
+    /// (LSB).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::{U14, U7};
+    ///
+    /// assert_eq!(U14::new(1057).split(), (U7::new(8), U7::new(33)));
+    /// ```
+    pub fn split(self) -> (U7, U7) {
+        (
+            crate::bit_util::extract_high_7_bit_value_from_14_bit_value(self),
+            crate::bit_util::extract_low_7_bit_value_from_14_bit_value(self),
+        )
+    }
+
+    /// Joins the given most significant 7 bits (MSB) and least significant 7 bits (LSB) to a
+    /// 14-bit value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::{U14, U7};
+    ///
+    /// assert_eq!(U14::join(U7::new(8), U7::new(33)), U14::new(1057));
+    /// ```
+    pub fn join(msb: U7, lsb: U7) -> U14 {
+        crate::bit_util::build_14_bit_value_from_two_7_bit_values(msb, lsb)
+    }
+
+    /// Same as [`join`].
+    ///
+    /// [`join`]: #method.join
+    pub fn from_two_7_bit(msb: U7, lsb: U7) -> U14 {
+        Self::join(msb, lsb)
+    }
+
+    /// Converts a run of 7-bit values which packs 14-bit values in MSB/LSB pairs (MSB first) to
+    /// 14-bit values.
+    ///
+    /// This format is common in System Exclusive parameter dumps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of 7-bit values is odd.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::{U14, U7};
+    ///
+    /// assert_eq!(
+    ///     U14::from_u7_pairs(&[U7::new(3), U7::new(36)]),
+    ///     Ok(vec![U14::new(420)])
+    /// );
+    /// assert!(U14::from_u7_pairs(&[U7::new(3)]).is_err());
+    /// ```
+    pub fn from_u7_pairs(values: &[U7]) -> Result<Vec<U14>, FromU7PairsError> {
+        let pairs = values.chunks_exact(2);
+        if !pairs.remainder().is_empty() {
+            return Err(FromU7PairsError(()));
+        }
+        let result = pairs.map(|pair| U14::join(pair[0], pair[1])).collect();
+        Ok(result)
+    }
+
+    /// Converts the given 14-bit values to a run of 7-bit values in MSB/LSB pairs (MSB first).
+    ///
+    /// This is the reverse of [`from_u7_pairs`].
+    ///
+    /// [`from_u7_pairs`]: #method.from_u7_pairs
+    pub fn to_u7_pairs(values: &[U14]) -> Vec<U7> {
+        let mut result = Vec::with_capacity(values.len() * 2);
+        for v in values {
+            let (msb, lsb) = v.split();
+            result.push(msb);
+            result.push(lsb);
+        }
+        result
+    }
+
+    /// Scales this value down to a 7-bit value, rounding to the nearest one.
+    ///
+    /// The value is mapped linearly from 0 - 16383 to 0 - 127 and rounded half up, so 0 becomes 0,
+    /// 8192 becomes 64 and 16383 becomes 127. In contrast to simply dropping the lower 7 bits,
+    /// this is stable when scaling up via [`U7::scale_to_u14`] and back down again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::U14;
+    ///
+    /// assert_eq!(U14::new(0).scale_to_u7().get(), 0);
+    /// assert_eq!(U14::new(8192).scale_to_u7().get(), 64);
+    /// assert_eq!(U14::new(16383).scale_to_u7().get(), 127);
+    /// ```
+    ///
+    /// [`U7::scale_to_u14`]: struct.U7.html#method.scale_to_u14
+    pub fn scale_to_u7(self) -> U7 {
+        let value = self.0 as u32;
+        U7(((value * 127 + 16383 / 2) / 16383) as u8)
+    }
+}
+
+/// Extracts the most significant 7 bits from the given 14-bit value.
+///
+/// # Example
+///
+/// ```
+/// #![allow(deprecated)]
+/// use helgoboss_midi::{extract_high_7_bit_value_from_14_bit_value, U14, U7};
+///
+/// assert_eq!(extract_high_7_bit_value_from_14_bit_value(U14::new(1057)), U7::new(8));
+/// ```
+#[deprecated(note = "use U14::split")]
+pub fn extract_high_7_bit_value_from_14_bit_value(value: U14) -> U7 {
+    value.split().0
+}
+
+/// Extracts the least significant 7 bits from the given 14-bit value.
+///
+/// # Example
+///
+/// ```
+/// #![allow(deprecated)]
+/// use helgoboss_midi::{extract_low_7_bit_value_from_14_bit_value, U14, U7};
+///
+/// assert_eq!(extract_low_7_bit_value_from_14_bit_value(U14::new(1057)), U7::new(33));
+/// ```
+#[deprecated(note = "use U14::split")]
+pub fn extract_low_7_bit_value_from_14_bit_value(value: U14) -> U7 {
+    value.split().1
+}
+
+/// Builds a 14-bit value from its most significant 7 bits (`high`) and its least significant 7
+/// bits (`low`).
+///
+/// # Example
+///
+/// ```
+/// #![allow(deprecated)]
+/// use helgoboss_midi::{build_14_bit_value_from_two_7_bit_values, U14, U7};
+///
+/// assert_eq!(
+///     build_14_bit_value_from_two_7_bit_values(U7::new(8), U7::new(33)),
+///     U14::new(1057)
+/// );
+/// ```
+#[deprecated(note = "use U14::join")]
+pub fn build_14_bit_value_from_two_7_bit_values(high: U7, low: U7) -> U14 {
+    U14::join(high, low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{u14, u7};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn split_and_join() {
+        // Given
+        // When
+        // Then
+        for v in [0, 1, 127, 128, 1057, 8192, 16383].iter() {
+            let (msb, lsb) = u14(*v).split();
+            assert_eq!(U14::join(msb, lsb), u14(*v));
+        }
+        assert_eq!(U14::MAX.split(), (u7(127), u7(127)));
+        assert_eq!(U14::MIN.split(), (u7(0), u7(0)));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_aliases() {
+        // Given
+        let value = u14(1057);
+        // When
+        // Then
+        assert_eq!(extract_high_7_bit_value_from_14_bit_value(value), u7(8));
+        assert_eq!(extract_low_7_bit_value_from_14_bit_value(value), u7(33));
+        assert_eq!(
+            build_14_bit_value_from_two_7_bit_values(u7(8), u7(33)),
+            value
+        );
+    }
+
+    #[test]
+    fn try_from_u16() {
+        // Given
+        // When
+        // Then
+        assert_eq!(U14::try_from(16383u16), Ok(u14(16383)));
+        assert_eq!(U14::try_from(16384u16), Err(crate::TryFromGreaterError(())));
+        assert_eq!(U14::try_from(u16::MAX), Err(crate::TryFromGreaterError(())));
+    }
+
+    #[test]
+    fn arithmetic() {
+        // Given
+        // When
+        // Then
+        assert_eq!(u14(16383).saturating_add(u14(10)), u14(16383));
+        assert_eq!(u14(8192).saturating_add(u14(8191)), u14(16383));
+        assert_eq!(u14(5).saturating_sub(u14(10)), u14(0));
+        assert_eq!(u14(8192).checked_add(u14(8191)), Some(u14(16383)));
+        assert_eq!(u14(8192).checked_add(u14(8192)), None);
+        assert_eq!(u14(0).checked_sub(u14(1)), None);
+        assert_eq!(u14(8192).checked_sub(u14(192)), Some(u14(8000)));
+        assert_eq!(u14(16383).wrapping_add(u14(2)), u14(1));
+    }
+
+    #[test]
+    fn from_and_to_u7_pairs() {
+        // Given
+        let values = [u7(3), u7(36), u7(127), u7(127), u7(0), u7(1)];
+        // When
+        let result = U14::from_u7_pairs(&values).unwrap();
+        // Then
+        assert_eq!(result, vec![u14(420), u14(16383), u14(1)]);
+        assert_eq!(U14::to_u7_pairs(&result), values.to_vec());
+        assert_eq!(U14::from_u7_pairs(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn scale_to_u7() {
+        // Given
+        // When
+        // Then
+        assert_eq!(u14(0).scale_to_u7(), u7(0));
+        assert_eq!(u14(64).scale_to_u7(), u7(0));
+        assert_eq!(u14(65).scale_to_u7(), u7(1));
+        assert_eq!(u14(8192).scale_to_u7(), u7(64));
+        assert_eq!(u14(16383).scale_to_u7(), u7(127));
+        for v in 0..=127 {
+            assert_eq!(u7(v).scale_to_u14().scale_to_u7(), u7(v));
+        }
+    }
+
+    #[test]
+    fn from_u7_pairs_odd_length() {
+        // Given
+        let values = [u7(3), u7(36), u7(1)];
+        // When
+        let result = U14::from_u7_pairs(&values);
+        // Then
+        assert_eq!(result, Err(FromU7PairsError(())));
+    }
+}
@@ -4,6 +4,9 @@ newtype! {
     name = U7, repr = u8, max = 127
 }
 
+// Arithmetic
+impl_arithmetic!(U7);
+
 // From lower newtypes to this newtype
 impl_from_newtype_to_newtype!(crate::U4, U7);
 
@@ -39,3 +42,99 @@ impl_try_from_primitive_to_newtype!(u128, U7);
 impl_try_from_primitive_to_newtype!(i128, U7);
 impl_try_from_primitive_to_newtype!(usize, U7);
 impl_try_from_primitive_to_newtype!(isize, U7);
+
+impl U7 {
+    /// Scales this value up to a 14-bit value so that the minimum, center and maximum values are
+    /// preserved.
+    ///
+    /// Uses the min-center-max scaling defined by the MIDI 2.0 specification (M2-104-UM, section
+    /// "Data Value Translations"): Values up to the center (64) are simply shifted left by 7 bits.
+    /// For values above the center, the lower 6 bits are additionally replicated into the
+    /// vacated lower bits, so that 127 becomes 16383.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use helgoboss_midi::U7;
+    ///
+    /// assert_eq!(U7::new(0).scale_to_u14().get(), 0);
+    /// assert_eq!(U7::new(64).scale_to_u14().get(), 8192);
+    /// assert_eq!(U7::new(127).scale_to_u14().get(), 16383);
+    /// ```
+    pub fn scale_to_u14(self) -> crate::U14 {
+        const SCALE_BITS: u16 = 7;
+        const REPEAT_BITS: u16 = 6;
+        let value = self.0 as u16;
+        let mut scaled = value << SCALE_BITS;
+        if value <= 64 {
+            return crate::U14(scaled);
+        }
+        let mut repeat_value = (value & ((1 << REPEAT_BITS) - 1)) << (SCALE_BITS - REPEAT_BITS);
+        while repeat_value != 0 {
+            scaled |= repeat_value;
+            repeat_value >>= REPEAT_BITS;
+        }
+        crate::U14(scaled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::u14;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn all() {
+        // Given
+        // When
+        // Then
+        assert_eq!(U7::all().count(), 128);
+        assert!(U7::all().zip(0..).all(|(v, i)| v.get() == i));
+    }
+
+    #[test]
+    fn try_from_u8() {
+        // Given
+        // When
+        // Then
+        assert_eq!(U7::try_from(127u8), Ok(U7(127)));
+        assert_eq!(U7::try_from(0u8), Ok(U7(0)));
+        assert_eq!(U7::try_from(128u8), Err(crate::TryFromGreaterError(())));
+        assert_eq!(U7::try_from(255u8), Err(crate::TryFromGreaterError(())));
+    }
+
+    #[test]
+    fn arithmetic() {
+        // Given
+        // When
+        // Then
+        assert_eq!(U7(127).saturating_add(U7(10)), U7(127));
+        assert_eq!(U7(100).saturating_add(U7(27)), U7(127));
+        assert_eq!(U7(5).saturating_sub(U7(10)), U7(0));
+        assert_eq!(U7(10).saturating_sub(U7(10)), U7(0));
+        assert_eq!(U7(100).checked_add(U7(27)), Some(U7(127)));
+        assert_eq!(U7(100).checked_add(U7(28)), None);
+        assert_eq!(U7(127).checked_add(U7(127)), None);
+        assert_eq!(U7(0).checked_sub(U7(1)), None);
+        assert_eq!(U7(10).checked_sub(U7(3)), Some(U7(7)));
+        assert_eq!(U7(127).wrapping_add(U7(1)), U7(0));
+        assert_eq!(U7(127).wrapping_add(U7(127)), U7(126));
+        assert_eq!(U7(3).wrapping_add(U7(4)), U7(7));
+    }
+
+    #[test]
+    fn scale_to_u14() {
+        // Given
+        // When
+        // Then
+        assert_eq!(U7(0).scale_to_u14(), u14(0));
+        assert_eq!(U7(1).scale_to_u14(), u14(128));
+        assert_eq!(U7(64).scale_to_u14(), u14(8192));
+        assert_eq!(U7(65).scale_to_u14(), u14(8322));
+        assert_eq!(U7(126).scale_to_u14(), u14(16253));
+        assert_eq!(U7(127).scale_to_u14(), u14(16383));
+        let values: Vec<_> = (0..=127).map(|v| U7(v).scale_to_u14()).collect();
+        assert!(values.windows(2).all(|w| w[0] < w[1]));
+    }
+}
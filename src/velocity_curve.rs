@@ -0,0 +1,124 @@
+use crate::U7;
+
+/// A curve for mapping velocities, e.g. in order to adjust the velocity response of a keyboard.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::{VelocityCurve, U7};
+///
+/// let curve = VelocityCurve::Exponential(2.0);
+/// let output = curve.apply(U7::new(64));
+/// assert_eq!(output, U7::new(35));
+/// let inverse = curve.inverse().unwrap();
+/// assert_eq!(inverse.apply(output), U7::new(64));
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum VelocityCurve {
+    /// Leaves the velocity unchanged.
+    Linear,
+    /// Makes the response softer for low velocities and steeper for high velocities.
+    ///
+    /// The factor determines the strength of the curvature. A negative factor has the opposite
+    /// effect. A factor of 0 is equivalent to [`Linear`].
+    ///
+    /// [`Linear`]: #variant.Linear
+    Exponential(f64),
+    /// Makes the response steeper for low velocities and softer for high velocities.
+    ///
+    /// This is the inverse of [`Exponential`] with the same factor.
+    ///
+    /// [`Exponential`]: #variant.Exponential
+    Logarithmic(f64),
+    /// Always returns the given velocity.
+    Fixed(U7),
+}
+
+impl VelocityCurve {
+    /// Applies this curve to the given velocity.
+    pub fn apply(&self, velocity: U7) -> U7 {
+        use VelocityCurve::*;
+        let x = velocity.get() as f64 / 127.0;
+        let y = match *self {
+            Linear => return velocity,
+            Exponential(k) if k.abs() < f64::EPSILON => return velocity,
+            Logarithmic(k) if k.abs() < f64::EPSILON => return velocity,
+            Exponential(k) => ((k * x).exp() - 1.0) / (k.exp() - 1.0),
+            Logarithmic(k) => (1.0 + x * (k.exp() - 1.0)).ln() / k,
+            Fixed(v) => return v,
+        };
+        U7((y * 127.0).round().clamp(0.0, 127.0) as u8)
+    }
+
+    /// Returns the inverse of this curve or `None` if it's not invertible.
+    ///
+    /// Applying the inverse to the output of this curve yields the original velocity again,
+    /// apart from rounding errors.
+    pub fn inverse(&self) -> Option<VelocityCurve> {
+        use VelocityCurve::*;
+        let inverse = match *self {
+            Linear => Linear,
+            Exponential(k) => Logarithmic(k),
+            Logarithmic(k) => Exponential(k),
+            Fixed(_) => return None,
+        };
+        Some(inverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::u7;
+
+    fn assert_round_trips(curve: VelocityCurve, max_deviation: i16) {
+        let inverse = curve.inverse().unwrap();
+        for v in 0..=127 {
+            let result = curve.apply(inverse.apply(u7(v)));
+            let deviation = (result.get() as i16 - v as i16).abs();
+            assert!(
+                deviation <= max_deviation,
+                "{:?}: {} turned into {}",
+                curve,
+                v,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn apply() {
+        // Given
+        // When
+        // Then
+        assert_eq!(VelocityCurve::Linear.apply(u7(64)), u7(64));
+        assert_eq!(VelocityCurve::Exponential(0.0).apply(u7(64)), u7(64));
+        assert_eq!(VelocityCurve::Exponential(2.0).apply(u7(0)), u7(0));
+        assert_eq!(VelocityCurve::Exponential(2.0).apply(u7(127)), u7(127));
+        assert!(VelocityCurve::Exponential(2.0).apply(u7(64)) < u7(64));
+        assert!(VelocityCurve::Logarithmic(2.0).apply(u7(64)) > u7(64));
+        assert_eq!(VelocityCurve::Fixed(u7(100)).apply(u7(5)), u7(100));
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        // Given
+        // When
+        // Then
+        assert_round_trips(VelocityCurve::Linear, 0);
+        assert_round_trips(VelocityCurve::Exponential(1.0), 1);
+        assert_round_trips(VelocityCurve::Exponential(2.0), 1);
+        assert_round_trips(VelocityCurve::Exponential(-2.0), 1);
+        assert_round_trips(VelocityCurve::Logarithmic(2.0), 1);
+    }
+
+    #[test]
+    fn fixed_is_not_invertible() {
+        // Given
+        let curve = VelocityCurve::Fixed(u7(100));
+        // When
+        let inverse = curve.inverse();
+        // Then
+        assert_eq!(inverse, None);
+    }
+}
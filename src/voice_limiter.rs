@@ -0,0 +1,224 @@
+use crate::{Channel, KeyNumber, ShortMessage, ShortMessageFactory, StructuredShortMessage, U7};
+
+/// Decides which note is stolen by [`VoiceLimiter`] if the maximum number of simultaneously held
+/// notes is exceeded.
+///
+/// [`VoiceLimiter`]: struct.VoiceLimiter.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum StealingPolicy {
+    /// Steals the note which has been held for the longest time.
+    Oldest,
+    /// Steals the note with the lowest velocity. If there are several ones, steals the oldest one
+    /// of them.
+    Quietest,
+}
+
+/// Limits the number of simultaneously held notes per channel by stealing notes.
+///
+/// Feed it all messages before passing them on. Whenever a Note On message would exceed the
+/// limit, [`feed`] returns a Note Off message for the stolen note, which should be sent before
+/// the Note On message.
+///
+/// # Example
+///
+/// ```
+/// use helgoboss_midi::test_util::{note_off, note_on};
+/// use helgoboss_midi::{RawShortMessage, StealingPolicy, VoiceLimiter};
+///
+/// let mut limiter = VoiceLimiter::new(1, StealingPolicy::Oldest);
+/// let result_1: Option<RawShortMessage> = limiter.feed(&note_on(0, 60, 100));
+/// let result_2: Option<RawShortMessage> = limiter.feed(&note_on(0, 64, 100));
+/// assert_eq!(result_1, None);
+/// assert_eq!(result_2, Some(note_off(0, 60, 0)));
+/// ```
+///
+/// [`feed`]: #method.feed
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct VoiceLimiter {
+    max_voices: usize,
+    policy: StealingPolicy,
+    held_notes_by_channel: [Vec<HeldNote>; 16],
+}
+
+/// Number of distinct key numbers, which is the maximum number of notes held on one channel.
+const KEY_NUMBER_COUNT: usize = 128;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct HeldNote {
+    key_number: KeyNumber,
+    velocity: U7,
+}
+
+impl VoiceLimiter {
+    /// Creates a limiter which allows at most `max_voices` simultaneously held notes per channel.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `max_voices` is 0.
+    pub fn new(max_voices: usize, policy: StealingPolicy) -> VoiceLimiter {
+        assert!(max_voices > 0, "at least one voice must be allowed");
+        let mut held_notes_by_channel: [Vec<HeldNote>; 16] = Default::default();
+        // Reserve in advance so that feeding doesn't allocate. There can't be more held notes
+        // than key numbers.
+        for notes in held_notes_by_channel.iter_mut() {
+            notes.reserve_exact(max_voices.min(KEY_NUMBER_COUNT));
+        }
+        VoiceLimiter {
+            max_voices,
+            policy,
+            held_notes_by_channel,
+        }
+    }
+
+    /// Feeds the limiter a single short message.
+    ///
+    /// Returns a Note Off message for the stolen note if the given message is a Note On message
+    /// which exceeds the limit.
+    pub fn feed<T: ShortMessageFactory>(&mut self, msg: &impl ShortMessage) -> Option<T> {
+        match msg.to_structured() {
+            StructuredShortMessage::NoteOn {
+                channel,
+                key_number,
+                velocity,
+            } if velocity > U7::MIN => self.process_note_on(channel, key_number, velocity),
+            StructuredShortMessage::NoteOn {
+                channel,
+                key_number,
+                ..
+            }
+            | StructuredShortMessage::NoteOff {
+                channel,
+                key_number,
+                ..
+            } => {
+                self.release(channel, key_number);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the number of notes currently held on the given channel.
+    pub fn held_note_count(&self, channel: Channel) -> usize {
+        self.held_notes_by_channel[usize::from(channel)].len()
+    }
+
+    /// Resets the limiter forgetting all held notes.
+    pub fn reset(&mut self) {
+        for notes in self.held_notes_by_channel.iter_mut() {
+            notes.clear();
+        }
+    }
+
+    fn process_note_on<T: ShortMessageFactory>(
+        &mut self,
+        channel: Channel,
+        key_number: KeyNumber,
+        velocity: U7,
+    ) -> Option<T> {
+        // A retriggered note doesn't occupy an additional voice
+        self.release(channel, key_number);
+        let notes = &mut self.held_notes_by_channel[usize::from(channel)];
+        let stolen = if notes.len() >= self.max_voices {
+            let index = match self.policy {
+                StealingPolicy::Oldest => 0,
+                StealingPolicy::Quietest => notes
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(i, n)| (n.velocity, *i))
+                    .map(|(i, _)| i)
+                    .expect("impossible"),
+            };
+            let stolen_note = notes.remove(index);
+            Some(T::note_off(channel, stolen_note.key_number, U7::MIN))
+        } else {
+            None
+        };
+        notes.push(HeldNote {
+            key_number,
+            velocity,
+        });
+        stolen
+    }
+
+    fn release(&mut self, channel: Channel, key_number: KeyNumber) {
+        self.held_notes_by_channel[usize::from(channel)].retain(|n| n.key_number != key_number);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{channel as ch, key_number as kn, u7};
+    use crate::RawShortMessage;
+
+    #[test]
+    fn should_steal_oldest_note() {
+        // Given
+        let mut limiter = VoiceLimiter::new(2, StealingPolicy::Oldest);
+        // When
+        let result_1: Option<RawShortMessage> =
+            limiter.feed(&RawShortMessage::note_on(ch(0), kn(60), u7(100)));
+        let result_2: Option<RawShortMessage> =
+            limiter.feed(&RawShortMessage::note_on(ch(0), kn(64), u7(20)));
+        let result_3: Option<RawShortMessage> =
+            limiter.feed(&RawShortMessage::note_on(ch(0), kn(67), u7(100)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(
+            result_3,
+            Some(RawShortMessage::note_off(ch(0), kn(60), u7(0)))
+        );
+        assert_eq!(limiter.held_note_count(ch(0)), 2);
+    }
+
+    #[test]
+    fn should_steal_quietest_note() {
+        // Given
+        let mut limiter = VoiceLimiter::new(2, StealingPolicy::Quietest);
+        // When
+        limiter.feed::<RawShortMessage>(&RawShortMessage::note_on(ch(0), kn(60), u7(100)));
+        limiter.feed::<RawShortMessage>(&RawShortMessage::note_on(ch(0), kn(64), u7(20)));
+        let result: Option<RawShortMessage> =
+            limiter.feed(&RawShortMessage::note_on(ch(0), kn(67), u7(100)));
+        // Then
+        assert_eq!(
+            result,
+            Some(RawShortMessage::note_off(ch(0), kn(64), u7(0)))
+        );
+    }
+
+    #[test]
+    fn should_free_voices_on_note_off() {
+        // Given
+        let mut limiter = VoiceLimiter::new(2, StealingPolicy::Oldest);
+        // When
+        limiter.feed::<RawShortMessage>(&RawShortMessage::note_on(ch(0), kn(60), u7(100)));
+        limiter.feed::<RawShortMessage>(&RawShortMessage::note_on(ch(0), kn(64), u7(100)));
+        limiter.feed::<RawShortMessage>(&RawShortMessage::note_off(ch(0), kn(60), u7(0)));
+        limiter.feed::<RawShortMessage>(&RawShortMessage::note_on(ch(0), kn(64), u7(0)));
+        let result_1: Option<RawShortMessage> =
+            limiter.feed(&RawShortMessage::note_on(ch(0), kn(67), u7(100)));
+        let result_2: Option<RawShortMessage> =
+            limiter.feed(&RawShortMessage::note_on(ch(1), kn(67), u7(100)));
+        // Then
+        assert_eq!(result_1, None);
+        assert_eq!(result_2, None);
+        assert_eq!(limiter.held_note_count(ch(0)), 1);
+        assert_eq!(limiter.held_note_count(ch(1)), 1);
+    }
+
+    #[test]
+    fn should_accept_unbounded_max_voices() {
+        // Given
+        let mut limiter = VoiceLimiter::new(usize::MAX, StealingPolicy::Oldest);
+        // When
+        let results: Vec<Option<RawShortMessage>> = (0..128)
+            .map(|k| limiter.feed(&RawShortMessage::note_on(ch(0), kn(k), u7(100))))
+            .collect();
+        // Then
+        assert!(results.iter().all(|r| r.is_none()));
+        assert_eq!(limiter.held_note_count(ch(0)), 128);
+    }
+}
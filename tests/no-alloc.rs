@@ -0,0 +1,72 @@
+//! Verifies that decoding doesn't allocate on the heap.
+//!
+//! The crate doesn't support `no_std` yet, but the decode path is supposed to be usable in
+//! real-time threads which must not allocate. The global allocator of this test binary counts
+//! allocations per thread, so allocations made by the test harness in other threads don't count.
+use helgoboss_midi::test_util::{control_change, note_on};
+use helgoboss_midi::{
+    HighResEvent, HighResScanner, ParameterNumberMessageScanner, RawShortMessage,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATION_COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATION_COUNT.try_with(|c| c.set(c.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocation_count() -> usize {
+    ALLOCATION_COUNT.with(|c| c.get())
+}
+
+fn nrpn_sequence() -> [RawShortMessage; 6] {
+    [
+        note_on(0, 60, 100),
+        control_change(0, 99, 3),
+        control_change(0, 98, 37),
+        control_change(0, 38, 65),
+        control_change(0, 6, 2),
+        control_change(0, 96, 0),
+    ]
+}
+
+#[test]
+fn no_alloc_decoding() {
+    // Given
+    let msgs = nrpn_sequence();
+    let mut pn_scanner = ParameterNumberMessageScanner::new();
+    let mut high_res_scanner = HighResScanner::new();
+    let count_before = allocation_count();
+    // When
+    let mut pn_results = 0;
+    let mut high_res_results = 0;
+    for msg in msgs.iter() {
+        if pn_scanner.feed(msg).is_some() {
+            pn_results += 1;
+        }
+        if let Some(HighResEvent::ParameterNumber(_)) = high_res_scanner.feed(msg) {
+            high_res_results += 1;
+        }
+    }
+    let batch = pn_scanner.feed_batch::<4>(&msgs);
+    // Then
+    assert_eq!(allocation_count(), count_before);
+    assert_eq!(pn_results, 2);
+    assert_eq!(high_res_results, 2);
+    assert_eq!(batch.iter().flatten().count(), 2);
+}